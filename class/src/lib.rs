@@ -1,27 +1,172 @@
 #![deny(warnings)]
-pub use enumflags2::BitFlags;
+pub use enumflags2::{BitFlag, BitFlags};
+use std::collections::HashMap;
+
+// The pointer the model shares strings and name-and-type entries through. The `sync` feature switches it to Arc so a
+// parsed class is Send and Sync.
+#[cfg(not(feature = "sync"))]
+pub use std::rc::Rc as Shared;
+#[cfg(feature = "sync")]
+pub use std::sync::Arc as Shared;
 
 pub mod const_pool {
-    use std::rc::Rc;
+    use std::fmt;
+    use crate::Shared;
+    use std::ops::Deref;
+
+    // A CONSTANT_Utf8 string. A class file can spell a string other than the canonical modified UTF-8 way, e.g. with
+    // overlong sequences, or hold a lone surrogate that decodes to U+FFFD; such a string keeps the bytes it was read
+    // from, which are written back as long as the string is not replaced. serde only carries the string.
+    #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(from = "String", into = "String"))]
+    pub struct Utf8Info {
+        value: Shared<String>,
+        raw: Option<Shared<[u8]>>
+    }
+
+    impl Utf8Info {
+        pub fn from_raw(value: String, raw: Vec<u8>) -> Utf8Info {
+            Utf8Info { value: Shared::new(value), raw: Some(raw.into()) }
+        }
+
+        #[inline(always)]
+        pub fn raw(&self) -> Option<&[u8]> {
+            self.raw.as_deref()
+        }
+
+        #[inline(always)]
+        pub fn ptr_eq(this: &Utf8Info, other: &Utf8Info) -> bool {
+            Shared::ptr_eq(&this.value, &other.value)
+        }
+    }
+
+    impl Deref for Utf8Info {
+        type Target = String;
+
+        #[inline(always)]
+        fn deref(&self) -> &String {
+            &self.value
+        }
+    }
+
+    impl From<String> for Utf8Info {
+        fn from(value: String) -> Utf8Info {
+            Utf8Info { value: Shared::new(value), raw: None }
+        }
+    }
+
+    impl From<&str> for Utf8Info {
+        fn from(value: &str) -> Utf8Info {
+            Utf8Info::from(value.to_string())
+        }
+    }
+
+    impl From<&Utf8Info> for Utf8Info {
+        #[inline(always)]
+        fn from(value: &Utf8Info) -> Utf8Info {
+            value.clone()
+        }
+    }
+
+    impl From<Utf8Info> for String {
+        fn from(value: Utf8Info) -> String {
+            value.value.to_string()
+        }
+    }
+
+    impl fmt::Debug for Utf8Info {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(self.value.as_str(), f)
+        }
+    }
+
+    impl fmt::Display for Utf8Info {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(&self.value)
+        }
+    }
 
-    pub type Utf8Info = Rc<String>;
     pub type FloatInfo = f32;
     pub type DoubleInfo = f64;
     pub type IntInfo = i32;
     pub type LongInfo = i64;
     pub type StringInfo = Utf8Info;
 
-    #[derive(Clone, Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ClassInfo(pub Utf8Info);
 
-    #[derive(Clone, Debug)]
+    // An array class is named by its descriptor, e.g. `[Ljava/lang/String;`; the other names follow Class.getName.
+    impl ClassInfo {
+        pub fn from_binary_name(name: &str) -> ClassInfo {
+            ClassInfo(Utf8Info::from(name.replace('.', "/")))
+        }
+
+        #[inline(always)]
+        pub fn internal_name(&self) -> &str {
+            &self.0
+        }
+
+        pub fn binary_name(&self) -> String {
+            self.0.replace('/', ".")
+        }
+
+        #[inline(always)]
+        pub fn is_array(&self) -> bool {
+            self.0.starts_with('[')
+        }
+
+        pub fn descriptor(&self) -> String {
+            if self.is_array() { self.0.to_string() } else { format!("L{};", self.0) }
+        }
+
+        // The innermost element type, as a descriptor for primitives; e.g. `java/lang/String` for `[[Ljava/lang/String;`.
+        pub fn element_name(&self) -> &str {
+            let element = self.0.trim_start_matches('[');
+            match element.strip_prefix('L') {
+                Some(name) if self.is_array() => name.strip_suffix(';').unwrap_or(name),
+                _ => element
+            }
+        }
+
+        // Arrays belong to the package of their element type; primitive arrays and the unnamed package give "".
+        pub fn package_name(&self) -> &str {
+            let element = self.element_name();
+            element.rfind('/').map_or("", |end| &element[..end])
+        }
+
+        // Based on the name alone: nested classes are split at the last `$`, which a top-level class may also contain.
+        pub fn simple_name(&self) -> String {
+            let element = self.element_name();
+            let name = match element {
+                "B" if self.is_array() => "byte",
+                "C" if self.is_array() => "char",
+                "D" if self.is_array() => "double",
+                "F" if self.is_array() => "float",
+                "I" if self.is_array() => "int",
+                "J" if self.is_array() => "long",
+                "S" if self.is_array() => "short",
+                "Z" if self.is_array() => "boolean",
+                element => {
+                    let name = &element[element.rfind('/').map_or(0, |end| end + 1)..];
+                    &name[name.rfind('$').map_or(0, |end| end + 1)..]
+                }
+            };
+            let dimensions = self.0.len() - self.0.trim_start_matches('[').len();
+            format!("{name}{}", "[]".repeat(dimensions))
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct NameAndTypeInfoStruct {
         pub name: Utf8Info,
         pub descriptor: Utf8Info
     }
-    pub type NameAndTypeInfo = Rc<NameAndTypeInfoStruct>;
+    pub type NameAndTypeInfo = Shared<NameAndTypeInfoStruct>;
 
-    #[derive(Clone, Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ComponentRef {
         pub class: ClassInfo,
         pub name_and_type: NameAndTypeInfo
@@ -30,7 +175,36 @@ pub mod const_pool {
     pub type MethodRefInfo = ComponentRef;
     pub type InterfaceMethodRefInfo = ComponentRef;
 
-    #[derive(Clone, Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct MethodTypeInfo(pub Utf8Info);
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleInfo(pub Utf8Info);
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PackageInfo(pub Utf8Info);
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct BootstrapRef {
+        pub bootstrap_method_attr_index: u16,
+        pub name_and_type: NameAndTypeInfo
+    }
+    pub type DynamicInfo = BootstrapRef;
+    pub type InvokeDynamicInfo = BootstrapRef;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct MethodHandleInfo {
+        pub reference_kind: u8,
+        pub reference: ComponentRef
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ConstPoolType {
         Utf8(Utf8Info),
         Float(FloatInfo),
@@ -42,29 +216,119 @@ pub mod const_pool {
         NameAndType(NameAndTypeInfo),
         Field(FieldRefInfo),
         MethodRef(MethodRefInfo),
-        InterfaceMethodRef(InterfaceMethodRefInfo)
+        InterfaceMethodRef(InterfaceMethodRefInfo),
+        MethodHandle(MethodHandleInfo),
+        MethodType(MethodTypeInfo),
+        Dynamic(DynamicInfo),
+        InvokeDynamic(InvokeDynamicInfo),
+        Module(ModuleInfo),
+        Package(PackageInfo),
+        Placeholder
+    }
+
+    impl ConstPoolType {
+        pub fn as_component_ref(&self) -> Option<&ComponentRef> {
+            match self {
+                ConstPoolType::Field(component)
+                | ConstPoolType::MethodRef(component)
+                | ConstPoolType::InterfaceMethodRef(component) => Some(component),
+                _ => None
+            }
+        }
+    }
+
+    impl fmt::Display for ComponentRef {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}.{}:{}", self.class.0, self.name_and_type.name, self.name_and_type.descriptor)
+        }
+    }
+
+    // Follows the tag names javap uses for the constant pool listing, with references resolved in place.
+    impl fmt::Display for ConstPoolType {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ConstPoolType::Utf8(value) => write!(f, "Utf8 {value:?}"),
+                ConstPoolType::Float(value) => write!(f, "Float {value:?}f"),
+                ConstPoolType::Double(value) => write!(f, "Double {value:?}d"),
+                ConstPoolType::Int(value) => write!(f, "Integer {value}"),
+                ConstPoolType::Long(value) => write!(f, "Long {value}l"),
+                ConstPoolType::String(value) => write!(f, "String {value:?}"),
+                ConstPoolType::Class(class) => write!(f, "Class {}", class.0),
+                ConstPoolType::NameAndType(name_and_type) =>
+                    write!(f, "NameAndType {}:{}", name_and_type.name, name_and_type.descriptor),
+                ConstPoolType::Field(field) => write!(f, "Fieldref {field}"),
+                ConstPoolType::MethodRef(method) => write!(f, "Methodref {method}"),
+                ConstPoolType::InterfaceMethodRef(method) => write!(f, "InterfaceMethodref {method}"),
+                ConstPoolType::MethodHandle(handle) => write!(f, "MethodHandle {}:{}", handle.reference_kind, handle.reference),
+                ConstPoolType::MethodType(method_type) => write!(f, "MethodType {}", method_type.0),
+                ConstPoolType::Dynamic(dynamic) => write!(f, "Dynamic #{}:{}:{}", dynamic.bootstrap_method_attr_index,
+                    dynamic.name_and_type.name, dynamic.name_and_type.descriptor),
+                ConstPoolType::InvokeDynamic(dynamic) => write!(f, "InvokeDynamic #{}:{}:{}", dynamic.bootstrap_method_attr_index,
+                    dynamic.name_and_type.name, dynamic.name_and_type.descriptor),
+                ConstPoolType::Module(module) => write!(f, "Module {}", module.0),
+                ConstPoolType::Package(package) => write!(f, "Package {}", package.0),
+                ConstPoolType::Placeholder => write!(f, "<unusable>")
+            }
+        }
     }
 }
 
 pub mod attributes {
+    use std::any::Any;
+    use std::fmt;
     use enumflags2::{bitflags, BitFlags};
     use crate::const_pool;
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ConstValueType {
         Float(const_pool::FloatInfo),
         Double(const_pool::DoubleInfo),
         Int(const_pool::IntInfo),
         Long(const_pool::LongInfo),
+        Short(i16),
+        Byte(i8),
+        Boolean(bool),
+        Char(u16),
         String(const_pool::StringInfo)
     }
 
-    #[derive(Debug)]
+    impl ConstValueType {
+        // None when the descriptor is not an int-like type or the value does not fit it.
+        pub fn from_int(value: const_pool::IntInfo, descriptor: &str) -> Option<ConstValueType> {
+            match descriptor {
+                "I" => Some(ConstValueType::Int(value)),
+                "S" => i16::try_from(value).ok().map(ConstValueType::Short),
+                "B" => i8::try_from(value).ok().map(ConstValueType::Byte),
+                "Z" => matches!(value, 0 | 1).then_some(ConstValueType::Boolean(value == 1)),
+                "C" => u16::try_from(value).ok().map(ConstValueType::Char),
+                _ => None
+            }
+        }
+
+        pub fn matches_descriptor(&self, descriptor: &str) -> bool {
+            match self {
+                ConstValueType::Float(_) => descriptor == "F",
+                ConstValueType::Double(_) => descriptor == "D",
+                ConstValueType::Int(_) => matches!(descriptor, "I" | "S" | "C" | "B" | "Z"),
+                ConstValueType::Long(_) => descriptor == "J",
+                ConstValueType::Short(_) => descriptor == "S",
+                ConstValueType::Byte(_) => descriptor == "B",
+                ConstValueType::Boolean(_) => descriptor == "Z",
+                ConstValueType::Char(_) => descriptor == "C",
+                ConstValueType::String(_) => descriptor == "Ljava/lang/String;"
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ConstantValueAttribute {
         pub value: ConstValueType
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ExceptionEntry {
         pub start_pc: u16,
         pub end_pc: u16,
@@ -72,14 +336,20 @@ pub mod attributes {
         pub catch_type: Option<const_pool::ClassInfo>
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum CodeAttributes {
         LineNumberTable(LineNumberTableAttribute),
         LocalVariableTable(LocalVariableTableAttribute),
+        LocalVariableTypeTable(LocalVariableTypeTableAttribute),
+        StackMapTable(StackMapTableAttribute),
+        RuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotationsAttribute),
+        RuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotationsAttribute),
         Unknown(UnknownAttribute)
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct CodeAttribute {
         pub max_stack: u16,
         pub max_local: u16,
@@ -88,7 +358,47 @@ pub mod attributes {
         pub attributes: Vec<CodeAttributes>
     }
 
-    #[derive(Debug)]
+    impl CodeAttribute {
+        pub fn line_number_table(&self) -> Option<&LineNumberTableAttribute> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                CodeAttributes::LineNumberTable(table) => Some(table),
+                _ => None
+            })
+        }
+
+        pub fn local_variable_table(&self) -> Option<&LocalVariableTableAttribute> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                CodeAttributes::LocalVariableTable(table) => Some(table),
+                _ => None
+            })
+        }
+
+        pub fn local_variable_type_table(&self) -> Option<&LocalVariableTypeTableAttribute> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                CodeAttributes::LocalVariableTypeTable(table) => Some(table),
+                _ => None
+            })
+        }
+
+        pub fn stack_map_table(&self) -> Option<&StackMapTableAttribute> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                CodeAttributes::StackMapTable(table) => Some(table),
+                _ => None
+            })
+        }
+
+        // Entries are searched in table order as the JVM does; a catch_type of None is a finally handler.
+        pub fn handler_for(&self, pc: u16) -> Option<&ExceptionEntry> {
+            self.exceptions.iter().find(|entry| entry.start_pc <= pc && pc < entry.end_pc)
+        }
+
+        pub fn source_line_for_pc(&self, pc: u16) -> Option<u16> {
+            self.line_number_table()?.line_for(pc)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ExceptionsAttribute {
         pub exceptions_classes: Vec<const_pool::ClassInfo>
     }
@@ -103,11 +413,15 @@ pub mod attributes {
         Protected = 0x0004,
         Static = 0x0008,
         Final = 0x0010,
-        Interface = 0x0040,
-        Abstract = 0x0080
+        Interface = 0x0200,
+        Abstract = 0x0400,
+        Synthetic = 0x1000,
+        Annotation = 0x2000,
+        Enum = 0x4000
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ClassEntry {
         pub inner_class_info: Option<const_pool::ClassInfo>,
         pub outer_class_info: Option<const_pool::ClassInfo>,
@@ -115,28 +429,291 @@ pub mod attributes {
         pub access: BitFlags<ClassAccessSpecifier>
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct InnerClassesAttribute {
         pub classes: Vec<ClassEntry>
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct SourceFileAttribute {
         pub file: const_pool::Utf8Info
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PermittedSubclassesAttribute {
+        pub classes: Vec<const_pool::ClassInfo>
+    }
+
+    #[bitflags]
+    #[repr(u16)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum ModuleAccess {
+        Open = 0x0020,
+        Synthetic = 0x1000,
+        Mandated = 0x8000
+    }
+
+    #[bitflags]
+    #[repr(u16)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum RequiresAccess {
+        Transitive = 0x0020,
+        StaticPhase = 0x0040,
+        Synthetic = 0x1000,
+        Mandated = 0x8000
+    }
+
+    // Shared by the exports and opens tables.
+    #[bitflags]
+    #[repr(u16)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum ExportsAccess {
+        Synthetic = 0x1000,
+        Mandated = 0x8000
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleRequires {
+        pub module: const_pool::ModuleInfo,
+        pub access: BitFlags<RequiresAccess>,
+        pub version: Option<const_pool::Utf8Info>
+    }
+
+    // An empty `to` list makes the package exported or opened to every module.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleExports {
+        pub package: const_pool::PackageInfo,
+        pub access: BitFlags<ExportsAccess>,
+        pub to: Vec<const_pool::ModuleInfo>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleProvides {
+        pub service: const_pool::ClassInfo,
+        pub implementations: Vec<const_pool::ClassInfo>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleAttribute {
+        pub name: const_pool::ModuleInfo,
+        pub access: BitFlags<ModuleAccess>,
+        pub version: Option<const_pool::Utf8Info>,
+        pub requires: Vec<ModuleRequires>,
+        pub exports: Vec<ModuleExports>,
+        pub opens: Vec<ModuleExports>,
+        pub uses: Vec<const_pool::ClassInfo>,
+        pub provides: Vec<ModuleProvides>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModulePackagesAttribute {
+        pub packages: Vec<const_pool::PackageInfo>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModuleMainClassAttribute {
+        pub main_class: const_pool::ClassInfo
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct NestHostAttribute {
+        pub host_class: const_pool::ClassInfo
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct NestMembersAttribute {
+        pub classes: Vec<const_pool::ClassInfo>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RecordComponentInfo {
+        pub name: const_pool::Utf8Info,
+        pub descriptor: const_pool::Utf8Info,
+        pub attributes: Vec<Attribute>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RecordAttribute {
+        pub components: Vec<RecordComponentInfo>
+    }
+
+    // Static arguments are loadable constants, kept as the constant pool entries they refer to.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct BootstrapMethod {
+        pub method: const_pool::MethodHandleInfo,
+        pub arguments: Vec<const_pool::ConstPoolType>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct BootstrapMethodsAttribute {
+        pub methods: Vec<BootstrapMethod>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct SignatureAttribute {
+        pub signature: const_pool::Utf8Info
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum ElementValue {
+        Const(ConstValueType),
+        Enum {
+            type_name: const_pool::Utf8Info,
+            const_name: const_pool::Utf8Info
+        },
+        Class(const_pool::Utf8Info),
+        Annotation(Annotation),
+        Array(Vec<ElementValue>)
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Annotation {
+        pub type_descriptor: const_pool::Utf8Info,
+        pub elements: Vec<(const_pool::Utf8Info, ElementValue)>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct AnnotationDefaultAttribute {
+        pub value: ElementValue
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeVisibleAnnotationsAttribute {
+        pub annotations: Vec<Annotation>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeInvisibleAnnotationsAttribute {
+        pub annotations: Vec<Annotation>
+    }
+
+    // One annotation list per parameter; javac may record fewer parameters than the descriptor declares.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeVisibleParameterAnnotationsAttribute {
+        pub parameters: Vec<Vec<Annotation>>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeInvisibleParameterAnnotationsAttribute {
+        pub parameters: Vec<Vec<Annotation>>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LocalVariableTarget {
+        pub start_pc: u16,
+        pub length: u16,
+        pub index: u16
+    }
+
+    // The target_info union; which variant applies is fixed by the target_type of the annotation.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum TargetInfo {
+        TypeParameter {
+            index: u8
+        },
+        Supertype {
+            index: u16
+        },
+        TypeParameterBound {
+            type_parameter_index: u8,
+            bound_index: u8
+        },
+        Empty,
+        FormalParameter {
+            index: u8
+        },
+        Throws {
+            index: u16
+        },
+        LocalVariable(Vec<LocalVariableTarget>),
+        Catch {
+            exception_table_index: u16
+        },
+        Offset(u16),
+        TypeArgument {
+            offset: u16,
+            type_argument_index: u8
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct TypePathEntry {
+        pub kind: u8,
+        pub type_argument_index: u8
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct TypeAnnotation {
+        pub target_type: u8,
+        pub target_info: TargetInfo,
+        pub target_path: Vec<TypePathEntry>,
+        pub annotation: Annotation
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeVisibleTypeAnnotationsAttribute {
+        pub annotations: Vec<TypeAnnotation>
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RuntimeInvisibleTypeAnnotationsAttribute {
+        pub annotations: Vec<TypeAnnotation>
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LineNumberEntry {
         pub start_pc: u16,
         pub line: u16
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LineNumberTableAttribute {
         pub lines: Vec<LineNumberEntry>
     }
 
-    #[derive(Debug)]
+    impl LineNumberTableAttribute {
+        pub fn line_for(&self, pc: u16) -> Option<u16> {
+            // The table is not required to be sorted by start_pc.
+            self.lines.iter()
+                .filter(|entry| entry.start_pc <= pc)
+                .max_by_key(|entry| entry.start_pc)
+                .map(|entry| entry.line)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LocalVariableEntry {
         pub start_pc: u16,
         pub length: u16,
@@ -145,25 +722,197 @@ pub mod attributes {
         pub index: u16
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LocalVariableTableAttribute {
         pub variables: Vec<LocalVariableEntry>
     }
 
-    #[derive(Debug)]
+    impl LocalVariableTableAttribute {
+        pub fn variable_at(&self, pc: u16, slot: u16) -> Option<&LocalVariableEntry> {
+            self.variables.iter().find(|variable| variable.index == slot
+                && variable.start_pc <= pc && (pc as u32) < variable.start_pc as u32 + variable.length as u32)
+        }
+    }
+
+    // Only variables whose type uses a type variable or parameterized type get an entry here.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LocalVariableTypeEntry {
+        pub start_pc: u16,
+        pub length: u16,
+        pub name: const_pool::Utf8Info,
+        pub signature: const_pool::Utf8Info,
+        pub index: u16
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LocalVariableTypeTableAttribute {
+        pub variables: Vec<LocalVariableTypeEntry>
+    }
+
+    impl LocalVariableTypeTableAttribute {
+        pub fn variable_at(&self, pc: u16, slot: u16) -> Option<&LocalVariableTypeEntry> {
+            self.variables.iter().find(|variable| variable.index == slot
+                && variable.start_pc <= pc && (pc as u32) < variable.start_pc as u32 + variable.length as u32)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum VerificationType {
+        Top,
+        Integer,
+        Float,
+        Double,
+        Long,
+        Null,
+        UninitializedThis,
+        Object(const_pool::ClassInfo),
+        // The offset of the `new` instruction that created the object.
+        Uninitialized(u16)
+    }
+
+    // The extended variants keep frames read with tags 251 and 247 apart so they are written back with the same
+    // tag; Same and SameLocals1StackItem only fall back to the extended tag when their offset_delta needs it.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum StackMapFrame {
+        Same {
+            offset_delta: u16
+        },
+        SameExtended {
+            offset_delta: u16
+        },
+        SameLocals1StackItem {
+            offset_delta: u16,
+            stack: VerificationType
+        },
+        SameLocals1StackItemExtended {
+            offset_delta: u16,
+            stack: VerificationType
+        },
+        Chop {
+            offset_delta: u16,
+            count: u8
+        },
+        Append {
+            offset_delta: u16,
+            locals: Vec<VerificationType>
+        },
+        Full {
+            offset_delta: u16,
+            locals: Vec<VerificationType>,
+            stack: Vec<VerificationType>
+        }
+    }
+
+    impl StackMapFrame {
+        pub fn offset_delta(&self) -> u16 {
+            match self {
+                StackMapFrame::Same { offset_delta }
+                | StackMapFrame::SameExtended { offset_delta }
+                | StackMapFrame::SameLocals1StackItem { offset_delta, .. }
+                | StackMapFrame::SameLocals1StackItemExtended { offset_delta, .. }
+                | StackMapFrame::Chop { offset_delta, .. }
+                | StackMapFrame::Append { offset_delta, .. }
+                | StackMapFrame::Full { offset_delta, .. } => *offset_delta
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct StackMapTableAttribute {
+        pub frames: Vec<StackMapFrame>
+    }
+
+    impl StackMapTableAttribute {
+        // The first frame's delta is its offset; every later one is relative to the previous frame plus one.
+        pub fn offsets(&self) -> Vec<u32> {
+            self.frames.iter()
+                .scan(None, |previous: &mut Option<u32>, frame| {
+                    let delta = frame.offset_delta() as u32;
+                    let offset = previous.map_or(delta, |previous| previous + delta + 1);
+                    *previous = Some(offset);
+                    Some(offset)
+                })
+                .collect()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeprecatedAttribute {
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct UnknownAttribute {
-        pub size: u32
+        pub name: const_pool::Utf8Info,
+        pub size: u32,
+        pub data: Vec<u8>
     }
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct SyntheticAttribute {
     }
 
-    #[derive(Debug)]
+    // Send and Sync under the `sync` feature, so a custom value does not keep a class from crossing threads.
+    #[cfg(not(feature = "sync"))]
+    pub trait ThreadSafety {}
+    #[cfg(not(feature = "sync"))]
+    impl<T> ThreadSafety for T {}
+    #[cfg(feature = "sync")]
+    pub trait ThreadSafety: Send + Sync {}
+    #[cfg(feature = "sync")]
+    impl<T: Send + Sync> ThreadSafety for T {}
+
+    // What a custom attribute handler decodes. Every Clone type is one, which keeps a class holding it Clone; the
+    // value is read back through `downcast_ref`.
+    pub trait CustomValue: Any + ThreadSafety {
+        fn clone_value(&self) -> CustomAttribute;
+        fn as_any(&self) -> &dyn Any;
+    }
+
+    impl<T: Any + Clone + ThreadSafety> CustomValue for T {
+        #[inline(always)]
+        fn clone_value(&self) -> CustomAttribute {
+            Box::new(self.clone())
+        }
+
+        #[inline(always)]
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    impl dyn CustomValue {
+        #[inline(always)]
+        pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+            self.as_any().downcast_ref()
+        }
+    }
+
+    pub type CustomAttribute = Box<dyn CustomValue>;
+
+    impl Clone for CustomAttribute {
+        #[inline(always)]
+        fn clone(&self) -> CustomAttribute {
+            (**self).clone_value()
+        }
+    }
+
+    impl fmt::Debug for dyn CustomValue {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("CustomValue")
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Attribute {
         Code(CodeAttribute),
         Exceptions(ExceptionsAttribute),
@@ -174,15 +923,124 @@ pub mod attributes {
         Deprecated(DeprecatedAttribute),
         ConstantValue(ConstantValueAttribute),
         Synthetic(SyntheticAttribute),
+        Signature(SignatureAttribute),
+        RuntimeVisibleAnnotations(RuntimeVisibleAnnotationsAttribute),
+        RuntimeInvisibleAnnotations(RuntimeInvisibleAnnotationsAttribute),
+        RuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotationsAttribute),
+        RuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotationsAttribute),
+        RuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotationsAttribute),
+        RuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotationsAttribute),
+        AnnotationDefault(AnnotationDefaultAttribute),
+        PermittedSubclasses(PermittedSubclassesAttribute),
+        NestHost(NestHostAttribute),
+        NestMembers(NestMembersAttribute),
+        Module(ModuleAttribute),
+        ModulePackages(ModulePackagesAttribute),
+        ModuleMainClass(ModuleMainClassAttribute),
+        Record(RecordAttribute),
+        BootstrapMethods(BootstrapMethodsAttribute),
+        // Keeps the name and bytes it was read from, which are written back; the decoded value is not.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        Custom {
+            name: const_pool::Utf8Info,
+            data: Vec<u8>,
+            value: CustomAttribute
+        },
         Unknown(UnknownAttribute)
     }
 
+    // The value of a custom attribute is opaque, so custom attributes are equal when their names and bytes are.
+    impl PartialEq for Attribute {
+        fn eq(&self, other: &Attribute) -> bool {
+            match (self, other) {
+                (Attribute::Code(left), Attribute::Code(right)) => left == right,
+                (Attribute::Exceptions(left), Attribute::Exceptions(right)) => left == right,
+                (Attribute::InnerClasses(left), Attribute::InnerClasses(right)) => left == right,
+                (Attribute::SourceFile(left), Attribute::SourceFile(right)) => left == right,
+                (Attribute::LineNumberTable(left), Attribute::LineNumberTable(right)) => left == right,
+                (Attribute::LocalVariableTable(left), Attribute::LocalVariableTable(right)) => left == right,
+                (Attribute::Deprecated(left), Attribute::Deprecated(right)) => left == right,
+                (Attribute::ConstantValue(left), Attribute::ConstantValue(right)) => left == right,
+                (Attribute::Synthetic(left), Attribute::Synthetic(right)) => left == right,
+                (Attribute::Signature(left), Attribute::Signature(right)) => left == right,
+                (Attribute::RuntimeVisibleAnnotations(left), Attribute::RuntimeVisibleAnnotations(right)) => left == right,
+                (Attribute::RuntimeInvisibleAnnotations(left), Attribute::RuntimeInvisibleAnnotations(right)) => left == right,
+                (Attribute::RuntimeVisibleParameterAnnotations(left), Attribute::RuntimeVisibleParameterAnnotations(right)) => left == right,
+                (Attribute::RuntimeInvisibleParameterAnnotations(left), Attribute::RuntimeInvisibleParameterAnnotations(right)) => left == right,
+                (Attribute::RuntimeVisibleTypeAnnotations(left), Attribute::RuntimeVisibleTypeAnnotations(right)) => left == right,
+                (Attribute::RuntimeInvisibleTypeAnnotations(left), Attribute::RuntimeInvisibleTypeAnnotations(right)) => left == right,
+                (Attribute::AnnotationDefault(left), Attribute::AnnotationDefault(right)) => left == right,
+                (Attribute::PermittedSubclasses(left), Attribute::PermittedSubclasses(right)) => left == right,
+                (Attribute::NestHost(left), Attribute::NestHost(right)) => left == right,
+                (Attribute::NestMembers(left), Attribute::NestMembers(right)) => left == right,
+                (Attribute::Module(left), Attribute::Module(right)) => left == right,
+                (Attribute::ModulePackages(left), Attribute::ModulePackages(right)) => left == right,
+                (Attribute::ModuleMainClass(left), Attribute::ModuleMainClass(right)) => left == right,
+                (Attribute::Record(left), Attribute::Record(right)) => left == right,
+                (Attribute::BootstrapMethods(left), Attribute::BootstrapMethods(right)) => left == right,
+                (Attribute::Custom { name, data, .. }, Attribute::Custom { name: other_name, data: other_data, .. }) =>
+                    name == other_name && data == other_data,
+                (Attribute::Unknown(left), Attribute::Unknown(right)) => left == right,
+                _ => false
+            }
+        }
+    }
+
+    impl Attribute {
+        // The name the attribute is stored under in a class file.
+        pub fn name(&self) -> &str {
+            match self {
+                Attribute::Code(_) => "Code",
+                Attribute::Exceptions(_) => "Exceptions",
+                Attribute::InnerClasses(_) => "InnerClasses",
+                Attribute::SourceFile(_) => "SourceFile",
+                Attribute::LineNumberTable(_) => "LineNumberTable",
+                Attribute::LocalVariableTable(_) => "LocalVariableTable",
+                Attribute::Deprecated(_) => "Deprecated",
+                Attribute::ConstantValue(_) => "ConstantValue",
+                Attribute::Synthetic(_) => "Synthetic",
+                Attribute::Signature(_) => "Signature",
+                Attribute::RuntimeVisibleAnnotations(_) => "RuntimeVisibleAnnotations",
+                Attribute::RuntimeInvisibleAnnotations(_) => "RuntimeInvisibleAnnotations",
+                Attribute::RuntimeVisibleParameterAnnotations(_) => "RuntimeVisibleParameterAnnotations",
+                Attribute::RuntimeInvisibleParameterAnnotations(_) => "RuntimeInvisibleParameterAnnotations",
+                Attribute::RuntimeVisibleTypeAnnotations(_) => "RuntimeVisibleTypeAnnotations",
+                Attribute::RuntimeInvisibleTypeAnnotations(_) => "RuntimeInvisibleTypeAnnotations",
+                Attribute::AnnotationDefault(_) => "AnnotationDefault",
+                Attribute::PermittedSubclasses(_) => "PermittedSubclasses",
+                Attribute::NestHost(_) => "NestHost",
+                Attribute::NestMembers(_) => "NestMembers",
+                Attribute::Module(_) => "Module",
+                Attribute::ModulePackages(_) => "ModulePackages",
+                Attribute::ModuleMainClass(_) => "ModuleMainClass",
+                Attribute::Record(_) => "Record",
+                Attribute::BootstrapMethods(_) => "BootstrapMethods",
+                Attribute::Custom { name, .. } => name.as_str(),
+                Attribute::Unknown(unknown) => unknown.name.as_str()
+            }
+        }
+    }
+
+    impl CodeAttributes {
+        pub fn name(&self) -> &str {
+            match self {
+                CodeAttributes::LineNumberTable(_) => "LineNumberTable",
+                CodeAttributes::LocalVariableTable(_) => "LocalVariableTable",
+                CodeAttributes::LocalVariableTypeTable(_) => "LocalVariableTypeTable",
+                CodeAttributes::StackMapTable(_) => "StackMapTable",
+                CodeAttributes::RuntimeVisibleTypeAnnotations(_) => "RuntimeVisibleTypeAnnotations",
+                CodeAttributes::RuntimeInvisibleTypeAnnotations(_) => "RuntimeInvisibleTypeAnnotations",
+                CodeAttributes::Unknown(unknown) => unknown.name.as_str()
+            }
+        }
+    }
+
 }
 
 pub mod components {
-    use enumflags2::{bitflags, BitFlags};
+    use enumflags2::{bitflags, BitFlag, BitFlags};
     use crate::const_pool;
-    use crate::attributes::Attribute;
+    use crate::attributes::{Annotation, Attribute, CodeAttribute};
 
     #[bitflags]
     #[repr(u16)]
@@ -195,27 +1053,123 @@ pub mod components {
         Static = 0x0008,
         Final = 0x0010,
         Volatile = 0x0040,
-        Transient = 0x0080
+        Transient = 0x0080,
+        Synthetic = 0x1000,
+        Enum = 0x4000
+    }
+
+    #[bitflags]
+    #[repr(u16)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum MethodAccessSpecifier
+    {
+        Public = 0x0001,
+        Private = 0x0002,
+        Protected = 0x0004,
+        Static = 0x0008,
+        Final = 0x0010,
+        Synchronized = 0x0020,
+        Bridge = 0x0040,
+        Varargs = 0x0080,
+        Native = 0x0100,
+        Abstract = 0x0400,
+        Strict = 0x0800,
+        Synthetic = 0x1000
     }
 
-    #[derive(Debug)]
-    pub struct ComponentInfo {
-        pub access: BitFlags<AccessSpecifier>,
+    // Fields and methods share the ACC_SYNTHETIC bit.
+    const ACC_SYNTHETIC: u16 = 0x1000;
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(bound(serialize = "BitFlags<Access>: serde::Serialize",
+        deserialize = "BitFlags<Access>: serde::Deserialize<'de>")))]
+    pub struct ComponentInfo<Access: BitFlag = AccessSpecifier> {
+        pub access: BitFlags<Access>,
         pub name: const_pool::Utf8Info,
         pub descriptor: const_pool::Utf8Info,
         pub attributes: Vec<Attribute>
     }
 
+    impl<Access: BitFlag<Numeric = u16>> ComponentInfo<Access> {
+        pub fn code(&self) -> Option<&CodeAttribute> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Code(code) => Some(code),
+                _ => None
+            })
+        }
+
+        pub fn code_size(&self) -> Option<usize> {
+            self.code().map(|code| code.code.len())
+        }
+
+        pub fn is_synthetic(&self) -> bool {
+            self.access.bits() & ACC_SYNTHETIC != 0
+                || self.attributes.iter().any(|attribute| matches!(attribute, Attribute::Synthetic(_)))
+        }
+
+        pub fn is_deprecated(&self) -> bool {
+            self.attributes.iter().any(|attribute| matches!(attribute, Attribute::Deprecated(_)))
+        }
+
+        pub fn signature(&self) -> Option<&str> {
+            self.attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Signature(signature) => Some(signature.signature.as_str()),
+                _ => None
+            })
+        }
+
+        pub fn is_lambda_body(&self) -> bool {
+            self.name.starts_with("lambda$") && self.is_synthetic()
+        }
+    }
+
+    impl ComponentInfo<MethodAccessSpecifier> {
+        // Visible and invisible annotations of each parameter, merged by parameter position.
+        pub fn parameter_annotations(&self) -> Vec<Vec<&Annotation>> {
+            let mut parameters: Vec<Vec<&Annotation>> = vec![];
+            for attribute in &self.attributes {
+                let annotated = match attribute {
+                    Attribute::RuntimeVisibleParameterAnnotations(visible) => &visible.parameters,
+                    Attribute::RuntimeInvisibleParameterAnnotations(invisible) => &invisible.parameters,
+                    _ => continue
+                };
+                if parameters.len() < annotated.len() {
+                    parameters.resize(annotated.len(), vec![]);
+                }
+                for (position, annotations) in annotated.iter().enumerate() {
+                    parameters[position].extend(annotations);
+                }
+            }
+            parameters
+        }
+    }
+
     pub type Interface = const_pool::ClassInfo;
-    pub type FieldInfo = ComponentInfo;
-    pub type MethodInfo = ComponentInfo;
+    pub type FieldInfo = ComponentInfo<AccessSpecifier>;
+    pub type MethodInfo = ComponentInfo<MethodAccessSpecifier>;
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ClassVersion {
         pub minor: u16,
         pub major: u16
     }
 
+    impl ClassVersion {
+        // Feature release number: 45 covers Java 1.0 and 1.1, and every major after it is one release.
+        pub fn java_version(&self) -> Option<u8> {
+            if self.major < 45 {
+                return None;
+            }
+            u8::try_from(self.major - 44).ok()
+        }
+
+        pub fn is_preview(&self) -> bool {
+            self.minor == 0xFFFF
+        }
+    }
+
     #[bitflags]
     #[repr(u16)]
     #[derive(Copy, Clone, Debug, PartialEq)]
@@ -224,11 +1178,16 @@ pub mod components {
         Final = 0x0010,
         Super = 0x0020,
         Interface = 0x0200,
-        Abstract = 0x0400
+        Abstract = 0x0400,
+        Synthetic = 0x1000,
+        Annotation = 0x2000,
+        Enum = 0x4000,
+        Module = 0x8000
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Class {
     pub version: components::ClassVersion,
     pub const_pool: Vec<const_pool::ConstPoolType>,
@@ -241,4 +1200,364 @@ pub struct Class {
     pub attributes: Vec<attributes::Attribute>
 }
 
+impl Class {
+    pub fn method_refs_by_owner(&self) -> HashMap<String, Vec<(String, String)>> {
+        let mut owners: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for entry in &self.const_pool {
+            if let const_pool::ConstPoolType::MethodRef(component)
+                | const_pool::ConstPoolType::InterfaceMethodRef(component) = entry {
+                owners.entry(component.class.0.to_string())
+                    .or_default()
+                    .push((component.name_and_type.name.to_string(), component.name_and_type.descriptor.to_string()));
+            }
+        }
+        owners
+    }
+
+    pub fn is_synthetic_lambda_class(&self) -> bool {
+        self.this_class.0.contains("$$Lambda")
+            && (self.access.contains(components::ClassAccess::Synthetic)
+                || self.attributes.iter().any(|attribute| matches!(attribute, attributes::Attribute::Synthetic(_))))
+    }
+
+    pub fn source_file(&self) -> Option<&str> {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            attributes::Attribute::SourceFile(source) => Some(source.file.as_str()),
+            _ => None
+        })
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.attributes.iter().any(|attribute| matches!(attribute, attributes::Attribute::Deprecated(_)))
+    }
+
+    pub fn signature(&self) -> Option<&str> {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            attributes::Attribute::Signature(signature) => Some(signature.signature.as_str()),
+            _ => None
+        })
+    }
+
+    pub fn module(&self) -> Option<&attributes::ModuleAttribute> {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            attributes::Attribute::Module(module) => Some(module),
+            _ => None
+        })
+    }
+
+    // A class without a NestHost attribute is the host of its own nest.
+    pub fn nest_host(&self) -> &str {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            attributes::Attribute::NestHost(host) => Some(host.host_class.0.as_str()),
+            _ => None
+        }).unwrap_or(self.this_class.0.as_str())
+    }
+
+    // Resolves the bootstrap_method_attr_index of a Dynamic or InvokeDynamic entry.
+    pub fn bootstrap_method(&self, index: u16) -> Option<&attributes::BootstrapMethod> {
+        self.attributes.iter().find_map(|attribute| match attribute {
+            attributes::Attribute::BootstrapMethods(bootstrap) => bootstrap.methods.get(index as usize),
+            _ => None
+        })
+    }
+
+    pub fn largest_method(&self) -> Option<(&str, usize)> {
+        self.methods.iter()
+            .filter_map(|method| Some((method.name.as_str(), method.code_size()?)))
+            .max_by_key(|(_, size)| *size)
+    }
+
+    pub fn clone_with_renamed_class(&self, new_internal_name: &str) -> Class {
+        let renamer = ClassRenamer {
+            old_name: self.this_class.0.as_str(),
+            new_name: const_pool::Utf8Info::from(new_internal_name)
+        };
+        let mut class = self.clone();
+        class.const_pool.iter_mut().for_each(|entry| renamer.const_pool_entry(entry));
+        class.this_class = renamer.class_info(&class.this_class);
+        class.fields.iter_mut().for_each(|field| renamer.component(field));
+        class.methods.iter_mut().for_each(|method| renamer.component(method));
+        class.attributes.iter_mut().for_each(|attribute| renamer.attribute(attribute));
+        class
+    }
+
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&components::MethodInfo> {
+        self.methods.iter().find(|method| method.name.as_str() == name && method.descriptor.as_str() == descriptor)
+    }
+
+    pub fn find_field(&self, name: &str) -> Option<&components::FieldInfo> {
+        self.fields.iter().find(|field| field.name.as_str() == name)
+    }
+
+    pub fn methods_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a components::MethodInfo> + 'a {
+        self.methods.iter().filter(move |method| method.name.as_str() == name)
+    }
+
+    // Like find_method, but also matches the signature polymorphic methods of MethodHandle and VarHandle
+    // (`invokeExact`, `get`, ...), which accept any descriptor at a call site (JVMS 5.4.3.3).
+    pub fn resolve_method(&self, name: &str, descriptor: &str) -> Option<&components::MethodInfo> {
+        if let Some(method) = self.find_method(name, descriptor) {
+            return Some(method);
+        }
+        if !matches!(self.this_class.0.as_str(), "java/lang/invoke/MethodHandle" | "java/lang/invoke/VarHandle") {
+            return None;
+        }
+        let mut candidates = self.methods.iter().filter(|method| method.name.as_str() == name);
+        let method = candidates.next()?;
+        let polymorphic = candidates.next().is_none()
+            && method.descriptor.starts_with("([Ljava/lang/Object;)")
+            && method.access.contains(components::MethodAccessSpecifier::Varargs | components::MethodAccessSpecifier::Native);
+        polymorphic.then_some(method)
+    }
+
+    // Indices are 1-based as in the class file; the unusable slot after a long or double yields None.
+    pub fn const_pool_entry(&self, index: u16) -> Option<&const_pool::ConstPoolType> {
+        match self.const_pool.get(index.checked_sub(1)? as usize)? {
+            const_pool::ConstPoolType::Placeholder => None,
+            entry => Some(entry)
+        }
+    }
+
+    pub fn utf8_at(&self, index: u16) -> Option<&str> {
+        match self.const_pool_entry(index)? {
+            const_pool::ConstPoolType::Utf8(value) => Some(value.as_str()),
+            _ => None
+        }
+    }
+
+    pub fn class_name_at(&self, index: u16) -> Option<&str> {
+        match self.const_pool_entry(index)? {
+            const_pool::ConstPoolType::Class(class) => Some(class.0.as_str()),
+            _ => None
+        }
+    }
 
+    pub fn name_and_type_at(&self, index: u16) -> Option<&const_pool::NameAndTypeInfoStruct> {
+        match self.const_pool_entry(index)? {
+            const_pool::ConstPoolType::NameAndType(name_and_type) => Some(name_and_type),
+            _ => None
+        }
+    }
+}
+
+struct ClassRenamer<'a> {
+    old_name: &'a str,
+    new_name: const_pool::Utf8Info
+}
+
+impl ClassRenamer<'_> {
+    fn name(&self, name: &const_pool::Utf8Info) -> const_pool::Utf8Info {
+        if name.as_str() == self.old_name {
+            self.new_name.clone()
+        } else {
+            self.signature(name)
+        }
+    }
+
+    fn descriptor(&self, descriptor: &const_pool::Utf8Info) -> const_pool::Utf8Info {
+        let old_reference = format!("L{};", self.old_name);
+        if descriptor.contains(&old_reference) {
+            const_pool::Utf8Info::from(descriptor.replace(&old_reference, &format!("L{};", self.new_name)))
+        } else {
+            descriptor.clone()
+        }
+    }
+
+    // Signatures write the class as `L{old}<…>;` or `L{old}<…>.Inner;`, so a reference ends at `;`, `<` or `.`.
+    fn signature(&self, signature: &const_pool::Utf8Info) -> const_pool::Utf8Info {
+        let old_reference = format!("L{}", self.old_name);
+        let mut renamed = String::with_capacity(signature.len());
+        let mut rest = signature.as_str();
+        let mut changed = false;
+        while let Some(start) = rest.find(&old_reference) {
+            let end = start + old_reference.len();
+            renamed.push_str(&rest[..start]);
+            if matches!(rest[end..].chars().next(), Some(';' | '<' | '.')) {
+                renamed.push('L');
+                renamed.push_str(&self.new_name);
+                changed = true;
+            } else {
+                renamed.push_str(&rest[start..end]);
+            }
+            rest = &rest[end..];
+        }
+        if changed {
+            renamed.push_str(rest);
+            const_pool::Utf8Info::from(renamed)
+        } else {
+            signature.clone()
+        }
+    }
+
+    fn class_info(&self, class: &const_pool::ClassInfo) -> const_pool::ClassInfo {
+        const_pool::ClassInfo(self.name(&class.0))
+    }
+
+    fn name_and_type(&self, name_and_type: &const_pool::NameAndTypeInfo) -> const_pool::NameAndTypeInfo {
+        Shared::new(const_pool::NameAndTypeInfoStruct {
+            name: name_and_type.name.clone(),
+            descriptor: self.descriptor(&name_and_type.descriptor)
+        })
+    }
+
+    fn component_ref(&self, component: &const_pool::ComponentRef) -> const_pool::ComponentRef {
+        const_pool::ComponentRef {
+            class: self.class_info(&component.class),
+            name_and_type: self.name_and_type(&component.name_and_type)
+        }
+    }
+
+    fn bootstrap_ref(&self, dynamic: &const_pool::BootstrapRef) -> const_pool::BootstrapRef {
+        const_pool::BootstrapRef {
+            bootstrap_method_attr_index: dynamic.bootstrap_method_attr_index,
+            name_and_type: self.name_and_type(&dynamic.name_and_type)
+        }
+    }
+
+    fn const_pool_entry(&self, entry: &mut const_pool::ConstPoolType) {
+        use const_pool::ConstPoolType;
+        *entry = match entry {
+            ConstPoolType::Utf8(utf8) => ConstPoolType::Utf8(self.name(utf8)),
+            ConstPoolType::Class(class) => ConstPoolType::Class(self.class_info(class)),
+            ConstPoolType::NameAndType(name_and_type) => ConstPoolType::NameAndType(self.name_and_type(name_and_type)),
+            ConstPoolType::Field(component) => ConstPoolType::Field(self.component_ref(component)),
+            ConstPoolType::MethodRef(component) => ConstPoolType::MethodRef(self.component_ref(component)),
+            ConstPoolType::InterfaceMethodRef(component) => ConstPoolType::InterfaceMethodRef(self.component_ref(component)),
+            ConstPoolType::MethodHandle(handle) => ConstPoolType::MethodHandle(const_pool::MethodHandleInfo {
+                reference_kind: handle.reference_kind,
+                reference: self.component_ref(&handle.reference)
+            }),
+            ConstPoolType::MethodType(method_type) => ConstPoolType::MethodType(const_pool::MethodTypeInfo(self.descriptor(&method_type.0))),
+            ConstPoolType::Dynamic(dynamic) => ConstPoolType::Dynamic(self.bootstrap_ref(dynamic)),
+            ConstPoolType::InvokeDynamic(dynamic) => ConstPoolType::InvokeDynamic(self.bootstrap_ref(dynamic)),
+            _ => return
+        };
+    }
+
+    fn component<Access: BitFlag>(&self, component: &mut components::ComponentInfo<Access>) {
+        component.descriptor = self.descriptor(&component.descriptor);
+        component.attributes.iter_mut().for_each(|attribute| self.attribute(attribute));
+    }
+
+    fn local_variables(&self, table: &mut attributes::LocalVariableTableAttribute) {
+        for variable in &mut table.variables {
+            variable.descriptor = self.descriptor(&variable.descriptor);
+        }
+    }
+
+    fn stack_map_frame(&self, frame: &mut attributes::StackMapFrame) {
+        use attributes::{StackMapFrame, VerificationType};
+        let types: Vec<&mut VerificationType> = match frame {
+            StackMapFrame::SameLocals1StackItem { stack, .. } | StackMapFrame::SameLocals1StackItemExtended { stack, .. } => vec![stack],
+            StackMapFrame::Append { locals, .. } => locals.iter_mut().collect(),
+            StackMapFrame::Full { locals, stack, .. } => locals.iter_mut().chain(stack.iter_mut()).collect(),
+            StackMapFrame::Same { .. } | StackMapFrame::SameExtended { .. } | StackMapFrame::Chop { .. } => vec![]
+        };
+        for verification_type in types {
+            if let VerificationType::Object(class) = verification_type {
+                *class = self.class_info(class);
+            }
+        }
+    }
+
+    fn annotation(&self, annotation: &mut attributes::Annotation) {
+        annotation.type_descriptor = self.descriptor(&annotation.type_descriptor);
+        annotation.elements.iter_mut().for_each(|(_, value)| self.element_value(value));
+    }
+
+    fn element_value(&self, value: &mut attributes::ElementValue) {
+        use attributes::ElementValue;
+        match value {
+            ElementValue::Enum { type_name, .. } => *type_name = self.descriptor(type_name),
+            ElementValue::Class(class) => *class = self.descriptor(class),
+            ElementValue::Annotation(annotation) => self.annotation(annotation),
+            ElementValue::Array(values) => values.iter_mut().for_each(|value| self.element_value(value)),
+            ElementValue::Const(_) => {}
+        }
+    }
+
+    fn attribute(&self, attribute: &mut attributes::Attribute) {
+        use attributes::{Attribute, CodeAttributes};
+        match attribute {
+            Attribute::Code(code) => {
+                for exception in &mut code.exceptions {
+                    exception.catch_type = exception.catch_type.as_ref().map(|class| self.class_info(class));
+                }
+                for code_attribute in &mut code.attributes {
+                    match code_attribute {
+                        CodeAttributes::LocalVariableTable(table) => self.local_variables(table),
+                        CodeAttributes::LocalVariableTypeTable(table) => for variable in &mut table.variables {
+                            variable.signature = self.signature(&variable.signature);
+                        },
+                        CodeAttributes::StackMapTable(table) => table.frames.iter_mut().for_each(|frame| self.stack_map_frame(frame)),
+                        CodeAttributes::RuntimeVisibleTypeAnnotations(visible) =>
+                            visible.annotations.iter_mut().for_each(|annotation| self.annotation(&mut annotation.annotation)),
+                        CodeAttributes::RuntimeInvisibleTypeAnnotations(invisible) =>
+                            invisible.annotations.iter_mut().for_each(|annotation| self.annotation(&mut annotation.annotation)),
+                        _ => {}
+                    }
+                }
+            }
+            Attribute::Exceptions(exceptions) => {
+                exceptions.exceptions_classes = exceptions.exceptions_classes.iter()
+                    .map(|class| self.class_info(class))
+                    .collect();
+            }
+            Attribute::InnerClasses(inner_classes) => {
+                for entry in &mut inner_classes.classes {
+                    entry.inner_class_info = entry.inner_class_info.as_ref().map(|class| self.class_info(class));
+                    entry.outer_class_info = entry.outer_class_info.as_ref().map(|class| self.class_info(class));
+                }
+            }
+            Attribute::LocalVariableTable(table) => self.local_variables(table),
+            Attribute::PermittedSubclasses(permitted) => {
+                permitted.classes = permitted.classes.iter()
+                    .map(|class| self.class_info(class))
+                    .collect();
+            }
+            Attribute::NestHost(host) => host.host_class = self.class_info(&host.host_class),
+            Attribute::NestMembers(members) => {
+                members.classes = members.classes.iter()
+                    .map(|class| self.class_info(class))
+                    .collect();
+            }
+            Attribute::Module(module) => {
+                module.uses = module.uses.iter()
+                    .map(|class| self.class_info(class))
+                    .collect();
+                for provides in &mut module.provides {
+                    provides.service = self.class_info(&provides.service);
+                    provides.implementations = provides.implementations.iter()
+                        .map(|class| self.class_info(class))
+                        .collect();
+                }
+            }
+            Attribute::ModuleMainClass(main) => main.main_class = self.class_info(&main.main_class),
+            Attribute::Record(record) => {
+                for component in &mut record.components {
+                    component.descriptor = self.descriptor(&component.descriptor);
+                    component.attributes.iter_mut().for_each(|attribute| self.attribute(attribute));
+                }
+            }
+            Attribute::AnnotationDefault(default) => self.element_value(&mut default.value),
+            Attribute::BootstrapMethods(bootstrap) => {
+                for method in &mut bootstrap.methods {
+                    method.method.reference = self.component_ref(&method.method.reference);
+                    method.arguments.iter_mut().for_each(|argument| self.const_pool_entry(argument));
+                }
+            }
+            Attribute::Signature(signature) => signature.signature = self.signature(&signature.signature),
+            Attribute::RuntimeVisibleAnnotations(visible) => visible.annotations.iter_mut().for_each(|annotation| self.annotation(annotation)),
+            Attribute::RuntimeInvisibleAnnotations(invisible) => invisible.annotations.iter_mut().for_each(|annotation| self.annotation(annotation)),
+            Attribute::RuntimeVisibleParameterAnnotations(visible) =>
+                visible.parameters.iter_mut().flatten().for_each(|annotation| self.annotation(annotation)),
+            Attribute::RuntimeInvisibleParameterAnnotations(invisible) =>
+                invisible.parameters.iter_mut().flatten().for_each(|annotation| self.annotation(annotation)),
+            Attribute::RuntimeVisibleTypeAnnotations(visible) =>
+                visible.annotations.iter_mut().for_each(|annotation| self.annotation(&mut annotation.annotation)),
+            Attribute::RuntimeInvisibleTypeAnnotations(invisible) =>
+                invisible.annotations.iter_mut().for_each(|annotation| self.annotation(&mut annotation.annotation)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use class_parser::deserialization::from_reader;
+use std::fs::File;
+
+// Usage: cargo run --example class_to_json -- resources/HelloWorld.class
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "resources/HelloWorld.class".to_string());
+    let file = File::open(&path).unwrap_or_else(|error| panic!("Unable to open {path}: {error}"));
+    let class = from_reader(file).unwrap_or_else(|error| panic!("Unable to parse {path}: {error}"));
+    println!("{}", serde_json::to_string_pretty(&class).unwrap());
+}
@@ -8,714 +8,6743 @@ pub enum DeserializationError {
     Parsing(String),
     #[error("Link element index error.")]
     Link,
+    #[error("Reference class index `{0}` is invalid.")]
+    RefClassIndex(u16),
+    #[error("Reference name and type index `{0}` is invalid.")]
+    RefNameAndTypeIndex(u16),
     #[error("UTF-8 string parsing.")]
     Encoding(#[from] std::string::FromUtf8Error),
+    #[error("At byte offset {offset}: {source}")]
+    AtOffset {
+        offset: u64,
+        source: Box<DeserializationError>
+    },
+    #[error("Declared {what} of {declared} exceeds the limit of {limit}.")]
+    LimitExceeded {
+        what: &'static str,
+        declared: u64,
+        limit: u64
+    },
+    #[error("Duplicate `{attribute}` attribute on {owner}.")]
+    DuplicateAttribute {
+        attribute: String,
+        owner: String
+    },
+    #[cfg(feature = "zip")]
+    #[error("Archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
 }
 
-mod proxy {
-    use std::rc::Rc;
-    use class::const_pool::{ConstPoolType, NameAndTypeInfoStruct, Utf8Info, ComponentRef, ClassInfo};
-    use super::*;
-
-    #[derive(Debug, Copy, Clone)]
-    pub struct Proxy(pub u16);
-
-
-    #[derive(Debug, Copy, Clone)]
-    pub struct DoubleProxy {
-        pub class: ProxyToProxyClass,
-        pub name_and_type: ProxyToProxyNameAndType,
+impl DeserializationError {
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            DeserializationError::AtOffset { offset, .. } => Some(*offset),
+            _ => None
+        }
     }
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct ProxyToProxyNameAndType(pub u16);
-
-    #[derive(Debug, Copy, Clone)]
-    pub struct ProxyToProxyClass(pub u16);
-
-    #[derive(Debug, Copy, Clone)]
-    pub struct NameAndTypeProxy {
-        pub name: Proxy,
-        pub descriptor: Proxy,
+    pub fn into_inner(self) -> DeserializationError {
+        match self {
+            DeserializationError::AtOffset { source, .. } => *source,
+            error => error
+        }
     }
+}
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct FieldRefProxy(pub DoubleProxy);
+#[derive(Error, Debug)]
+pub enum SerializationError {
+    #[error("Data stream error")]
+    CannotWrite(#[from] std::io::Error),
+    #[error("Constant pool exceeds 65535 entries.")]
+    ConstPoolOverflow,
+    #[error("Unable to serialize data: `{0}`")]
+    Unsupported(String),
+    #[error("Unable to read the input class: {0}")]
+    Input(#[from] DeserializationError),
+}
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct MethodRefProxy(pub DoubleProxy);
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum DescriptorError {
+    #[error("Descriptor `{0}` ends unexpectedly.")]
+    Incomplete(String),
+    #[error("Unexpected character `{1}` at {2} in descriptor `{0}`.")]
+    UnexpectedChar(String, char, usize),
+    #[error("Descriptor `{0}` has trailing characters.")]
+    Trailing(String),
+}
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct InterfaceMethodRefProxy(pub DoubleProxy);
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    #[error("Class `{0}` has no matching constant pool entry.")]
+    MissingClassEntry(String),
+    #[error("Constant pool entry `{index}` has an invalid descriptor: {error}")]
+    InvalidDescriptor {
+        index: u16,
+        error: DescriptorError
+    },
+    #[error("Method `{method}` declares {max_local} locals but its parameters need {required}.")]
+    MaxLocalsTooSmall {
+        method: String,
+        max_local: u16,
+        required: u16
+    },
+    #[error("Exception handler `{entry}` of method `{method}` lies outside the code.")]
+    HandlerOutOfRange {
+        method: String,
+        entry: usize
+    },
+    #[error("Constant pool entry `{index}` must refer to a {expected} entry but refers to `{target}`.")]
+    WrongReference {
+        index: u16,
+        target: u16,
+        expected: &'static str
+    },
+    #[error("Unable to read the constant pool: {0}")]
+    Unreadable(String),
+    #[error("Attribute `{attribute}` is not allowed on {owner}.")]
+    MisplacedAttribute {
+        attribute: String,
+        owner: String
+    },
+    #[error("Access flags of {owner} are illegal: {reason}.")]
+    IllegalAccessFlags {
+        owner: String,
+        reason: &'static str
+    },
+}
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct StringProxy(pub Proxy);
+pub mod mutf8 {
+    use super::DeserializationError;
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct ClassProxy(pub Proxy);
+    #[inline(always)]
+    fn continuation(bytes: &[u8], index: usize) -> Result<u16, DeserializationError> {
+        match bytes.get(index) {
+            Some(byte) if byte & 0xC0 == 0x80 => Ok((byte & 0x3F) as u16),
+            _ => Err(DeserializationError::Parsing(format!("Invalid modified UTF-8 continuation byte at {index}")))
+        }
+    }
 
+    pub fn decode_modified_utf8(bytes: &[u8]) -> Result<String, DeserializationError> {
+        let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            let byte = bytes[index];
+            match byte {
+                0x01..=0x7F => {
+                    units.push(byte as u16);
+                    index += 1;
+                }
+                0xC0..=0xDF => {
+                    units.push(((byte & 0x1F) as u16) << 6 | continuation(bytes, index + 1)?);
+                    index += 2;
+                }
+                0xE0..=0xEF => {
+                    units.push(((byte & 0x0F) as u16) << 12
+                        | continuation(bytes, index + 1)? << 6
+                        | continuation(bytes, index + 2)?);
+                    index += 3;
+                }
+                unexpected => return Err(DeserializationError::Parsing(
+                    format!("Invalid modified UTF-8 byte {unexpected:#04x} at {index}")))
+            }
+        }
+        // javac writes a lone surrogate from a string literal as is; it has no Rust counterpart and becomes U+FFFD.
+        Ok(char::decode_utf16(units).map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER)).collect())
+    }
 
-    pub enum ProxyConstPoolType {
-        Value(ConstPoolType),
-        NameAndType(NameAndTypeProxy),
-        FieldRef(FieldRefProxy),
-        MethodRef(MethodRefProxy),
-        InterfaceMethodRef(InterfaceMethodRefProxy),
-        String(StringProxy),
-        Class(ClassProxy),
+    pub fn encode_modified_utf8(s: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(s.len());
+        for unit in s.encode_utf16() {
+            match unit {
+                0x01..=0x7F => bytes.push(unit as u8),
+                0x00 | 0x80..=0x7FF => {
+                    bytes.push(0xC0 | (unit >> 6) as u8);
+                    bytes.push(0x80 | (unit & 0x3F) as u8);
+                }
+                _ => {
+                    bytes.push(0xE0 | (unit >> 12) as u8);
+                    bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                    bytes.push(0x80 | (unit & 0x3F) as u8);
+                }
+            }
+        }
+        bytes
     }
+}
 
-    pub trait ResolveProxy: Sized {
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError>;
+pub mod descriptor {
+    use super::DescriptorError;
+    use std::fmt;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum FieldType {
+        Byte,
+        Char,
+        Double,
+        Float,
+        Int,
+        Long,
+        Short,
+        Boolean,
+        Object(String),
+        Array(Box<FieldType>)
     }
 
-    impl ResolveProxy for ConstPoolType {
-        fn resolve(&self, _: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(self.clone())
-        }
+    // A return_type of None stands for void.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MethodDescriptor {
+        pub parameters: Vec<FieldType>,
+        pub return_type: Option<FieldType>
     }
 
-    #[inline(always)]
-    fn resolve_simple_proxy(proxy: &Proxy, pool: &[ProxyConstPoolType]) -> Result<Utf8Info, DeserializationError> {
-        if let ProxyConstPoolType::Value(ConstPoolType::Utf8(utf8)) =
-            pool.get((proxy.0) as usize).ok_or(DeserializationError::Link)? {
-            return Ok(utf8.clone());
-        }
-        Err(DeserializationError::Link)
+    // The kind of value a method returns, matching the return instruction it uses; boolean, byte, char and short
+    // are returned as int.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ReturnKind {
+        Void,
+        Int,
+        Long,
+        Float,
+        Double,
+        Reference
     }
 
-    impl ResolveProxy for StringProxy {
+    impl FieldType {
+        // Local variable and operand stack slots taken by a value of this type.
         #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(ConstPoolType::String(resolve_simple_proxy(&self.0, pool)?))
+        pub fn slot_size(&self) -> u16 {
+            match self {
+                FieldType::Long | FieldType::Double => 2,
+                _ => 1
+            }
         }
     }
 
-    impl ResolveProxy for ClassProxy {
+    impl MethodDescriptor {
         #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            if let ProxyConstPoolType::Value(ConstPoolType::Utf8(utf8)) =
-                pool.get(self.0.0 as usize).ok_or(DeserializationError::Link)? {
-                return Ok(ConstPoolType::Class(ClassInfo(utf8.clone())));
-            }
-            Err(DeserializationError::Link)
+        pub fn parameter_count(&self) -> usize {
+            self.parameters.len()
         }
-    }
 
-    impl ResolveProxy for NameAndTypeProxy {
+        // Slots taken by the arguments, not counting the receiver of an instance method.
         #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(ConstPoolType::NameAndType(Rc::new(NameAndTypeInfoStruct {
-                name: resolve_simple_proxy(&self.name, pool)?,
-                descriptor: resolve_simple_proxy(&self.descriptor, pool)?,
-            })))
+        pub fn argument_slot_size(&self) -> u16 {
+            self.parameters.iter().map(FieldType::slot_size).sum()
         }
-    }
 
-    #[inline(always)]
-    fn resolve_double_proxy(proxy: &DoubleProxy, pool: &[ProxyConstPoolType]) -> Result<ComponentRef, DeserializationError> {
-        let class = if let ConstPoolType::Class(class) = proxy.class.resolve(pool)? {
-            Ok(class)
-        } else { Err(DeserializationError::Link) }?;
-        let name_and_type = if let ConstPoolType::NameAndType(name_and_type) = proxy.name_and_type.resolve(pool)? {
-            Ok(name_and_type)
-        } else { Err(DeserializationError::Link) }?;
-        Ok(ComponentRef {
-            class,
-            name_and_type,
-        })
+        pub fn return_kind(&self) -> ReturnKind {
+            match &self.return_type {
+                None => ReturnKind::Void,
+                Some(FieldType::Long) => ReturnKind::Long,
+                Some(FieldType::Float) => ReturnKind::Float,
+                Some(FieldType::Double) => ReturnKind::Double,
+                Some(FieldType::Object(_) | FieldType::Array(_)) => ReturnKind::Reference,
+                Some(_) => ReturnKind::Int
+            }
+        }
     }
 
-    impl ResolveProxy for FieldRefProxy {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(ConstPoolType::Field(resolve_double_proxy(&self.0, pool)?))
+    // Writes the type back in descriptor syntax, e.g. `[Ljava/lang/String;`.
+    impl fmt::Display for FieldType {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                FieldType::Byte => write!(f, "B"),
+                FieldType::Char => write!(f, "C"),
+                FieldType::Double => write!(f, "D"),
+                FieldType::Float => write!(f, "F"),
+                FieldType::Int => write!(f, "I"),
+                FieldType::Long => write!(f, "J"),
+                FieldType::Short => write!(f, "S"),
+                FieldType::Boolean => write!(f, "Z"),
+                FieldType::Object(name) => write!(f, "L{name};"),
+                FieldType::Array(component) => write!(f, "[{component}")
+            }
         }
     }
 
-
-    impl ResolveProxy for MethodRefProxy {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(ConstPoolType::MethodRef(resolve_double_proxy(&self.0, pool)?))
+    impl fmt::Display for MethodDescriptor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "(")?;
+            for parameter in &self.parameters {
+                write!(f, "{parameter}")?;
+            }
+            match &self.return_type {
+                Some(return_type) => write!(f, "){return_type}"),
+                None => write!(f, ")V")
+            }
         }
     }
 
-    impl ResolveProxy for InterfaceMethodRefProxy {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            Ok(ConstPoolType::InterfaceMethodRef(resolve_double_proxy(&self.0, pool)?))
+    // Returns the field type starting at `position` and the position just after it.
+    fn parse_field_type(descriptor: &str, position: usize) -> Result<(FieldType, usize), DescriptorError> {
+        let unexpected = |position: usize| match descriptor[position..].chars().next() {
+            Some(char) => DescriptorError::UnexpectedChar(descriptor.to_string(), char, position),
+            None => DescriptorError::Incomplete(descriptor.to_string())
+        };
+        let dimensions = descriptor.as_bytes()[position..].iter().take_while(|byte| **byte == b'[').count();
+        let start = position + dimensions;
+        let (mut field_type, end) = match descriptor.as_bytes().get(start) {
+            Some(b'B') => (FieldType::Byte, start + 1),
+            Some(b'C') => (FieldType::Char, start + 1),
+            Some(b'D') => (FieldType::Double, start + 1),
+            Some(b'F') => (FieldType::Float, start + 1),
+            Some(b'I') => (FieldType::Int, start + 1),
+            Some(b'J') => (FieldType::Long, start + 1),
+            Some(b'S') => (FieldType::Short, start + 1),
+            Some(b'Z') => (FieldType::Boolean, start + 1),
+            Some(b'L') => {
+                let length = descriptor[start + 1..].find(';')
+                    .ok_or_else(|| DescriptorError::Incomplete(descriptor.to_string()))?;
+                if length == 0 {
+                    return Err(unexpected(start + 1));
+                }
+                (FieldType::Object(descriptor[start + 1..start + 1 + length].to_string()), start + length + 2)
+            }
+            _ => return Err(unexpected(start))
+        };
+        for _ in 0..dimensions {
+            field_type = FieldType::Array(Box::new(field_type));
         }
+        Ok((field_type, end))
     }
 
-    impl ResolveProxy for ProxyToProxyNameAndType {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            if let ProxyConstPoolType::NameAndType(proxy) =
-                pool.get(self.0 as usize).ok_or(DeserializationError::Link)? {
-                return proxy.resolve(pool);
-            }
-            Err(DeserializationError::Link)
+    pub fn parse_field_descriptor(descriptor: &str) -> Result<FieldType, DescriptorError> {
+        let (field_type, end) = parse_field_type(descriptor, 0)?;
+        if end != descriptor.len() {
+            return Err(DescriptorError::Trailing(descriptor.to_string()));
         }
+        Ok(field_type)
     }
 
-    impl ResolveProxy for ProxyToProxyClass {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            if let ProxyConstPoolType::Class(proxy) =
-                pool.get(self.0 as usize).ok_or(DeserializationError::Link)? {
-                return proxy.resolve(pool);
-            }
-            Err(DeserializationError::Link)
+    // Renders a field type the way it is written in Java source, e.g. `int[]` or `java.lang.String`.
+    pub fn to_java_type(field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::Byte => "byte".to_string(),
+            FieldType::Char => "char".to_string(),
+            FieldType::Double => "double".to_string(),
+            FieldType::Float => "float".to_string(),
+            FieldType::Int => "int".to_string(),
+            FieldType::Long => "long".to_string(),
+            FieldType::Short => "short".to_string(),
+            FieldType::Boolean => "boolean".to_string(),
+            FieldType::Object(name) => name.replace('/', "."),
+            FieldType::Array(component) => format!("{}[]", to_java_type(component))
         }
     }
 
-    impl ResolveProxy for ProxyConstPoolType {
-        #[inline(always)]
-        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
-            match self {
-                ProxyConstPoolType::Value(value) => value.resolve(pool),
-                ProxyConstPoolType::NameAndType(value) => value.resolve(pool),
-                ProxyConstPoolType::FieldRef(value) => value.resolve(pool),
-                ProxyConstPoolType::MethodRef(value) => value.resolve(pool),
-                ProxyConstPoolType::InterfaceMethodRef(value) => value.resolve(pool),
-                ProxyConstPoolType::String(value) => value.resolve(pool),
-                ProxyConstPoolType::Class(value) => value.resolve(pool)
-            }
+    pub fn parse_method_descriptor(descriptor: &str) -> Result<MethodDescriptor, DescriptorError> {
+        if !descriptor.starts_with('(') {
+            return Err(match descriptor.chars().next() {
+                Some(char) => DescriptorError::UnexpectedChar(descriptor.to_string(), char, 0),
+                None => DescriptorError::Incomplete(descriptor.to_string())
+            });
         }
+        let mut parameters = vec![];
+        let mut position = 1;
+        while descriptor.as_bytes().get(position) != Some(&b')') {
+            let (parameter, end) = parse_field_type(descriptor, position)?;
+            parameters.push(parameter);
+            position = end;
+        }
+        let return_type = if descriptor.as_bytes().get(position + 1) == Some(&b'V') {
+            position += 2;
+            None
+        } else {
+            let (return_type, end) = parse_field_type(descriptor, position + 1)?;
+            position = end;
+            Some(return_type)
+        };
+        if position != descriptor.len() {
+            return Err(DescriptorError::Trailing(descriptor.to_string()));
+        }
+        Ok(MethodDescriptor {
+            parameters,
+            return_type
+        })
     }
 }
 
-pub mod deserialization {
-    use super::*;
-    use std::io::{Error, Read, Seek};
-    use std::rc::Rc;
-    use byteorder::{BigEndian, ReadBytesExt};
-    use class::const_pool::ConstPoolType;
-    use class::const_pool::ClassInfo;
-    use class::const_pool::Utf8Info;
-    use class::const_pool::{LongInfo, DoubleInfo, FloatInfo, IntInfo};
-    use class::const_pool::ConstPoolType::Utf8;
-    use super::proxy::*;
-    use class::attributes::*;
-    use class::components::*;
-    use class::BitFlags;
-    use class::Class;
+pub mod signature {
+    use super::descriptor::FieldType;
+    use super::DescriptorError;
 
-    trait Deserializable: Sized {
-        fn deserialize(cursor: impl Read + ReadBytesExt) -> Result<Self, DeserializationError>;
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum TypeArgument {
+        Wildcard,
+        Exact(ReferenceType),
+        Extends(ReferenceType),
+        Super(ReferenceType)
     }
 
-    trait DeserializableLinked: Sized {
-        fn deserialize_link(cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Self, DeserializationError>;
+    // One `.` separated segment of a class type; only the first segment carries the package.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SimpleClassType {
+        pub name: String,
+        pub arguments: Vec<TypeArgument>
     }
 
-    trait DeserializableLinkedNamed: Sized {
-        fn deserialize_link_named(name: String, cursor: impl Read + ReadBytesExt + Seek,
-                                  pool: &[ConstPoolType]) -> Result<Self, DeserializationError>;
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ClassType {
+        pub segments: Vec<SimpleClassType>
     }
 
-    #[inline(always)]
-    fn get_real_index(mut cursor: impl Read + ReadBytesExt) -> Result<u16, DeserializationError> {
-        let index = cursor.read_u16::<BigEndian>()?;
-        if index == 0 {
-            Err(DeserializationError::Link)
-        } else {
-            Ok(index - 1)
+    impl ClassType {
+        // The erased internal name, e.g. `java/util/Map$Entry` for `Ljava/util/Map<TK;TV;>.Entry;`.
+        pub fn internal_name(&self) -> String {
+            self.segments.iter().map(|segment| segment.name.as_str()).collect::<Vec<&str>>().join("$")
         }
     }
 
-    impl Deserializable for Utf8Info {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<Utf8Info, DeserializationError> {
-            let mut data: Vec<u8> = vec![0; cursor.read_u16::<BigEndian>()? as usize];
-            let _ = cursor.read(&mut data[..])?;
-            Ok(Rc::new(String::from_utf8(data)?))
-        }
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum ReferenceType {
+        Class(ClassType),
+        TypeVariable(String),
+        Array(Box<JavaType>)
     }
 
-    impl Deserializable for IntInfo {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<IntInfo, DeserializationError> {
-            Ok(cursor.read_i32::<BigEndian>()?)
-        }
+    // Base holds one of the primitive field types.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum JavaType {
+        Base(FieldType),
+        Reference(ReferenceType)
     }
 
-    impl Deserializable for FloatInfo {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<FloatInfo, DeserializationError> {
-            Ok(cursor.read_f32::<BigEndian>()?)
-        }
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TypeParameter {
+        pub name: String,
+        pub class_bound: Option<ReferenceType>,
+        pub interface_bounds: Vec<ReferenceType>
     }
 
-    impl Deserializable for LongInfo {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<LongInfo, DeserializationError> {
-            Ok(cursor.read_i64::<BigEndian>()?)
-        }
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ClassSignature {
+        pub type_parameters: Vec<TypeParameter>,
+        pub super_class: ClassType,
+        pub interfaces: Vec<ClassType>
     }
 
-    impl Deserializable for DoubleInfo {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<DoubleInfo, DeserializationError> {
-            Ok(cursor.read_f64::<BigEndian>()?)
-        }
+    // A return_type of None stands for void.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MethodSignature {
+        pub type_parameters: Vec<TypeParameter>,
+        pub parameters: Vec<JavaType>,
+        pub return_type: Option<JavaType>,
+        pub throws: Vec<ReferenceType>
     }
 
-    impl Deserializable for NameAndTypeProxy {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<NameAndTypeProxy, DeserializationError> {
-            Ok(NameAndTypeProxy {
-                name: Proxy(get_real_index(&mut cursor)?),
-                descriptor: Proxy(get_real_index(&mut cursor)?),
-            })
-        }
+    struct Parser<'a> {
+        signature: &'a str,
+        position: usize
     }
 
-    impl Deserializable for Proxy {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<Proxy, DeserializationError> {
-            Ok(Proxy(get_real_index(&mut cursor)?))
+    impl<'a> Parser<'a> {
+        fn new(signature: &'a str) -> Parser<'a> {
+            Parser {
+                signature,
+                position: 0
+            }
         }
-    }
 
-    impl Deserializable for DoubleProxy {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<DoubleProxy, DeserializationError> {
-            Ok(DoubleProxy {
-                class: ProxyToProxyClass(get_real_index(&mut cursor)?),
-                name_and_type: ProxyToProxyNameAndType(get_real_index(&mut cursor)?),
-            })
+        fn peek(&self) -> Option<u8> {
+            self.signature.as_bytes().get(self.position).copied()
         }
-    }
 
-    impl Deserializable for ProxyConstPoolType {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<ProxyConstPoolType, DeserializationError> {
-            match cursor.read_u8()? {
-                1 => Ok(ProxyConstPoolType::Value(Utf8(Utf8Info::deserialize(&mut cursor)?))),
-                3 => Ok(ProxyConstPoolType::Value(ConstPoolType::Int(IntInfo::deserialize(&mut cursor)?))),
-                4 => Ok(ProxyConstPoolType::Value(ConstPoolType::Float(FloatInfo::deserialize(&mut cursor)?))),
-                5 => Ok(ProxyConstPoolType::Value(ConstPoolType::Long(LongInfo::deserialize(&mut cursor)?))),
-                6 => Ok(ProxyConstPoolType::Value(ConstPoolType::Double(DoubleInfo::deserialize(&mut cursor)?))),
-                7 => Ok(ProxyConstPoolType::Class(ClassProxy(Proxy::deserialize(&mut cursor)?))),
-                8 => Ok(ProxyConstPoolType::String(StringProxy(Proxy::deserialize(&mut cursor)?))),
-                9 => Ok(ProxyConstPoolType::FieldRef(FieldRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
-                10 => Ok(ProxyConstPoolType::MethodRef(MethodRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
-                11 => Ok(ProxyConstPoolType::InterfaceMethodRef(InterfaceMethodRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
-                12 => Ok(ProxyConstPoolType::NameAndType(NameAndTypeProxy::deserialize(&mut cursor)?)),
-                unexpected => Err(DeserializationError::Parsing(format!("Invalid const pool type id: {unexpected}")))
+        fn unexpected(&self) -> DescriptorError {
+            match self.signature[self.position..].chars().next() {
+                Some(char) => DescriptorError::UnexpectedChar(self.signature.to_string(), char, self.position),
+                None => DescriptorError::Incomplete(self.signature.to_string())
             }
         }
-    }
 
-    impl Deserializable for Vec<ConstPoolType> {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<Vec<ConstPoolType>, DeserializationError> {
-            let proxy = (0..(cursor.read_u16::<BigEndian>()? - 1) as usize)
-                .map(|_| ProxyConstPoolType::deserialize(&mut cursor))
-                .collect::<Result<Vec<ProxyConstPoolType>, _>>()?;
-            let pool = proxy.iter()
-                .map(|p| p.resolve(&proxy))
-                .collect::<Result<Vec<ConstPoolType>, _>>()?;
-            Ok(pool)
+        fn expect(&mut self, byte: u8) -> Result<(), DescriptorError> {
+            if self.peek() != Some(byte) {
+                return Err(self.unexpected());
+            }
+            self.position += 1;
+            Ok(())
         }
-    }
 
-    #[inline(always)]
-    fn find_const_pool_element(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Option<&ConstPoolType>, DeserializationError> {
-        let index = cursor.read_u16::<BigEndian>()? as usize;
-        if index == 0 {
-            Ok(None)
-        } else {
-            Ok(Some(pool.get(index - 1).ok_or(DeserializationError::Link)?))
+        // Package separators are only allowed in the leading segment of a class type.
+        fn identifier(&mut self, allow_package: bool) -> Result<String, DescriptorError> {
+            let rest = &self.signature[self.position..];
+            let length = rest.find(|char| matches!(char, '.' | ';' | '[' | '<' | '>' | ':') || (char == '/' && !allow_package))
+                .unwrap_or(rest.len());
+            if length == 0 {
+                return Err(self.unexpected());
+            }
+            self.position += length;
+            Ok(rest[..length].to_string())
         }
-    }
 
-    impl DeserializableLinked for Utf8Info {
-        #[inline(always)]
-        fn deserialize_link(cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Utf8Info, DeserializationError> {
-            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
-                Utf8(info) => Ok(info.clone()),
-                _ => Err(DeserializationError::Link)
+        fn class_type(&mut self) -> Result<ClassType, DescriptorError> {
+            self.expect(b'L')?;
+            let mut segments = vec![];
+            loop {
+                let name = self.identifier(segments.is_empty())?;
+                let arguments = if self.peek() == Some(b'<') { self.type_arguments()? } else { vec![] };
+                segments.push(SimpleClassType {
+                    name,
+                    arguments
+                });
+                if self.peek() != Some(b'.') {
+                    break;
+                }
+                self.position += 1;
             }
+            self.expect(b';')?;
+            Ok(ClassType {
+                segments
+            })
         }
-    }
 
-    impl DeserializableLinked for ClassInfo {
-        #[inline(always)]
-        fn deserialize_link(cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ClassInfo, DeserializationError> {
-            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
-                ConstPoolType::Class(info) => Ok(info.clone()),
-                _ => Err(DeserializationError::Link)
+        fn type_arguments(&mut self) -> Result<Vec<TypeArgument>, DescriptorError> {
+            self.expect(b'<')?;
+            let mut arguments = vec![];
+            while self.peek() != Some(b'>') {
+                arguments.push(match self.peek() {
+                    Some(b'*') => {
+                        self.position += 1;
+                        TypeArgument::Wildcard
+                    }
+                    Some(b'+') => {
+                        self.position += 1;
+                        TypeArgument::Extends(self.reference_type()?)
+                    }
+                    Some(b'-') => {
+                        self.position += 1;
+                        TypeArgument::Super(self.reference_type()?)
+                    }
+                    _ => TypeArgument::Exact(self.reference_type()?)
+                });
+            }
+            if arguments.is_empty() {
+                return Err(self.unexpected());
             }
+            self.position += 1;
+            Ok(arguments)
         }
-    }
 
-    impl DeserializableLinked for ConstValueType {
-        #[inline(always)]
-        fn deserialize_link(cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ConstValueType, DeserializationError> {
-            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
-                ConstPoolType::Long(data) => Ok(ConstValueType::Long(*data)),
-                ConstPoolType::Int(data) => Ok(ConstValueType::Int(*data)),
-                ConstPoolType::Float(data) => Ok(ConstValueType::Float(*data)),
-                ConstPoolType::Double(data) => Ok(ConstValueType::Double(*data)),
+        fn reference_type(&mut self) -> Result<ReferenceType, DescriptorError> {
+            match self.peek() {
+                Some(b'L') => Ok(ReferenceType::Class(self.class_type()?)),
+                Some(b'T') => {
+                    self.position += 1;
+                    let name = self.identifier(false)?;
+                    self.expect(b';')?;
+                    Ok(ReferenceType::TypeVariable(name))
+                }
+                Some(b'[') => {
+                    self.position += 1;
+                    Ok(ReferenceType::Array(Box::new(self.java_type()?)))
+                }
+                _ => Err(self.unexpected())
+            }
+        }
+
+        fn java_type(&mut self) -> Result<JavaType, DescriptorError> {
+            let base = match self.peek() {
+                Some(b'B') => FieldType::Byte,
+                Some(b'C') => FieldType::Char,
+                Some(b'D') => FieldType::Double,
+                Some(b'F') => FieldType::Float,
+                Some(b'I') => FieldType::Int,
+                Some(b'J') => FieldType::Long,
+                Some(b'S') => FieldType::Short,
+                Some(b'Z') => FieldType::Boolean,
+                _ => return Ok(JavaType::Reference(self.reference_type()?))
+            };
+            self.position += 1;
+            Ok(JavaType::Base(base))
+        }
+
+        fn type_parameters(&mut self) -> Result<Vec<TypeParameter>, DescriptorError> {
+            if self.peek() != Some(b'<') {
+                return Ok(vec![]);
+            }
+            self.position += 1;
+            let mut parameters = vec![];
+            while self.peek() != Some(b'>') {
+                let name = self.identifier(false)?;
+                self.expect(b':')?;
+                let class_bound = match self.peek() {
+                    Some(b'L' | b'T' | b'[') => Some(self.reference_type()?),
+                    _ => None
+                };
+                let mut interface_bounds = vec![];
+                while self.peek() == Some(b':') {
+                    self.position += 1;
+                    interface_bounds.push(self.reference_type()?);
+                }
+                parameters.push(TypeParameter {
+                    name,
+                    class_bound,
+                    interface_bounds
+                });
+            }
+            if parameters.is_empty() {
+                return Err(self.unexpected());
+            }
+            self.position += 1;
+            Ok(parameters)
+        }
+
+        fn finish<T>(self, value: T) -> Result<T, DescriptorError> {
+            if self.position != self.signature.len() {
+                return Err(DescriptorError::Trailing(self.signature.to_string()));
+            }
+            Ok(value)
+        }
+    }
+
+    pub fn parse_class_signature(signature: &str) -> Result<ClassSignature, DescriptorError> {
+        let mut parser = Parser::new(signature);
+        let type_parameters = parser.type_parameters()?;
+        let super_class = parser.class_type()?;
+        let mut interfaces = vec![];
+        while parser.peek().is_some() {
+            interfaces.push(parser.class_type()?);
+        }
+        parser.finish(ClassSignature {
+            type_parameters,
+            super_class,
+            interfaces
+        })
+    }
+
+    pub fn parse_method_signature(signature: &str) -> Result<MethodSignature, DescriptorError> {
+        let mut parser = Parser::new(signature);
+        let type_parameters = parser.type_parameters()?;
+        parser.expect(b'(')?;
+        let mut parameters = vec![];
+        while parser.peek() != Some(b')') {
+            parameters.push(parser.java_type()?);
+        }
+        parser.position += 1;
+        let return_type = if parser.peek() == Some(b'V') {
+            parser.position += 1;
+            None
+        } else {
+            Some(parser.java_type()?)
+        };
+        let mut throws = vec![];
+        while parser.peek() == Some(b'^') {
+            parser.position += 1;
+            throws.push(match parser.peek() {
+                Some(b'L' | b'T') => parser.reference_type()?,
+                _ => return Err(parser.unexpected())
+            });
+        }
+        parser.finish(MethodSignature {
+            type_parameters,
+            parameters,
+            return_type,
+            throws
+        })
+    }
+
+    pub fn parse_field_signature(signature: &str) -> Result<ReferenceType, DescriptorError> {
+        let mut parser = Parser::new(signature);
+        let field_type = parser.reference_type()?;
+        parser.finish(field_type)
+    }
+}
+
+pub mod validation {
+    use super::deserialization::{read_proxy_pool, ParseOptions};
+    use super::descriptor::{parse_field_descriptor, parse_method_descriptor};
+    use super::proxy::{invalid_references, ResolveProxy};
+    use super::{DeserializationError, ValidationError};
+    use class::attributes::Attribute;
+    use class::components::{AccessSpecifier, ClassAccess, FieldInfo, MethodAccessSpecifier, MethodInfo};
+    use class::BitFlags;
+    use class::const_pool::{ClassInfo, ConstPoolType};
+    use class::Class;
+
+    // Locations of the attributes predefined by JVMS 4.7; anything else is left alone, as a JVM would ignore it.
+    const SHARED_ATTRIBUTES: [&str; 7] = ["Synthetic", "Deprecated", "Signature", "RuntimeVisibleAnnotations",
+        "RuntimeInvisibleAnnotations", "RuntimeVisibleTypeAnnotations", "RuntimeInvisibleTypeAnnotations"];
+    const CLASS_ATTRIBUTES: [&str; 12] = ["SourceFile", "InnerClasses", "EnclosingMethod", "SourceDebugExtension", "BootstrapMethods",
+        "Module", "ModulePackages", "ModuleMainClass", "NestHost", "NestMembers", "Record", "PermittedSubclasses"];
+    const FIELD_ATTRIBUTES: [&str; 1] = ["ConstantValue"];
+    const METHOD_ATTRIBUTES: [&str; 6] = ["Code", "Exceptions", "RuntimeVisibleParameterAnnotations",
+        "RuntimeInvisibleParameterAnnotations", "AnnotationDefault", "MethodParameters"];
+    const CODE_ATTRIBUTES: [&str; 4] = ["LineNumberTable", "LocalVariableTable", "LocalVariableTypeTable", "StackMapTable"];
+
+    fn check_placement(attributes: &[Attribute], allowed: &[&str], owner: impl Fn() -> String, errors: &mut Vec<ValidationError>) {
+        let predefined = |name: &str| [&SHARED_ATTRIBUTES[..], &CLASS_ATTRIBUTES, &FIELD_ATTRIBUTES, &METHOD_ATTRIBUTES, &CODE_ATTRIBUTES]
+            .iter()
+            .any(|names| names.contains(&name));
+        for name in attributes.iter().map(Attribute::name) {
+            if predefined(name) && !allowed.contains(&name) {
+                errors.push(ValidationError::MisplacedAttribute {
+                    attribute: name.to_string(),
+                    owner: owner()
+                });
+            }
+        }
+    }
+
+    fn check_attribute_placement(class: &Class, errors: &mut Vec<ValidationError>) {
+        let allowed = [&SHARED_ATTRIBUTES[..], &CLASS_ATTRIBUTES].concat();
+        check_placement(&class.attributes, &allowed, || format!("class `{}`", class.this_class.0), errors);
+        let allowed = [&SHARED_ATTRIBUTES[..], &FIELD_ATTRIBUTES].concat();
+        for field in &class.fields {
+            check_placement(&field.attributes, &allowed, || format!("field `{}`", field.name), errors);
+        }
+        let allowed = [&SHARED_ATTRIBUTES[..], &METHOD_ATTRIBUTES].concat();
+        for method in &class.methods {
+            check_placement(&method.attributes, &allowed, || format!("method `{}{}`", method.name, method.descriptor), errors);
+        }
+        // Record components take the shared attributes except Synthetic and Deprecated.
+        let records = class.attributes.iter().filter_map(|attribute| match attribute {
+            Attribute::Record(record) => Some(record),
+            _ => None
+        });
+        for component in records.flat_map(|record| &record.components) {
+            check_placement(&component.attributes, &SHARED_ATTRIBUTES[2..], || format!("record component `{}`", component.name), errors);
+        }
+    }
+
+    // At most one of public, private and protected may be set.
+    #[inline(always)]
+    fn conflicting_visibility<Access: class::BitFlag>(access: BitFlags<Access>, visibility: BitFlags<Access>) -> bool {
+        (access & visibility).len() > 1
+    }
+
+    fn class_flag_violation(access: BitFlags<ClassAccess>) -> Option<&'static str> {
+        if access.contains(ClassAccess::Module) {
+            return None;
+        }
+        if access.contains(ClassAccess::Interface) {
+            if !access.contains(ClassAccess::Abstract) {
+                return Some("an interface must be abstract");
+            }
+            if access.intersects(ClassAccess::Final | ClassAccess::Super | ClassAccess::Enum) {
+                return Some("an interface cannot be final, super or an enum");
+            }
+        } else {
+            if access.contains(ClassAccess::Annotation) {
+                return Some("an annotation must be an interface");
+            }
+            if access.contains(ClassAccess::Final | ClassAccess::Abstract) {
+                return Some("a class cannot be both final and abstract");
+            }
+        }
+        None
+    }
+
+    fn field_flag_violation(field: &FieldInfo, interface: bool) -> Option<&'static str> {
+        let access = field.access;
+        if interface {
+            let required = AccessSpecifier::Public | AccessSpecifier::Static | AccessSpecifier::Final;
+            return (!access.contains(required) || !(access & !(required | AccessSpecifier::Synthetic)).is_empty())
+                .then_some("an interface field must be public, static and final and nothing else");
+        }
+        if conflicting_visibility(access, AccessSpecifier::Public | AccessSpecifier::Private | AccessSpecifier::Protected) {
+            return Some("at most one of public, private and protected is allowed");
+        }
+        access.contains(AccessSpecifier::Final | AccessSpecifier::Volatile).then_some("a field cannot be both final and volatile")
+    }
+
+    fn method_flag_violation(method: &MethodInfo, interface: bool, major: u16) -> Option<&'static str> {
+        let access = method.access;
+        // Only ACC_STATIC matters for class initializers, and only from Java 7 on.
+        if method.name.as_str() == "<clinit>" {
+            return (major >= 51 && !access.contains(MethodAccessSpecifier::Static)).then_some("a class initializer must be static");
+        }
+        if conflicting_visibility(access, MethodAccessSpecifier::Public | MethodAccessSpecifier::Private | MethodAccessSpecifier::Protected) {
+            return Some("at most one of public, private and protected is allowed");
+        }
+        if interface {
+            if major < 52 && !access.contains(MethodAccessSpecifier::Public | MethodAccessSpecifier::Abstract) {
+                return Some("an interface method must be public and abstract before Java 8");
+            }
+            if !access.intersects(MethodAccessSpecifier::Public | MethodAccessSpecifier::Private) {
+                return Some("an interface method must be public or private");
+            }
+            if access.intersects(MethodAccessSpecifier::Protected | MethodAccessSpecifier::Final | MethodAccessSpecifier::Synchronized
+                | MethodAccessSpecifier::Native) {
+                return Some("an interface method cannot be protected, final, synchronized or native");
+            }
+        }
+        if method.name.as_str() == "<init>" {
+            let allowed = MethodAccessSpecifier::Public | MethodAccessSpecifier::Private | MethodAccessSpecifier::Protected
+                | MethodAccessSpecifier::Varargs | MethodAccessSpecifier::Strict | MethodAccessSpecifier::Synthetic;
+            return (!(access & !allowed).is_empty())
+                .then_some("an instance initializer may only carry visibility, varargs, strict and synthetic");
+        }
+        let mut concrete_only = MethodAccessSpecifier::Private | MethodAccessSpecifier::Static | MethodAccessSpecifier::Final
+            | MethodAccessSpecifier::Synchronized | MethodAccessSpecifier::Native;
+        // ACC_STRICT only has a meaning from Java 1.2 up to Java 16.
+        if (46..=60).contains(&major) {
+            concrete_only |= MethodAccessSpecifier::Strict;
+        }
+        (access.contains(MethodAccessSpecifier::Abstract) && access.intersects(concrete_only))
+            .then_some("an abstract method cannot be private, static, final, synchronized, native or strict")
+    }
+
+    // JVMS 4.1, 4.5 and 4.6; each class or member is reported for the first rule it breaks.
+    fn check_access_flags(class: &Class, errors: &mut Vec<ValidationError>) {
+        let mut report = |owner: String, reason: &'static str| errors.push(ValidationError::IllegalAccessFlags { owner, reason });
+        if let Some(reason) = class_flag_violation(class.access) {
+            report(format!("class `{}`", class.this_class.0), reason);
+        }
+        let interface = class.access.contains(ClassAccess::Interface);
+        for field in &class.fields {
+            if let Some(reason) = field_flag_violation(field, interface) {
+                report(format!("field `{}`", field.name), reason);
+            }
+        }
+        for method in &class.methods {
+            if let Some(reason) = method_flag_violation(method, interface, class.version.major) {
+                report(format!("method `{}{}`", method.name, method.descriptor), reason);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn check_class_entry(class: &Class, info: &ClassInfo, errors: &mut Vec<ValidationError>) {
+        let present = class.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Class(entry) if entry.0 == info.0));
+        if !present {
+            errors.push(ValidationError::MissingClassEntry(info.0.to_string()));
+        }
+    }
+
+    fn check_references(pool: &[ConstPoolType], errors: &mut Vec<ValidationError>) {
+        for (position, entry) in pool.iter().enumerate() {
+            let result = match entry {
+                ConstPoolType::Field(field) => parse_field_descriptor(&field.name_and_type.descriptor).map(|_| ()),
+                ConstPoolType::MethodRef(method) | ConstPoolType::InterfaceMethodRef(method) =>
+                    parse_method_descriptor(&method.name_and_type.descriptor).map(|_| ()),
+                _ => Ok(())
+            };
+            if let Err(error) = result {
+                errors.push(ValidationError::InvalidDescriptor {
+                    index: position as u16 + 1,
+                    error
+                });
+            }
+        }
+    }
+
+    fn check_method(method: &MethodInfo, errors: &mut Vec<ValidationError>) {
+        let code = match method.code() {
+            Some(code) => code,
+            None => return
+        };
+        // An unparseable descriptor is only reported for constant pool references.
+        if let Ok(descriptor) = parse_method_descriptor(&method.descriptor) {
+            let receiver = if method.access.contains(MethodAccessSpecifier::Static) { 0 } else { 1 };
+            let required = descriptor.argument_slot_size() as u32 + receiver;
+            if (code.max_local as u32) < required {
+                errors.push(ValidationError::MaxLocalsTooSmall {
+                    method: format!("{}{}", method.name, method.descriptor),
+                    max_local: code.max_local,
+                    required: required as u16
+                });
+            }
+        }
+        let length = code.code.len();
+        for (entry, handler) in code.exceptions.iter().enumerate() {
+            if handler.start_pc >= handler.end_pc || handler.end_pc as usize > length || handler.handler_pc as usize >= length {
+                errors.push(ValidationError::HandlerOutOfRange {
+                    method: format!("{}{}", method.name, method.descriptor),
+                    entry
+                });
+            }
+        }
+    }
+
+    // Checks cross references the parser takes on trust and reports every problem found.
+    pub fn validate(class: &Class) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        check_class_entry(class, &class.this_class, &mut errors);
+        if let Some(super_class) = &class.super_class {
+            check_class_entry(class, super_class, &mut errors);
+        }
+        check_references(&class.const_pool, &mut errors);
+        class.methods.iter().for_each(|method| check_method(method, &mut errors));
+        check_attribute_placement(class, &mut errors);
+        check_access_flags(class, &mut errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    // Works on the raw class file, since a parsed class only holds references that already resolved. Descriptors are
+    // checked once every reference points at the right kind of entry.
+    pub fn validate_const_pool(bytes: &[u8]) -> Result<(), Vec<ValidationError>> {
+        let unreadable = |error: DeserializationError| vec![ValidationError::Unreadable(error.to_string())];
+        if bytes.len() < 8 || bytes[..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+            return Err(unreadable(DeserializationError::Parsing("Its not JVM class file.".into())));
+        }
+        let pool = read_proxy_pool(&bytes[8..], &ParseOptions::default()).map_err(unreadable)?;
+        let mut errors = vec![];
+        for (position, entry) in pool.iter().enumerate() {
+            errors.extend(invalid_references(entry, &pool).into_iter().map(|(target, expected)| ValidationError::WrongReference {
+                index: position as u16 + 1,
+                target,
+                expected
+            }));
+        }
+        if errors.is_empty() {
+            let resolved = pool.iter()
+                .map(|entry| entry.resolve(&pool))
+                .collect::<Result<Vec<ConstPoolType>, DeserializationError>>()
+                .map_err(unreadable)?;
+            check_references(&resolved, &mut errors);
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+pub mod hierarchy {
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use class::Class;
+
+    const OBJECT: &str = "java/lang/Object";
+
+    // Classes missing from the map are leaves: they are reported as supertypes but their own supertypes are unknown.
+    pub struct Hierarchy<'a> {
+        classes: &'a HashMap<String, Class>
+    }
+
+    impl<'a> Hierarchy<'a> {
+        pub fn new(classes: &'a HashMap<String, Class>) -> Hierarchy<'a> {
+            Hierarchy {
+                classes
+            }
+        }
+
+        // Lists superclasses and interfaces breadth-first, nearest first, without duplicates.
+        pub fn supertypes_of(&self, name: &str) -> Vec<String> {
+            let mut supertypes = vec![];
+            let mut seen = HashSet::from([name.to_string()]);
+            let mut pending = VecDeque::from([name.to_string()]);
+            while let Some(current) = pending.pop_front() {
+                let class = match self.classes.get(&current) {
+                    Some(class) => class,
+                    None => continue
+                };
+                let direct = class.super_class.iter().chain(&class.interfaces).map(|supertype| supertype.0.to_string());
+                for supertype in direct {
+                    if seen.insert(supertype.clone()) {
+                        supertypes.push(supertype.clone());
+                        pending.push_back(supertype);
+                    }
+                }
+            }
+            supertypes
+        }
+
+        pub fn is_assignable(&self, sub: &str, sup: &str) -> bool {
+            sub == sup || sup == OBJECT || self.supertypes_of(sub).iter().any(|supertype| supertype == sup)
+        }
+    }
+}
+
+pub mod diff {
+    use class::attributes::Attribute;
+    use class::components::{AccessSpecifier, ClassAccess, ClassVersion, ComponentInfo, MethodAccessSpecifier};
+    use class::{BitFlag, BitFlags, Class};
+
+    // Attributes are told apart by name only; attributes with the same name are compared as a group.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum AttributeChange {
+        Added(String),
+        Removed(String),
+        Changed(String)
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum MemberChange<Access: BitFlag> {
+        Access {
+            old: BitFlags<Access>,
+            new: BitFlags<Access>
+        },
+        Attribute(AttributeChange)
+    }
+
+    // Fields are named `name:descriptor` and methods `namedescriptor`; a member whose descriptor changed is reported
+    // as removed and added, as a linker would see it.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum ClassChange {
+        Version {
+            old: ClassVersion,
+            new: ClassVersion
+        },
+        Access {
+            old: BitFlags<ClassAccess>,
+            new: BitFlags<ClassAccess>
+        },
+        SuperClass {
+            old: Option<String>,
+            new: Option<String>
+        },
+        InterfaceAdded(String),
+        InterfaceRemoved(String),
+        FieldAdded(String),
+        FieldRemoved(String),
+        FieldChanged {
+            field: String,
+            changes: Vec<MemberChange<AccessSpecifier>>
+        },
+        MethodAdded(String),
+        MethodRemoved(String),
+        MethodChanged {
+            method: String,
+            changes: Vec<MemberChange<MethodAccessSpecifier>>
+        },
+        Attribute(AttributeChange)
+    }
+
+    // Code is compared as a whole, so it also changes when only the constant pool indices it uses moved.
+    fn attribute_changes(old: &[Attribute], new: &[Attribute]) -> Vec<AttributeChange> {
+        let mut names: Vec<&str> = vec![];
+        for name in old.iter().chain(new).map(Attribute::name) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let named = |attributes: &[Attribute], name: &str| -> Vec<Attribute> {
+            attributes.iter().filter(|attribute| attribute.name() == name).cloned().collect()
+        };
+        names.into_iter()
+            .filter_map(|name| match (named(old, name), named(new, name)) {
+                (before, after) if before.is_empty() => (!after.is_empty()).then(|| AttributeChange::Added(name.to_string())),
+                (_, after) if after.is_empty() => Some(AttributeChange::Removed(name.to_string())),
+                (before, after) => (before != after).then(|| AttributeChange::Changed(name.to_string()))
+            })
+            .collect()
+    }
+
+    fn member_changes<Access: BitFlag>(old: &ComponentInfo<Access>, new: &ComponentInfo<Access>) -> Vec<MemberChange<Access>> {
+        let mut changes = vec![];
+        if old.access != new.access {
+            changes.push(MemberChange::Access {
+                old: old.access,
+                new: new.access
+            });
+        }
+        changes.extend(attribute_changes(&old.attributes, &new.attributes).into_iter().map(MemberChange::Attribute));
+        changes
+    }
+
+    struct MemberKind<Access: BitFlag> {
+        key: fn(&ComponentInfo<Access>) -> String,
+        added: fn(String) -> ClassChange,
+        removed: fn(String) -> ClassChange,
+        changed: fn(String, Vec<MemberChange<Access>>) -> ClassChange
+    }
+
+    fn member_diff<Access: BitFlag>(old: &[ComponentInfo<Access>], new: &[ComponentInfo<Access>], kind: MemberKind<Access>,
+                                    out: &mut Vec<ClassChange>) {
+        let find = |members: &[ComponentInfo<Access>], member: &ComponentInfo<Access>| members.iter()
+            .position(|candidate| candidate.name == member.name && candidate.descriptor == member.descriptor);
+        for member in old {
+            match find(new, member) {
+                Some(position) => {
+                    let changes = member_changes(member, &new[position]);
+                    if !changes.is_empty() {
+                        out.push((kind.changed)((kind.key)(member), changes));
+                    }
+                }
+                None => out.push((kind.removed)((kind.key)(member)))
+            }
+        }
+        out.extend(new.iter().filter(|member| find(old, member).is_none()).map(|member| (kind.added)((kind.key)(member))));
+    }
+
+    // Lists what changed from `old` to `new`: version, flags, supertypes, members and attributes, in that order.
+    pub fn diff(old: &Class, new: &Class) -> Vec<ClassChange> {
+        let mut changes = vec![];
+        if old.version != new.version {
+            changes.push(ClassChange::Version {
+                old: old.version.clone(),
+                new: new.version.clone()
+            });
+        }
+        if old.access != new.access {
+            changes.push(ClassChange::Access {
+                old: old.access,
+                new: new.access
+            });
+        }
+        let super_name = |class: &Class| class.super_class.as_ref().map(|super_class| super_class.0.to_string());
+        if super_name(old) != super_name(new) {
+            changes.push(ClassChange::SuperClass {
+                old: super_name(old),
+                new: super_name(new)
+            });
+        }
+        changes.extend(old.interfaces.iter().filter(|interface| !new.interfaces.contains(interface))
+            .map(|interface| ClassChange::InterfaceRemoved(interface.0.to_string())));
+        changes.extend(new.interfaces.iter().filter(|interface| !old.interfaces.contains(interface))
+            .map(|interface| ClassChange::InterfaceAdded(interface.0.to_string())));
+        member_diff(&old.fields, &new.fields, MemberKind {
+            key: |field| format!("{}:{}", field.name, field.descriptor),
+            added: ClassChange::FieldAdded,
+            removed: ClassChange::FieldRemoved,
+            changed: |field, changes| ClassChange::FieldChanged { field, changes }
+        }, &mut changes);
+        member_diff(&old.methods, &new.methods, MemberKind {
+            key: |method| format!("{}{}", method.name, method.descriptor),
+            added: ClassChange::MethodAdded,
+            removed: ClassChange::MethodRemoved,
+            changed: |method, changes| ClassChange::MethodChanged { method, changes }
+        }, &mut changes);
+        changes.extend(attribute_changes(&old.attributes, &new.attributes).into_iter().map(ClassChange::Attribute));
+        changes
+    }
+}
+
+mod proxy {
+    use class::Shared;
+    use class::const_pool::{ConstPoolType, NameAndTypeInfoStruct, Utf8Info, ComponentRef, ClassInfo, MethodHandleInfo,
+                            MethodTypeInfo, BootstrapRef, ModuleInfo, PackageInfo};
+    use super::*;
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct Proxy(pub u16);
+
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct DoubleProxy {
+        pub class: ProxyToProxyClass,
+        pub name_and_type: ProxyToProxyNameAndType,
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct ProxyToProxyNameAndType(pub u16);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct ProxyToProxyClass(pub u16);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct NameAndTypeProxy {
+        pub name: Proxy,
+        pub descriptor: Proxy,
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct FieldRefProxy(pub DoubleProxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct MethodRefProxy(pub DoubleProxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct InterfaceMethodRefProxy(pub DoubleProxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct StringProxy(pub Proxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct ClassProxy(pub Proxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct MethodTypeProxy(pub Proxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct ModuleProxy(pub Proxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct PackageProxy(pub Proxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct BootstrapProxy {
+        pub bootstrap_method_attr_index: u16,
+        pub name_and_type: ProxyToProxyNameAndType,
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct DynamicProxy(pub BootstrapProxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct InvokeDynamicProxy(pub BootstrapProxy);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct MethodHandleProxy {
+        pub reference_kind: u8,
+        pub reference: Proxy,
+    }
+
+
+    pub enum ProxyConstPoolType {
+        Value(ConstPoolType),
+        NameAndType(NameAndTypeProxy),
+        FieldRef(FieldRefProxy),
+        MethodRef(MethodRefProxy),
+        InterfaceMethodRef(InterfaceMethodRefProxy),
+        String(StringProxy),
+        Class(ClassProxy),
+        MethodHandle(MethodHandleProxy),
+        MethodType(MethodTypeProxy),
+        Dynamic(DynamicProxy),
+        InvokeDynamic(InvokeDynamicProxy),
+        Module(ModuleProxy),
+        Package(PackageProxy),
+    }
+
+    pub trait ResolveProxy: Sized {
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError>;
+    }
+
+    impl ResolveProxy for ConstPoolType {
+        fn resolve(&self, _: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(self.clone())
+        }
+    }
+
+    #[inline(always)]
+    fn resolve_simple_proxy(proxy: &Proxy, pool: &[ProxyConstPoolType]) -> Result<Utf8Info, DeserializationError> {
+        if let ProxyConstPoolType::Value(ConstPoolType::Utf8(utf8)) =
+            pool.get((proxy.0) as usize).ok_or(DeserializationError::Link)? {
+            return Ok(utf8.clone());
+        }
+        Err(DeserializationError::Link)
+    }
+
+    impl ResolveProxy for StringProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::String(resolve_simple_proxy(&self.0, pool)?))
+        }
+    }
+
+    impl ResolveProxy for ClassProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            if let ProxyConstPoolType::Value(ConstPoolType::Utf8(utf8)) =
+                pool.get(self.0.0 as usize).ok_or(DeserializationError::Link)? {
+                return Ok(ConstPoolType::Class(ClassInfo(utf8.clone())));
+            }
+            Err(DeserializationError::Link)
+        }
+    }
+
+    impl ResolveProxy for NameAndTypeProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::NameAndType(Shared::new(NameAndTypeInfoStruct {
+                name: resolve_simple_proxy(&self.name, pool)?,
+                descriptor: resolve_simple_proxy(&self.descriptor, pool)?,
+            })))
+        }
+    }
+
+    #[inline(always)]
+    fn resolve_double_proxy(proxy: &DoubleProxy, pool: &[ProxyConstPoolType]) -> Result<ComponentRef, DeserializationError> {
+        let class = match proxy.class.resolve(pool) {
+            Ok(ConstPoolType::Class(class)) => Ok(class),
+            _ => Err(DeserializationError::RefClassIndex(proxy.class.0 + 1))
+        }?;
+        let name_and_type = match proxy.name_and_type.resolve(pool) {
+            Ok(ConstPoolType::NameAndType(name_and_type)) => Ok(name_and_type),
+            _ => Err(DeserializationError::RefNameAndTypeIndex(proxy.name_and_type.0 + 1))
+        }?;
+        Ok(ComponentRef {
+            class,
+            name_and_type,
+        })
+    }
+
+    impl ResolveProxy for FieldRefProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::Field(resolve_double_proxy(&self.0, pool)?))
+        }
+    }
+
+
+    impl ResolveProxy for MethodRefProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::MethodRef(resolve_double_proxy(&self.0, pool)?))
+        }
+    }
+
+    impl ResolveProxy for InterfaceMethodRefProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::InterfaceMethodRef(resolve_double_proxy(&self.0, pool)?))
+        }
+    }
+
+    impl ResolveProxy for ModuleProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::Module(ModuleInfo(resolve_simple_proxy(&self.0, pool)?)))
+        }
+    }
+
+    impl ResolveProxy for PackageProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::Package(PackageInfo(resolve_simple_proxy(&self.0, pool)?)))
+        }
+    }
+
+    impl ResolveProxy for MethodTypeProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::MethodType(MethodTypeInfo(resolve_simple_proxy(&self.0, pool)?)))
+        }
+    }
+
+    #[inline(always)]
+    fn resolve_bootstrap_proxy(proxy: &BootstrapProxy, pool: &[ProxyConstPoolType]) -> Result<BootstrapRef, DeserializationError> {
+        let name_and_type = match proxy.name_and_type.resolve(pool) {
+            Ok(ConstPoolType::NameAndType(name_and_type)) => Ok(name_and_type),
+            _ => Err(DeserializationError::RefNameAndTypeIndex(proxy.name_and_type.0 + 1))
+        }?;
+        Ok(BootstrapRef {
+            bootstrap_method_attr_index: proxy.bootstrap_method_attr_index,
+            name_and_type,
+        })
+    }
+
+    impl ResolveProxy for DynamicProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::Dynamic(resolve_bootstrap_proxy(&self.0, pool)?))
+        }
+    }
+
+    impl ResolveProxy for InvokeDynamicProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            Ok(ConstPoolType::InvokeDynamic(resolve_bootstrap_proxy(&self.0, pool)?))
+        }
+    }
+
+    impl ResolveProxy for MethodHandleProxy {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            let reference = match (self.reference_kind, pool.get(self.reference.0 as usize).ok_or(DeserializationError::Link)?) {
+                (1..=4, ProxyConstPoolType::FieldRef(FieldRefProxy(proxy)))
+                | (5..=8, ProxyConstPoolType::MethodRef(MethodRefProxy(proxy)))
+                | (6 | 7 | 9, ProxyConstPoolType::InterfaceMethodRef(InterfaceMethodRefProxy(proxy))) => resolve_double_proxy(proxy, pool)?,
+                (1..=9, _) => return Err(DeserializationError::Link),
+                (unexpected, _) => return Err(DeserializationError::Parsing(format!("Invalid method handle reference kind: {unexpected}")))
+            };
+            Ok(ConstPoolType::MethodHandle(MethodHandleInfo {
+                reference_kind: self.reference_kind,
+                reference
+            }))
+        }
+    }
+
+    impl ResolveProxy for ProxyToProxyNameAndType {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            if let ProxyConstPoolType::NameAndType(proxy) =
+                pool.get(self.0 as usize).ok_or(DeserializationError::Link)? {
+                return proxy.resolve(pool);
+            }
+            Err(DeserializationError::Link)
+        }
+    }
+
+    impl ResolveProxy for ProxyToProxyClass {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            if let ProxyConstPoolType::Class(proxy) =
+                pool.get(self.0 as usize).ok_or(DeserializationError::Link)? {
+                return proxy.resolve(pool);
+            }
+            Err(DeserializationError::Link)
+        }
+    }
+
+    impl ResolveProxy for ProxyConstPoolType {
+        #[inline(always)]
+        fn resolve(&self, pool: &[ProxyConstPoolType]) -> Result<ConstPoolType, DeserializationError> {
+            match self {
+                ProxyConstPoolType::Value(value) => value.resolve(pool),
+                ProxyConstPoolType::NameAndType(value) => value.resolve(pool),
+                ProxyConstPoolType::FieldRef(value) => value.resolve(pool),
+                ProxyConstPoolType::MethodRef(value) => value.resolve(pool),
+                ProxyConstPoolType::InterfaceMethodRef(value) => value.resolve(pool),
+                ProxyConstPoolType::String(value) => value.resolve(pool),
+                ProxyConstPoolType::Class(value) => value.resolve(pool),
+                ProxyConstPoolType::MethodHandle(value) => value.resolve(pool),
+                ProxyConstPoolType::MethodType(value) => value.resolve(pool),
+                ProxyConstPoolType::Dynamic(value) => value.resolve(pool),
+                ProxyConstPoolType::InvokeDynamic(value) => value.resolve(pool),
+                ProxyConstPoolType::Module(value) => value.resolve(pool),
+                ProxyConstPoolType::Package(value) => value.resolve(pool)
+            }
+        }
+    }
+
+    // Lists the references of an entry that do not point at the kind of entry they require, as the 1-based target
+    // index together with the expected kind.
+    pub fn invalid_references(entry: &ProxyConstPoolType, pool: &[ProxyConstPoolType]) -> Vec<(u16, &'static str)> {
+        let utf8 = |proxy: &Proxy| (proxy.0, "Utf8", matches!(pool.get(proxy.0 as usize), Some(ProxyConstPoolType::Value(ConstPoolType::Utf8(_)))));
+        let class = |index: u16| (index, "Class", matches!(pool.get(index as usize), Some(ProxyConstPoolType::Class(_))));
+        let name_and_type = |index: u16| (index, "NameAndType", matches!(pool.get(index as usize), Some(ProxyConstPoolType::NameAndType(_))));
+        let references = match entry {
+            ProxyConstPoolType::Value(_) => vec![],
+            ProxyConstPoolType::NameAndType(value) => vec![utf8(&value.name), utf8(&value.descriptor)],
+            ProxyConstPoolType::FieldRef(FieldRefProxy(proxy))
+            | ProxyConstPoolType::MethodRef(MethodRefProxy(proxy))
+            | ProxyConstPoolType::InterfaceMethodRef(InterfaceMethodRefProxy(proxy)) =>
+                vec![class(proxy.class.0), name_and_type(proxy.name_and_type.0)],
+            ProxyConstPoolType::String(StringProxy(proxy))
+            | ProxyConstPoolType::Class(ClassProxy(proxy))
+            | ProxyConstPoolType::MethodType(MethodTypeProxy(proxy))
+            | ProxyConstPoolType::Module(ModuleProxy(proxy))
+            | ProxyConstPoolType::Package(PackageProxy(proxy)) => vec![utf8(proxy)],
+            ProxyConstPoolType::Dynamic(DynamicProxy(proxy))
+            | ProxyConstPoolType::InvokeDynamic(InvokeDynamicProxy(proxy)) => vec![name_and_type(proxy.name_and_type.0)],
+            ProxyConstPoolType::MethodHandle(handle) => {
+                let target = pool.get(handle.reference.0 as usize);
+                let (expected, valid) = match handle.reference_kind {
+                    1..=4 => ("Fieldref", matches!(target, Some(ProxyConstPoolType::FieldRef(_)))),
+                    5 | 8 => ("Methodref", matches!(target, Some(ProxyConstPoolType::MethodRef(_)))),
+                    6 | 7 => ("Methodref or InterfaceMethodref",
+                        matches!(target, Some(ProxyConstPoolType::MethodRef(_) | ProxyConstPoolType::InterfaceMethodRef(_)))),
+                    9 => ("InterfaceMethodref", matches!(target, Some(ProxyConstPoolType::InterfaceMethodRef(_)))),
+                    _ => ("method handle reference of kind 1 to 9", false)
+                };
+                vec![(handle.reference.0, expected, valid)]
+            }
+        };
+        references.into_iter()
+            .filter(|(_, _, valid)| !valid)
+            .map(|(index, expected, _)| (index + 1, expected))
+            .collect()
+    }
+}
+
+pub mod deserialization {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+    use std::fmt;
+    use std::fs::File;
+    use std::io::{BufReader, Cursor, Error, ErrorKind, Read};
+    use std::path::Path;
+    use std::rc::Rc;
+    use byteorder::{BigEndian, ReadBytesExt};
+    use class::const_pool::ConstPoolType;
+    use class::const_pool::ClassInfo;
+    use class::const_pool::Utf8Info;
+    use class::const_pool::{LongInfo, DoubleInfo, FloatInfo, IntInfo};
+    use class::const_pool::{ModuleInfo, PackageInfo};
+    use class::const_pool::ConstPoolType::Utf8;
+    use super::proxy::*;
+    use class::attributes::*;
+    use class::components::*;
+    use class::{BitFlag, BitFlags};
+    use class::Class;
+
+    pub type CustomAttributeHandler = fn(name: &str, length: u32, bytes: &[u8]) -> Option<CustomAttribute>;
+
+    pub type AttributeParser = Rc<dyn Fn(&mut dyn Read, &[ConstPoolType]) -> Result<CustomAttribute, DeserializationError>>;
+
+    // Parsers for attributes the crate does not know, looked up by name before `custom_attribute_handler`.
+    // Each parser reads from the attribute payload only and may resolve indices against the constant pool.
+    #[derive(Default, Clone)]
+    pub struct AttributeRegistry {
+        parsers: HashMap<String, AttributeParser>
+    }
+
+    impl AttributeRegistry {
+        pub fn new() -> AttributeRegistry {
+            AttributeRegistry::default()
+        }
+
+        pub fn register(&mut self, name: &str, parser: impl Fn(&mut dyn Read, &[ConstPoolType]) -> Result<CustomAttribute, DeserializationError> + 'static) {
+            self.parsers.insert(name.to_string(), Rc::new(parser));
+        }
+
+        pub fn get(&self, name: &str) -> Option<&AttributeParser> {
+            self.parsers.get(name)
+        }
+    }
+
+    impl fmt::Debug for AttributeRegistry {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_set().entries(self.parsers.keys()).finish()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct StringInterner {
+        strings: RefCell<HashSet<Utf8Info>>
+    }
+
+    impl StringInterner {
+        pub fn new() -> StringInterner {
+            StringInterner::default()
+        }
+
+        pub fn intern(&self, value: Utf8Info) -> Utf8Info {
+            let mut strings = self.strings.borrow_mut();
+            if let Some(interned) = strings.get(&value) {
+                return interned.clone();
+            }
+            strings.insert(value.clone());
+            value
+        }
+
+        pub fn len(&self) -> usize {
+            self.strings.borrow().len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.strings.borrow().is_empty()
+        }
+    }
+
+    // Declared sizes are checked against these caps before any of the content is read.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ParseLimits {
+        pub max_code_len: u32,
+        pub max_pool_entries: u16,
+        pub max_attribute_len: u32
+    }
+
+    // The code length cap is the one the JVM specification sets; the others are only bounded by the format.
+    impl Default for ParseLimits {
+        fn default() -> Self {
+            ParseLimits {
+                max_code_len: 65535,
+                max_pool_entries: u16::MAX,
+                max_attribute_len: u32::MAX
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn check_limit(what: &'static str, declared: u32, limit: u32) -> Result<(), DeserializationError> {
+        if declared > limit {
+            return Err(DeserializationError::LimitExceeded {
+                what,
+                declared: declared as u64,
+                limit: limit as u64
+            });
+        }
+        Ok(())
+    }
+
+    // With `recovered_errors` set, an attribute whose content fails to parse is recorded there and kept as unknown.
+    #[derive(Debug, Default, Clone)]
+    pub struct ParseOptions {
+        pub custom_attribute_handler: Option<CustomAttributeHandler>,
+        pub attribute_registry: Option<Rc<AttributeRegistry>>,
+        pub lazy_attributes: bool,
+        pub reject_duplicate_attributes: bool,
+        pub trust_content_over_length: bool,
+        pub interner: Option<Rc<StringInterner>>,
+        pub recovered_errors: Option<Rc<RefCell<Vec<DeserializationError>>>>,
+        pub limits: ParseLimits
+    }
+
+    #[inline(always)]
+    pub(crate) fn recover(options: &ParseOptions, error: DeserializationError) -> Result<(), DeserializationError> {
+        match &options.recovered_errors {
+            Some(errors) => {
+                errors.borrow_mut().push(error);
+                Ok(())
+            }
+            None => Err(error)
+        }
+    }
+
+    // `start` is the offset at which the most recent read began, i.e. where a failing read was attempted.
+    pub(crate) struct CountingReader<R> {
+        pub(crate) inner: R,
+        pub(crate) count: u64,
+        pub(crate) start: u64
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.start = self.count;
+            let read = self.inner.read(buf)?;
+            self.count += read as u64;
+            Ok(read)
+        }
+    }
+
+    pub trait Deserializable: Sized {
+        fn deserialize(cursor: impl ReadBytesExt) -> Result<Self, DeserializationError>;
+    }
+
+    pub trait DeserializableLinked: Sized {
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Self, DeserializationError>;
+    }
+
+    pub trait DeserializableSized: Sized {
+        fn deserialize_sized(size: u32, cursor: impl ReadBytesExt) -> Result<Self, DeserializationError>;
+    }
+
+    pub trait DeserializableConfigured: Sized {
+        fn deserialize_configured(cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<Self, DeserializationError>;
+    }
+
+    pub trait DeserializableLinkedConfigured: Sized {
+        fn deserialize_link_configured(cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<Self, DeserializationError>;
+    }
+
+    #[inline(always)]
+    fn get_real_index(mut cursor: impl ReadBytesExt) -> Result<u16, DeserializationError> {
+        let index = cursor.read_u16::<BigEndian>()?;
+        if index == 0 {
+            Err(DeserializationError::Link)
+        } else {
+            Ok(index - 1)
+        }
+    }
+
+    impl Deserializable for Utf8Info {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<Utf8Info, DeserializationError> {
+            let mut data: Vec<u8> = vec![0; cursor.read_u16::<BigEndian>()? as usize];
+            cursor.read_exact(&mut data[..])?;
+            let value = mutf8::decode_modified_utf8(&data)?;
+            // Only a string not written in the canonical form holds on to its bytes.
+            Ok(if mutf8::encode_modified_utf8(&value) == data { Utf8Info::from(value) } else { Utf8Info::from_raw(value, data) })
+        }
+    }
+
+    impl Deserializable for IntInfo {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<IntInfo, DeserializationError> {
+            Ok(cursor.read_i32::<BigEndian>()?)
+        }
+    }
+
+    impl Deserializable for FloatInfo {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<FloatInfo, DeserializationError> {
+            Ok(cursor.read_f32::<BigEndian>()?)
+        }
+    }
+
+    impl Deserializable for LongInfo {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<LongInfo, DeserializationError> {
+            Ok(cursor.read_i64::<BigEndian>()?)
+        }
+    }
+
+    impl Deserializable for DoubleInfo {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<DoubleInfo, DeserializationError> {
+            Ok(cursor.read_f64::<BigEndian>()?)
+        }
+    }
+
+    impl Deserializable for NameAndTypeProxy {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<NameAndTypeProxy, DeserializationError> {
+            Ok(NameAndTypeProxy {
+                name: Proxy(get_real_index(&mut cursor)?),
+                descriptor: Proxy(get_real_index(&mut cursor)?),
+            })
+        }
+    }
+
+    impl Deserializable for Proxy {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<Proxy, DeserializationError> {
+            Ok(Proxy(get_real_index(&mut cursor)?))
+        }
+    }
+
+    impl Deserializable for DoubleProxy {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<DoubleProxy, DeserializationError> {
+            Ok(DoubleProxy {
+                class: ProxyToProxyClass(get_real_index(&mut cursor)?),
+                name_and_type: ProxyToProxyNameAndType(get_real_index(&mut cursor)?),
+            })
+        }
+    }
+
+    impl Deserializable for BootstrapProxy {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BootstrapProxy, DeserializationError> {
+            Ok(BootstrapProxy {
+                bootstrap_method_attr_index: cursor.read_u16::<BigEndian>()?,
+                name_and_type: ProxyToProxyNameAndType(get_real_index(&mut cursor)?),
+            })
+        }
+    }
+
+    impl Deserializable for MethodHandleProxy {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<MethodHandleProxy, DeserializationError> {
+            Ok(MethodHandleProxy {
+                reference_kind: cursor.read_u8()?,
+                reference: Proxy::deserialize(&mut cursor)?,
+            })
+        }
+    }
+
+    impl DeserializableConfigured for ProxyConstPoolType {
+        #[inline(always)]
+        fn deserialize_configured(mut cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<ProxyConstPoolType, DeserializationError> {
+            match cursor.read_u8()? {
+                1 => {
+                    let utf8 = Utf8Info::deserialize(&mut cursor)?;
+                    Ok(ProxyConstPoolType::Value(Utf8(match &options.interner {
+                        Some(interner) => interner.intern(utf8),
+                        None => utf8
+                    })))
+                }
+                3 => Ok(ProxyConstPoolType::Value(ConstPoolType::Int(IntInfo::deserialize(&mut cursor)?))),
+                4 => Ok(ProxyConstPoolType::Value(ConstPoolType::Float(FloatInfo::deserialize(&mut cursor)?))),
+                5 => Ok(ProxyConstPoolType::Value(ConstPoolType::Long(LongInfo::deserialize(&mut cursor)?))),
+                6 => Ok(ProxyConstPoolType::Value(ConstPoolType::Double(DoubleInfo::deserialize(&mut cursor)?))),
+                7 => Ok(ProxyConstPoolType::Class(ClassProxy(Proxy::deserialize(&mut cursor)?))),
+                8 => Ok(ProxyConstPoolType::String(StringProxy(Proxy::deserialize(&mut cursor)?))),
+                9 => Ok(ProxyConstPoolType::FieldRef(FieldRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
+                10 => Ok(ProxyConstPoolType::MethodRef(MethodRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
+                11 => Ok(ProxyConstPoolType::InterfaceMethodRef(InterfaceMethodRefProxy(DoubleProxy::deserialize(&mut cursor)?))),
+                12 => Ok(ProxyConstPoolType::NameAndType(NameAndTypeProxy::deserialize(&mut cursor)?)),
+                15 => Ok(ProxyConstPoolType::MethodHandle(MethodHandleProxy::deserialize(&mut cursor)?)),
+                16 => Ok(ProxyConstPoolType::MethodType(MethodTypeProxy(Proxy::deserialize(&mut cursor)?))),
+                17 => Ok(ProxyConstPoolType::Dynamic(DynamicProxy(BootstrapProxy::deserialize(&mut cursor)?))),
+                18 => Ok(ProxyConstPoolType::InvokeDynamic(InvokeDynamicProxy(BootstrapProxy::deserialize(&mut cursor)?))),
+                19 => Ok(ProxyConstPoolType::Module(ModuleProxy(Proxy::deserialize(&mut cursor)?))),
+                20 => Ok(ProxyConstPoolType::Package(PackageProxy(Proxy::deserialize(&mut cursor)?))),
+                unexpected => Err(DeserializationError::Parsing(format!("Invalid const pool type id: {unexpected}")))
+            }
+        }
+    }
+
+    // Reads the constant pool with its references still unresolved.
+    pub(crate) fn read_proxy_pool(mut cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<Vec<ProxyConstPoolType>, DeserializationError> {
+        let count = match cursor.read_u16::<BigEndian>()? {
+            0 => return Err(DeserializationError::Parsing("Constant pool count must be at least 1".into())),
+            count => (count - 1) as usize
+        };
+        check_limit("constant pool entry count", count as u32, options.limits.max_pool_entries as u32)?;
+        let mut proxy: Vec<ProxyConstPoolType> = Vec::with_capacity(count);
+        while proxy.len() < count {
+            let entry = ProxyConstPoolType::deserialize_configured(&mut cursor, options)?;
+            let wide = matches!(entry, ProxyConstPoolType::Value(ConstPoolType::Long(_) | ConstPoolType::Double(_)));
+            proxy.push(entry);
+            if wide {
+                if proxy.len() == count {
+                    return Err(DeserializationError::Parsing("Wide constant in the last constant pool slot".into()));
+                }
+                proxy.push(ProxyConstPoolType::Value(ConstPoolType::Placeholder));
+            }
+        }
+        Ok(proxy)
+    }
+
+    impl DeserializableConfigured for Vec<ConstPoolType> {
+        #[inline(always)]
+        fn deserialize_configured(cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<Vec<ConstPoolType>, DeserializationError> {
+            let proxy = read_proxy_pool(cursor, options)?;
+            let pool = proxy.iter()
+                .map(|p| p.resolve(&proxy))
+                .collect::<Result<Vec<ConstPoolType>, _>>()?;
+            Ok(pool)
+        }
+    }
+
+    #[inline(always)]
+    fn find_const_pool_element(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Option<&ConstPoolType>, DeserializationError> {
+        let index = cursor.read_u16::<BigEndian>()? as usize;
+        if index == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(pool.get(index - 1).ok_or(DeserializationError::Link)?))
+        }
+    }
+
+    impl DeserializableLinked for Utf8Info {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Utf8Info, DeserializationError> {
+            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
+                Utf8(info) => Ok(info.clone()),
+                _ => Err(DeserializationError::Link)
+            }
+        }
+    }
+
+    impl DeserializableLinked for ClassInfo {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ClassInfo, DeserializationError> {
+            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
+                ConstPoolType::Class(info) => Ok(info.clone()),
+                _ => Err(DeserializationError::Link)
+            }
+        }
+    }
+
+    impl DeserializableLinked for ConstValueType {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ConstValueType, DeserializationError> {
+            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
+                ConstPoolType::Long(data) => Ok(ConstValueType::Long(*data)),
+                ConstPoolType::Int(data) => Ok(ConstValueType::Int(*data)),
+                ConstPoolType::Float(data) => Ok(ConstValueType::Float(*data)),
+                ConstPoolType::Double(data) => Ok(ConstValueType::Double(*data)),
                 ConstPoolType::String(data) => Ok(ConstValueType::String(data.clone())),
                 _ => Err(DeserializationError::Link)
             }
         }
     }
 
-    impl DeserializableLinked for ConstantValueAttribute {
+    impl DeserializableLinked for ConstantValueAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ConstantValueAttribute, DeserializationError> {
+            let value = ConstValueType::deserialize_link(&mut cursor, pool)?;
+            Ok(ConstantValueAttribute {
+                value
+            })
+        }
+    }
+
+    impl DeserializableLinked for ExceptionEntry {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ExceptionEntry, DeserializationError> {
+            let start_pc = cursor.read_u16::<BigEndian>()?;
+            let end_pc = cursor.read_u16::<BigEndian>()?;
+            let handler_pc = cursor.read_u16::<BigEndian>()?;
+            let catch_type: Option<ClassInfo> = match find_const_pool_element(cursor, pool)? {
+                Some(value) => Some(match value {
+                    ConstPoolType::Class(info) => Ok(info.clone()),
+                    _ => Err(DeserializationError::Link)
+                }?),
+                None => None
+            };
+            Ok(ExceptionEntry {
+                start_pc,
+                end_pc,
+                handler_pc,
+                catch_type,
+            })
+        }
+    }
+
+    impl DeserializableLinked for SourceFileAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<SourceFileAttribute, DeserializationError> {
+            let file = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            Ok(SourceFileAttribute {
+                file,
+            })
+        }
+    }
+
+    impl DeserializableLinked for PermittedSubclassesAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<PermittedSubclassesAttribute, DeserializationError> {
+            Ok(PermittedSubclassesAttribute {
+                classes: Vec::deserialize_link(cursor, pool)?,
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModuleInfo {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleInfo, DeserializationError> {
+            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
+                ConstPoolType::Module(info) => Ok(info.clone()),
+                _ => Err(DeserializationError::Link)
+            }
+        }
+    }
+
+    impl DeserializableLinked for PackageInfo {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<PackageInfo, DeserializationError> {
+            match find_const_pool_element(cursor, pool)?.ok_or(DeserializationError::Link)? {
+                ConstPoolType::Package(info) => Ok(info.clone()),
+                _ => Err(DeserializationError::Link)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_table<T: DeserializableLinked>(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<T>, DeserializationError> {
+        let count = cursor.read_u16::<BigEndian>()?;
+        (0..count)
+            .map(|_| T::deserialize_link(&mut cursor, pool))
+            .collect::<Result<Vec<T>, DeserializationError>>()
+    }
+
+    // Version strings are optional; index 0 means the version is not recorded.
+    #[inline(always)]
+    fn deserialize_version(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Option<Utf8Info>, DeserializationError> {
+        match find_const_pool_element(cursor, pool)? {
+            Some(Utf8(version)) => Ok(Some(version.clone())),
+            Some(_) => Err(DeserializationError::Link),
+            None => Ok(None)
+        }
+    }
+
+    impl DeserializableLinked for ModuleRequires {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleRequires, DeserializationError> {
+            Ok(ModuleRequires {
+                module: ModuleInfo::deserialize_link(&mut cursor, pool)?,
+                access: BitFlags::deserialize(&mut cursor)?,
+                version: deserialize_version(&mut cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModuleExports {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleExports, DeserializationError> {
+            Ok(ModuleExports {
+                package: PackageInfo::deserialize_link(&mut cursor, pool)?,
+                access: BitFlags::deserialize(&mut cursor)?,
+                to: deserialize_table(&mut cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModuleProvides {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleProvides, DeserializationError> {
+            Ok(ModuleProvides {
+                service: ClassInfo::deserialize_link(&mut cursor, pool)?,
+                implementations: deserialize_table(&mut cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModuleAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleAttribute, DeserializationError> {
+            Ok(ModuleAttribute {
+                name: ModuleInfo::deserialize_link(&mut cursor, pool)?,
+                access: BitFlags::deserialize(&mut cursor)?,
+                version: deserialize_version(&mut cursor, pool)?,
+                requires: deserialize_table(&mut cursor, pool)?,
+                exports: deserialize_table(&mut cursor, pool)?,
+                opens: deserialize_table(&mut cursor, pool)?,
+                uses: deserialize_table(&mut cursor, pool)?,
+                provides: deserialize_table(&mut cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModulePackagesAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModulePackagesAttribute, DeserializationError> {
+            Ok(ModulePackagesAttribute {
+                packages: deserialize_table(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for ModuleMainClassAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ModuleMainClassAttribute, DeserializationError> {
+            Ok(ModuleMainClassAttribute {
+                main_class: ClassInfo::deserialize_link(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for NestHostAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<NestHostAttribute, DeserializationError> {
+            Ok(NestHostAttribute {
+                host_class: ClassInfo::deserialize_link(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for NestMembersAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<NestMembersAttribute, DeserializationError> {
+            Ok(NestMembersAttribute {
+                classes: Vec::deserialize_link(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinkedConfigured for RecordComponentInfo {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<RecordComponentInfo, DeserializationError> {
+            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            let descriptor = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            let attributes: Vec<Attribute> = Vec::deserialize_link_configured(&mut cursor, pool, options)?;
+            Ok(RecordComponentInfo {
+                name,
+                descriptor,
+                attributes,
+            })
+        }
+    }
+
+    impl DeserializableLinkedConfigured for RecordAttribute {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<RecordAttribute, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            let components = (0..count)
+                .map(|_| RecordComponentInfo::deserialize_link_configured(&mut cursor, pool, options))
+                .collect::<Result<Vec<RecordComponentInfo>, DeserializationError>>()?;
+            Ok(RecordAttribute {
+                components,
+            })
+        }
+    }
+
+    impl DeserializableLinked for BootstrapMethod {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<BootstrapMethod, DeserializationError> {
+            let method = match find_const_pool_element(&mut cursor, pool)?.ok_or(DeserializationError::Link)? {
+                ConstPoolType::MethodHandle(handle) => handle.clone(),
+                _ => return Err(DeserializationError::Link)
+            };
+            let count = cursor.read_u16::<BigEndian>()?;
+            let arguments = (0..count)
+                .map(|_| match find_const_pool_element(&mut cursor, pool)?.ok_or(DeserializationError::Link)? {
+                    ConstPoolType::Placeholder => Err(DeserializationError::Link),
+                    argument => Ok(argument.clone())
+                })
+                .collect::<Result<Vec<ConstPoolType>, DeserializationError>>()?;
+            Ok(BootstrapMethod {
+                method,
+                arguments
+            })
+        }
+    }
+
+    impl DeserializableLinked for BootstrapMethodsAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<BootstrapMethodsAttribute, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            let methods = (0..count)
+                .map(|_| BootstrapMethod::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<BootstrapMethod>, DeserializationError>>()?;
+            Ok(BootstrapMethodsAttribute {
+                methods
+            })
+        }
+    }
+
+    impl DeserializableLinked for SignatureAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<SignatureAttribute, DeserializationError> {
+            let signature = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            Ok(SignatureAttribute {
+                signature,
+            })
+        }
+    }
+
+    fn deserialize_element_value(cursor: &mut dyn Read, pool: &[ConstPoolType]) -> Result<ElementValue, DeserializationError> {
+        let tag = cursor.read_u8()?;
+        match tag {
+            b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => {
+                let descriptor = (tag as char).to_string();
+                let value = match ConstValueType::deserialize_link(&mut *cursor, pool)? {
+                    ConstValueType::Int(value) => ConstValueType::from_int(value, &descriptor),
+                    value if value.matches_descriptor(&descriptor) => Some(value),
+                    _ => None
+                };
+                Ok(ElementValue::Const(value.ok_or(DeserializationError::Link)?))
+            }
+            b's' => Ok(ElementValue::Const(ConstValueType::String(Utf8Info::deserialize_link(&mut *cursor, pool)?))),
+            b'e' => Ok(ElementValue::Enum {
+                type_name: Utf8Info::deserialize_link(&mut *cursor, pool)?,
+                const_name: Utf8Info::deserialize_link(&mut *cursor, pool)?
+            }),
+            b'c' => Ok(ElementValue::Class(Utf8Info::deserialize_link(&mut *cursor, pool)?)),
+            b'@' => Ok(ElementValue::Annotation(deserialize_annotation(cursor, pool)?)),
+            b'[' => {
+                let count = cursor.read_u16::<BigEndian>()?;
+                let values = (0..count)
+                    .map(|_| deserialize_element_value(&mut *cursor, pool))
+                    .collect::<Result<Vec<ElementValue>, DeserializationError>>()?;
+                Ok(ElementValue::Array(values))
+            }
+            unexpected => Err(DeserializationError::Parsing(format!("Invalid element value tag: {}", unexpected as char)))
+        }
+    }
+
+    fn deserialize_annotation(cursor: &mut dyn Read, pool: &[ConstPoolType]) -> Result<Annotation, DeserializationError> {
+        let type_descriptor = Utf8Info::deserialize_link(&mut *cursor, pool)?;
+        let count = cursor.read_u16::<BigEndian>()?;
+        let elements = (0..count)
+            .map(|_| Ok((Utf8Info::deserialize_link(&mut *cursor, pool)?, deserialize_element_value(&mut *cursor, pool)?)))
+            .collect::<Result<Vec<(Utf8Info, ElementValue)>, DeserializationError>>()?;
+        Ok(Annotation {
+            type_descriptor,
+            elements,
+        })
+    }
+
+    impl DeserializableLinked for Vec<Annotation> {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<Annotation>, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            let annotations = (0..count)
+                .map(|_| deserialize_annotation(&mut cursor, pool))
+                .collect::<Result<Vec<Annotation>, DeserializationError>>()?;
+            Ok(annotations)
+        }
+    }
+
+    impl DeserializableLinked for AnnotationDefaultAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<AnnotationDefaultAttribute, DeserializationError> {
+            Ok(AnnotationDefaultAttribute {
+                value: deserialize_element_value(&mut cursor, pool)?,
+            })
+        }
+    }
+
+    impl DeserializableLinked for RuntimeVisibleAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeVisibleAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeVisibleAnnotationsAttribute {
+                annotations: Vec::deserialize_link(cursor, pool)?,
+            })
+        }
+    }
+
+    impl DeserializableLinked for RuntimeInvisibleAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeInvisibleAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeInvisibleAnnotationsAttribute {
+                annotations: Vec::deserialize_link(cursor, pool)?,
+            })
+        }
+    }
+
+    // num_parameters is a single byte, unlike the u16 counts elsewhere.
+    fn deserialize_parameter_annotations(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<Vec<Annotation>>, DeserializationError> {
+        let count = cursor.read_u8()?;
+        (0..count)
+            .map(|_| Vec::deserialize_link(&mut cursor, pool))
+            .collect::<Result<Vec<Vec<Annotation>>, DeserializationError>>()
+    }
+
+    impl DeserializableLinked for RuntimeVisibleParameterAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeVisibleParameterAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeVisibleParameterAnnotationsAttribute {
+                parameters: deserialize_parameter_annotations(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for RuntimeInvisibleParameterAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeInvisibleParameterAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeInvisibleParameterAnnotationsAttribute {
+                parameters: deserialize_parameter_annotations(cursor, pool)?
+            })
+        }
+    }
+
+    fn deserialize_target_info(target_type: u8, mut cursor: impl ReadBytesExt) -> Result<TargetInfo, DeserializationError> {
+        Ok(match target_type {
+            0x00 | 0x01 => TargetInfo::TypeParameter {
+                index: cursor.read_u8()?
+            },
+            0x10 => TargetInfo::Supertype {
+                index: cursor.read_u16::<BigEndian>()?
+            },
+            0x11 | 0x12 => TargetInfo::TypeParameterBound {
+                type_parameter_index: cursor.read_u8()?,
+                bound_index: cursor.read_u8()?
+            },
+            0x13..=0x15 => TargetInfo::Empty,
+            0x16 => TargetInfo::FormalParameter {
+                index: cursor.read_u8()?
+            },
+            0x17 => TargetInfo::Throws {
+                index: cursor.read_u16::<BigEndian>()?
+            },
+            0x40 | 0x41 => {
+                let count = cursor.read_u16::<BigEndian>()?;
+                TargetInfo::LocalVariable((0..count)
+                    .map(|_| Ok(LocalVariableTarget {
+                        start_pc: cursor.read_u16::<BigEndian>()?,
+                        length: cursor.read_u16::<BigEndian>()?,
+                        index: cursor.read_u16::<BigEndian>()?
+                    }))
+                    .collect::<Result<Vec<LocalVariableTarget>, DeserializationError>>()?)
+            }
+            0x42 => TargetInfo::Catch {
+                exception_table_index: cursor.read_u16::<BigEndian>()?
+            },
+            0x43..=0x46 => TargetInfo::Offset(cursor.read_u16::<BigEndian>()?),
+            0x47..=0x4B => TargetInfo::TypeArgument {
+                offset: cursor.read_u16::<BigEndian>()?,
+                type_argument_index: cursor.read_u8()?
+            },
+            unexpected => return Err(DeserializationError::Parsing(format!("Invalid type annotation target type: {unexpected:#04x}")))
+        })
+    }
+
+    fn deserialize_type_annotation(cursor: &mut dyn Read, pool: &[ConstPoolType]) -> Result<TypeAnnotation, DeserializationError> {
+        let target_type = cursor.read_u8()?;
+        let target_info = deserialize_target_info(target_type, &mut *cursor)?;
+        let length = cursor.read_u8()?;
+        let target_path = (0..length)
+            .map(|_| Ok(TypePathEntry {
+                kind: cursor.read_u8()?,
+                type_argument_index: cursor.read_u8()?
+            }))
+            .collect::<Result<Vec<TypePathEntry>, DeserializationError>>()?;
+        Ok(TypeAnnotation {
+            target_type,
+            target_info,
+            target_path,
+            annotation: deserialize_annotation(cursor, pool)?
+        })
+    }
+
+    impl DeserializableLinked for Vec<TypeAnnotation> {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<TypeAnnotation>, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            (0..count)
+                .map(|_| deserialize_type_annotation(&mut cursor, pool))
+                .collect::<Result<Vec<TypeAnnotation>, DeserializationError>>()
+        }
+    }
+
+    impl DeserializableLinked for RuntimeVisibleTypeAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeVisibleTypeAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeVisibleTypeAnnotationsAttribute {
+                annotations: Vec::deserialize_link(cursor, pool)?
+            })
+        }
+    }
+
+    impl DeserializableLinked for RuntimeInvisibleTypeAnnotationsAttribute {
+        #[inline(always)]
+        fn deserialize_link(cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<RuntimeInvisibleTypeAnnotationsAttribute, DeserializationError> {
+            Ok(RuntimeInvisibleTypeAnnotationsAttribute {
+                annotations: Vec::deserialize_link(cursor, pool)?
+            })
+        }
+    }
+
+    #[inline(always)]
+    // The buffer grows with the data actually read, so a forged size fails at the end of the stream instead of
+    // allocating up front.
+    fn read_attribute_bytes(size: u32, cursor: impl ReadBytesExt) -> Result<Vec<u8>, DeserializationError> {
+        let mut buffer = vec![];
+        cursor.take(size as u64).read_to_end(&mut buffer)?;
+        if buffer.len() != size as usize {
+            return Err(DeserializationError::CannotRead(Error::from(ErrorKind::UnexpectedEof)));
+        }
+        Ok(buffer)
+    }
+
+    impl DeserializableSized for SyntheticAttribute {
+        #[inline(always)]
+        fn deserialize_sized(size: u32, _: impl ReadBytesExt) -> Result<SyntheticAttribute, DeserializationError> {
+            if size != 0 {
+                return Err(DeserializationError::Parsing("Synthetic attribute must be zero-sized".into()));
+            }
+            Ok(SyntheticAttribute {})
+        }
+    }
+
+    impl DeserializableSized for DeprecatedAttribute {
+        #[inline(always)]
+        fn deserialize_sized(size: u32, _: impl ReadBytesExt) -> Result<DeprecatedAttribute, DeserializationError> {
+            if size != 0 {
+                return Err(DeserializationError::Parsing("Deprecated attribute must be zero-sized".into()));
+            }
+            Ok(DeprecatedAttribute {})
+        }
+    }
+
+    impl DeserializableLinked for LocalVariableEntry {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableEntry, DeserializationError> {
+            Ok(LocalVariableEntry {
+                start_pc: cursor.read_u16::<BigEndian>()?,
+                length: cursor.read_u16::<BigEndian>()?,
+                name: Utf8Info::deserialize_link(&mut cursor, pool)?,
+                descriptor: Utf8Info::deserialize_link(&mut cursor, pool)?,
+                index: cursor.read_u16::<BigEndian>()?,
+            })
+        }
+    }
+
+    impl DeserializableLinked for LocalVariableTypeEntry {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableTypeEntry, DeserializationError> {
+            Ok(LocalVariableTypeEntry {
+                start_pc: cursor.read_u16::<BigEndian>()?,
+                length: cursor.read_u16::<BigEndian>()?,
+                name: Utf8Info::deserialize_link(&mut cursor, pool)?,
+                signature: Utf8Info::deserialize_link(&mut cursor, pool)?,
+                index: cursor.read_u16::<BigEndian>()?
+            })
+        }
+    }
+
+    impl DeserializableLinked for VerificationType {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<VerificationType, DeserializationError> {
+            match cursor.read_u8()? {
+                0 => Ok(VerificationType::Top),
+                1 => Ok(VerificationType::Integer),
+                2 => Ok(VerificationType::Float),
+                3 => Ok(VerificationType::Double),
+                4 => Ok(VerificationType::Long),
+                5 => Ok(VerificationType::Null),
+                6 => Ok(VerificationType::UninitializedThis),
+                7 => Ok(VerificationType::Object(ClassInfo::deserialize_link(cursor, pool)?)),
+                8 => Ok(VerificationType::Uninitialized(cursor.read_u16::<BigEndian>()?)),
+                unexpected => Err(DeserializationError::Parsing(format!("Invalid verification type tag: {unexpected}")))
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn deserialize_verification_types(count: u16, mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<VerificationType>, DeserializationError> {
+        (0..count)
+            .map(|_| VerificationType::deserialize_link(&mut cursor, pool))
+            .collect()
+    }
+
+    impl DeserializableLinked for StackMapFrame {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<StackMapFrame, DeserializationError> {
+            let frame_type = cursor.read_u8()?;
+            match frame_type {
+                0..=63 => Ok(StackMapFrame::Same {
+                    offset_delta: frame_type as u16
+                }),
+                64..=127 => Ok(StackMapFrame::SameLocals1StackItem {
+                    offset_delta: frame_type as u16 - 64,
+                    stack: VerificationType::deserialize_link(cursor, pool)?
+                }),
+                247 => Ok(StackMapFrame::SameLocals1StackItemExtended {
+                    offset_delta: cursor.read_u16::<BigEndian>()?,
+                    stack: VerificationType::deserialize_link(cursor, pool)?
+                }),
+                248..=250 => Ok(StackMapFrame::Chop {
+                    offset_delta: cursor.read_u16::<BigEndian>()?,
+                    count: 251 - frame_type
+                }),
+                251 => Ok(StackMapFrame::SameExtended {
+                    offset_delta: cursor.read_u16::<BigEndian>()?
+                }),
+                252..=254 => Ok(StackMapFrame::Append {
+                    offset_delta: cursor.read_u16::<BigEndian>()?,
+                    locals: deserialize_verification_types(frame_type as u16 - 251, cursor, pool)?
+                }),
+                255 => {
+                    let offset_delta = cursor.read_u16::<BigEndian>()?;
+                    let locals_count = cursor.read_u16::<BigEndian>()?;
+                    let locals = deserialize_verification_types(locals_count, &mut cursor, pool)?;
+                    let stack_count = cursor.read_u16::<BigEndian>()?;
+                    Ok(StackMapFrame::Full {
+                        offset_delta,
+                        locals,
+                        stack: deserialize_verification_types(stack_count, &mut cursor, pool)?
+                    })
+                }
+                reserved => Err(DeserializationError::Parsing(format!("Reserved stack map frame type: {reserved}")))
+            }
+        }
+    }
+
+    impl DeserializableLinked for StackMapTableAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<StackMapTableAttribute, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            let frames = (0..count)
+                .map(|_| StackMapFrame::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<StackMapFrame>, DeserializationError>>()?;
+            Ok(StackMapTableAttribute {
+                frames
+            })
+        }
+    }
+
+    impl DeserializableLinked for LocalVariableTableAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableTableAttribute, DeserializationError> {
+            let length = cursor.read_u16::<BigEndian>()?;
+            let variables = (0..length)
+                .map(|_| LocalVariableEntry::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<LocalVariableEntry>, DeserializationError>>()?;
+            Ok(LocalVariableTableAttribute {
+                variables
+            })
+        }
+    }
+
+    impl DeserializableLinked for LocalVariableTypeTableAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableTypeTableAttribute, DeserializationError> {
+            let length = cursor.read_u16::<BigEndian>()?;
+            let variables = (0..length)
+                .map(|_| LocalVariableTypeEntry::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<LocalVariableTypeEntry>, DeserializationError>>()?;
+            Ok(LocalVariableTypeTableAttribute {
+                variables
+            })
+        }
+    }
+
+    impl Deserializable for LineNumberEntry {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<LineNumberEntry, DeserializationError> {
+            Ok(LineNumberEntry {
+                start_pc: cursor.read_u16::<BigEndian>()?,
+                line: cursor.read_u16::<BigEndian>()?,
+            })
+        }
+    }
+
+    impl Deserializable for LineNumberTableAttribute {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<LineNumberTableAttribute, DeserializationError> {
+            let length = cursor.read_u16::<BigEndian>()?;
+            let lines = (0..length)
+                .map(|_| LineNumberEntry::deserialize(&mut cursor))
+                .collect::<Result<Vec<LineNumberEntry>, DeserializationError>>()?;
+            Ok(LineNumberTableAttribute {
+                lines
+            })
+        }
+    }
+
+    impl DeserializableLinked for ExceptionsAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ExceptionsAttribute, DeserializationError> {
+            let length = cursor.read_u16::<BigEndian>()?;
+            let exceptions_classes = (0..length)
+                .map(|_| ClassInfo::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<ClassInfo>, DeserializationError>>()?;
+            Ok(ExceptionsAttribute {
+                exceptions_classes
+            })
+        }
+    }
+
+    impl Deserializable for BitFlags<AccessSpecifier> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<AccessSpecifier>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<MethodAccessSpecifier> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<MethodAccessSpecifier>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<ClassAccessSpecifier> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<ClassAccessSpecifier>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<ModuleAccess> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<ModuleAccess>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<RequiresAccess> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<RequiresAccess>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<ExportsAccess> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<ExportsAccess>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl Deserializable for BitFlags<ClassAccess> {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<BitFlags<ClassAccess>, DeserializationError> {
+            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
+                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        }
+    }
+
+    impl DeserializableLinked for ClassEntry {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<ClassEntry, DeserializationError> {
+            let inner_class_info: Option<ClassInfo> = ClassInfo::deserialize_link(&mut cursor, pool).ok();
+            let outer_class_info: Option<ClassInfo> = ClassInfo::deserialize_link(&mut cursor, pool).ok();
+            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            let access: BitFlags<ClassAccessSpecifier> = BitFlags::deserialize(&mut cursor)?;
+            Ok(ClassEntry {
+                inner_class_info,
+                outer_class_info,
+                name,
+                access,
+            })
+        }
+    }
+
+    impl DeserializableLinked for InnerClassesAttribute {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<InnerClassesAttribute, DeserializationError> {
+            let length = cursor.read_u16::<BigEndian>()?;
+            let classes = (0..length)
+                .map(|_| ClassEntry::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<ClassEntry>, DeserializationError>>()?;
+            Ok(InnerClassesAttribute {
+                classes
+            })
+        }
+    }
+
+    impl DeserializableLinkedConfigured for CodeAttribute {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<CodeAttribute, DeserializationError> {
+            let max_stack = cursor.read_u16::<BigEndian>()?;
+            let max_local = cursor.read_u16::<BigEndian>()?;
+            let code_length = cursor.read_u32::<BigEndian>()?;
+            if code_length == 0 {
+                return Err(DeserializationError::Link);
+            }
+            check_limit("code length", code_length, options.limits.max_code_len)?;
+            let code = (0..code_length)
+                .map(|_| cursor.read_u8())
+                .collect::<Result<Vec<u8>, Error>>()?;
+            let exception_table_length = cursor.read_u16::<BigEndian>()?;
+            let exceptions = (0..exception_table_length)
+                .map(|_| ExceptionEntry::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<ExceptionEntry>, DeserializationError>>()?;
+            let attributes: Vec<CodeAttributes> = Vec::deserialize_link_configured(&mut cursor, pool, options)?;
+            Ok(CodeAttribute {
+                max_stack,
+                max_local,
+                code,
+                exceptions,
+                attributes,
+            })
+        }
+    }
+
+    const ATTRIBUTE_ALIASES: [(&str, &str); 1] = [
+        // JDK 15 early-access builds of sealed classes
+        ("PermittedSubtypes", "PermittedSubclasses"),
+    ];
+
+    pub fn canonical_attribute_name(name: &str) -> &str {
+        ATTRIBUTE_ALIASES.iter()
+            .find(|(alias, _)| *alias == name)
+            .map_or(name, |(_, canonical)| canonical)
+    }
+
+    #[inline(always)]
+    fn deserialize_unknown(name: &Utf8Info, size: u32, cursor: impl ReadBytesExt) -> Result<UnknownAttribute, DeserializationError> {
+        Ok(UnknownAttribute {
+            name: name.clone(),
+            size,
+            data: read_attribute_bytes(size, cursor)?
+        })
+    }
+
+    #[inline(always)]
+    fn deserialize_unrecognized(name: &Utf8Info, size: u32, cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                options: &ParseOptions) -> Result<Attribute, DeserializationError> {
+        let unknown = deserialize_unknown(name, size, cursor)?;
+        let registered = options.attribute_registry.as_ref().and_then(|registry| registry.get(canonical_attribute_name(name)));
+        let value = match registered {
+            Some(parser) => Some(parser(&mut &unknown.data[..], pool)?),
+            None => options.custom_attribute_handler.and_then(|handler| handler(canonical_attribute_name(name), size, &unknown.data))
+        };
+        Ok(match value {
+            Some(value) => Attribute::Custom { name: unknown.name, data: unknown.data, value },
+            None => Attribute::Unknown(unknown)
+        })
+    }
+
+    // Recovery relies on the declared length to skip the attribute, so it does not apply to trusted content.
+    #[inline(always)]
+    fn deserialize_attribute_body<T>(name: &Utf8Info, mut cursor: impl ReadBytesExt, options: &ParseOptions,
+                                     parse: impl FnOnce(u32, &mut dyn Read) -> Result<T, DeserializationError>,
+                                     unknown: impl FnOnce(UnknownAttribute) -> T) -> Result<T, DeserializationError> {
+        let size = cursor.read_u32::<BigEndian>()?;
+        check_limit("attribute length", size, options.limits.max_attribute_len)?;
+        if options.trust_content_over_length {
+            let mut counting = CountingReader {
+                inner: &mut cursor,
+                count: 0,
+                start: 0
+            };
+            let value = parse(size, &mut counting)?;
+            if counting.count != size as u64 {
+                log::warn!("Attribute `{name}` declares {size} bytes but its content takes {}", counting.count);
+            }
+            return Ok(value);
+        }
+        let buffer = read_attribute_bytes(size, &mut cursor)?;
+        let mut body = &buffer[..];
+        let result = parse(size, &mut body).and_then(|value| if body.is_empty() {
+            Ok(value)
+        } else {
+            Err(DeserializationError::Parsing(format!(
+                "Attribute `{name}` declares {size} bytes but its content takes {}", buffer.len() - body.len())))
+        });
+        match result {
+            Ok(value) => Ok(value),
+            Err(error) => {
+                recover(options, error)?;
+                Ok(unknown(UnknownAttribute {
+                    name: name.clone(),
+                    size,
+                    data: buffer
+                }))
+            }
+        }
+    }
+
+    // Payloads that metadata-only readers rarely need; `lazy_attributes` keeps them as raw bytes.
+    const DEFERRED_ATTRIBUTES: [&str; 8] = ["Code", "AnnotationDefault", "RuntimeVisibleAnnotations", "RuntimeInvisibleAnnotations",
+        "RuntimeVisibleParameterAnnotations", "RuntimeInvisibleParameterAnnotations", "RuntimeVisibleTypeAnnotations",
+        "RuntimeInvisibleTypeAnnotations"];
+
+    fn deserialize_named_attribute(raw_name: &Utf8Info, size: u32, cursor: &mut dyn Read, pool: &[ConstPoolType],
+                                   options: &ParseOptions) -> Result<Attribute, DeserializationError> {
+        match canonical_attribute_name(raw_name) {
+            "Synthetic" => Ok(Attribute::Synthetic(SyntheticAttribute::deserialize_sized(size, cursor)?)),
+            "SourceFile" => Ok(Attribute::SourceFile(SourceFileAttribute::deserialize_link(cursor, pool)?)),
+            "LineNumberTable" => Ok(Attribute::LineNumberTable(LineNumberTableAttribute::deserialize(cursor)?)),
+            "LocalVariableTable" => Ok(Attribute::LocalVariableTable(LocalVariableTableAttribute::deserialize_link(cursor, pool)?)),
+            "Deprecated" => Ok(Attribute::Deprecated(DeprecatedAttribute::deserialize_sized(size, cursor)?)),
+            "InnerClasses" => Ok(Attribute::InnerClasses(InnerClassesAttribute::deserialize_link(cursor, pool)?)),
+            "Exceptions" => Ok(Attribute::Exceptions(ExceptionsAttribute::deserialize_link(cursor, pool)?)),
+            "Code" => Ok(Attribute::Code(CodeAttribute::deserialize_link_configured(cursor, pool, options)?)),
+            "ConstantValue" => Ok(Attribute::ConstantValue(ConstantValueAttribute::deserialize_link(cursor, pool)?)),
+            "Signature" => Ok(Attribute::Signature(SignatureAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeVisibleAnnotations" => Ok(Attribute::RuntimeVisibleAnnotations(RuntimeVisibleAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            "PermittedSubclasses" => Ok(Attribute::PermittedSubclasses(PermittedSubclassesAttribute::deserialize_link(cursor, pool)?)),
+            "NestHost" => Ok(Attribute::NestHost(NestHostAttribute::deserialize_link(cursor, pool)?)),
+            "NestMembers" => Ok(Attribute::NestMembers(NestMembersAttribute::deserialize_link(cursor, pool)?)),
+            "Module" => Ok(Attribute::Module(ModuleAttribute::deserialize_link(cursor, pool)?)),
+            "ModulePackages" => Ok(Attribute::ModulePackages(ModulePackagesAttribute::deserialize_link(cursor, pool)?)),
+            "ModuleMainClass" => Ok(Attribute::ModuleMainClass(ModuleMainClassAttribute::deserialize_link(cursor, pool)?)),
+            "AnnotationDefault" => Ok(Attribute::AnnotationDefault(AnnotationDefaultAttribute::deserialize_link(cursor, pool)?)),
+            "Record" => Ok(Attribute::Record(RecordAttribute::deserialize_link_configured(cursor, pool, options)?)),
+            "BootstrapMethods" => Ok(Attribute::BootstrapMethods(BootstrapMethodsAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeInvisibleAnnotations" => Ok(Attribute::RuntimeInvisibleAnnotations(RuntimeInvisibleAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeVisibleParameterAnnotations" =>
+                Ok(Attribute::RuntimeVisibleParameterAnnotations(RuntimeVisibleParameterAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeInvisibleParameterAnnotations" =>
+                Ok(Attribute::RuntimeInvisibleParameterAnnotations(RuntimeInvisibleParameterAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeVisibleTypeAnnotations" =>
+                Ok(Attribute::RuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            "RuntimeInvisibleTypeAnnotations" =>
+                Ok(Attribute::RuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+            _ => deserialize_unrecognized(raw_name, size, cursor, pool, options)
+        }
+    }
+
+    impl DeserializableLinkedConfigured for Attribute {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<Attribute, DeserializationError> {
+            let raw_name = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            if options.lazy_attributes && DEFERRED_ATTRIBUTES.contains(&canonical_attribute_name(&raw_name)) {
+                let size = cursor.read_u32::<BigEndian>()?;
+                check_limit("attribute length", size, options.limits.max_attribute_len)?;
+                return Ok(Attribute::Unknown(deserialize_unknown(&raw_name, size, cursor)?));
+            }
+            deserialize_attribute_body(&raw_name, &mut cursor, options,
+                                       |size, cursor| deserialize_named_attribute(&raw_name, size, cursor, pool, options), Attribute::Unknown)
+        }
+    }
+
+    // Parses an attribute kept as raw bytes, e.g. one deferred by `lazy_attributes`, against the pool of its class.
+    pub fn decode_attribute(attribute: &UnknownAttribute, pool: &[ConstPoolType]) -> Result<Attribute, DeserializationError> {
+        let mut body = &attribute.data[..];
+        let decoded = deserialize_named_attribute(&attribute.name, attribute.size, &mut body, pool, &ParseOptions::default())?;
+        if !body.is_empty() {
+            return Err(DeserializationError::Parsing(format!("Attribute `{}` declares {} bytes but its content takes {}",
+                attribute.name, attribute.size, attribute.data.len() - body.len())));
+        }
+        Ok(decoded)
+    }
+
+    pub trait LazyAttributes {
+        fn decoded_attributes(&self, class: &Class) -> Result<Vec<Attribute>, DeserializationError>;
+        fn load_code(&self, class: &Class) -> Result<Option<CodeAttribute>, DeserializationError>;
+    }
+
+    impl<Access: BitFlag<Numeric = u16>> LazyAttributes for ComponentInfo<Access> {
+        fn decoded_attributes(&self, class: &Class) -> Result<Vec<Attribute>, DeserializationError> {
+            self.attributes.iter()
+                .map(|attribute| match attribute {
+                    Attribute::Unknown(unknown) if DEFERRED_ATTRIBUTES.contains(&canonical_attribute_name(&unknown.name)) =>
+                        decode_attribute(unknown, &class.const_pool),
+                    attribute => Ok(attribute.clone())
+                })
+                .collect()
+        }
+
+        fn load_code(&self, class: &Class) -> Result<Option<CodeAttribute>, DeserializationError> {
+            for attribute in &self.attributes {
+                match attribute {
+                    Attribute::Code(code) => return Ok(Some(code.clone())),
+                    Attribute::Unknown(unknown) if unknown.name.as_str() == "Code" => {
+                        if let Attribute::Code(code) = decode_attribute(unknown, &class.const_pool)? {
+                            return Ok(Some(code));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(None)
+        }
+    }
+
+    impl DeserializableLinkedConfigured for CodeAttributes {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<CodeAttributes, DeserializationError> {
+            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            deserialize_attribute_body(&name, &mut cursor, options, |size, cursor| match name.as_str() {
+                "LineNumberTable" => Ok(CodeAttributes::LineNumberTable(LineNumberTableAttribute::deserialize(cursor)?)),
+                "LocalVariableTable" => Ok(CodeAttributes::LocalVariableTable(LocalVariableTableAttribute::deserialize_link(cursor, pool)?)),
+                "LocalVariableTypeTable" =>
+                    Ok(CodeAttributes::LocalVariableTypeTable(LocalVariableTypeTableAttribute::deserialize_link(cursor, pool)?)),
+                "StackMapTable" => Ok(CodeAttributes::StackMapTable(StackMapTableAttribute::deserialize_link(cursor, pool)?)),
+                "RuntimeVisibleTypeAnnotations" =>
+                    Ok(CodeAttributes::RuntimeVisibleTypeAnnotations(RuntimeVisibleTypeAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+                "RuntimeInvisibleTypeAnnotations" =>
+                    Ok(CodeAttributes::RuntimeInvisibleTypeAnnotations(RuntimeInvisibleTypeAnnotationsAttribute::deserialize_link(cursor, pool)?)),
+                _ => Ok(CodeAttributes::Unknown(deserialize_unknown(&name, size, cursor)?))
+            }, CodeAttributes::Unknown)
+        }
+    }
+
+    impl DeserializableLinkedConfigured for Vec<Attribute> {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<Vec<Attribute>, DeserializationError> {
+            let attributes_count = cursor.read_u16::<BigEndian>()?;
+            let attributes = (0..attributes_count)
+                .map(|_| Attribute::deserialize_link_configured(&mut cursor, pool, options))
+                .collect::<Result<Vec<Attribute>, DeserializationError>>()?;
+            Ok(attributes)
+        }
+    }
+
+    impl DeserializableLinkedConfigured for Vec<CodeAttributes> {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<Vec<CodeAttributes>, DeserializationError> {
+            let attributes_count = cursor.read_u16::<BigEndian>()?;
+            let attributes = (0..attributes_count)
+                .map(|_| CodeAttributes::deserialize_link_configured(&mut cursor, pool, options))
+                .collect::<Result<Vec<CodeAttributes>, DeserializationError>>()?;
+            Ok(attributes)
+        }
+    }
+
+    impl<Access: BitFlag> DeserializableLinkedConfigured for ComponentInfo<Access> where BitFlags<Access>: Deserializable {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<ComponentInfo<Access>, DeserializationError> {
+            let access = BitFlags::deserialize(&mut cursor)?;
+            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            let descriptor = Utf8Info::deserialize_link(&mut cursor, pool)?;
+            let attributes: Vec<Attribute> = Vec::deserialize_link_configured(&mut cursor, pool, options)?;
+            Ok(ComponentInfo {
+                access,
+                name,
+                descriptor,
+                attributes,
+            })
+        }
+    }
+
+    // JVMS allows at most one of every predefined attribute on an owner, except these.
+    const REPEATABLE_ATTRIBUTES: [&str; 5] = ["LineNumberTable", "LocalVariableTable", "LocalVariableTypeTable", "Synthetic", "Deprecated"];
+
+    #[inline(always)]
+    fn check_name(seen: &mut HashSet<String>, name: &str, owner: impl Fn() -> String) -> Result<(), DeserializationError> {
+        if !REPEATABLE_ATTRIBUTES.contains(&name) && !seen.insert(name.to_string()) {
+            return Err(DeserializationError::DuplicateAttribute {
+                attribute: name.to_string(),
+                owner: owner()
+            });
+        }
+        Ok(())
+    }
+
+    // `seen` holds the names read so far on one owner, so attributes can be checked as they are streamed. The
+    // attributes of a Code attribute and of record components are owned by those and checked on their own.
+    pub(crate) fn check_duplicate(seen: &mut HashSet<String>, attribute: &Attribute, owner: impl Fn() -> String,
+                                  options: &ParseOptions) -> Result<(), DeserializationError> {
+        if !options.reject_duplicate_attributes {
+            return Ok(());
+        }
+        match attribute {
+            Attribute::Unknown(unknown) if !DEFERRED_ATTRIBUTES.contains(&canonical_attribute_name(&unknown.name)) => return Ok(()),
+            Attribute::Code(code) => {
+                let mut nested = HashSet::new();
+                for name in code.attributes.iter().filter(|attribute| !matches!(attribute, CodeAttributes::Unknown(_))).map(CodeAttributes::name) {
+                    check_name(&mut nested, name, || format!("Code of {}", owner()))?;
+                }
+            }
+            Attribute::Record(record) => check_record_components(record, options)?,
+            _ => {}
+        }
+        check_name(seen, attribute.name(), owner)
+    }
+
+    fn check_record_components(record: &RecordAttribute, options: &ParseOptions) -> Result<(), DeserializationError> {
+        record.components.iter()
+            .try_for_each(|component| check_duplicates(&component.attributes, || format!("record component `{}`", component.name), options))
+    }
+
+    pub(crate) fn check_duplicates(attributes: &[Attribute], owner: impl Fn() -> String, options: &ParseOptions) -> Result<(), DeserializationError> {
+        let mut seen = HashSet::new();
+        attributes.iter().try_for_each(|attribute| check_duplicate(&mut seen, attribute, &owner, options))
+    }
+
+    #[inline(always)]
+    pub(crate) fn link_constant_value(field: &mut FieldInfo) -> Result<(), DeserializationError> {
+        for attribute in &mut field.attributes {
+            if let Attribute::ConstantValue(constant) = attribute {
+                if !constant.value.matches_descriptor(&field.descriptor) {
+                    return Err(DeserializationError::Parsing(format!(
+                        "Constant value {:?} does not match type {} of field {}", constant.value, field.descriptor, field.name)));
+                }
+                // A value that does not fit the field type stays an Int, so it is written back as it was read.
+                if let ConstValueType::Int(value) = constant.value {
+                    if let Some(narrowed) = ConstValueType::from_int(value, &field.descriptor) {
+                        constant.value = narrowed;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    impl<Access: BitFlag> DeserializableLinkedConfigured for Vec<ComponentInfo<Access>> where BitFlags<Access>: Deserializable {
+        #[inline(always)]
+        fn deserialize_link_configured(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType],
+                                       options: &ParseOptions) -> Result<Vec<ComponentInfo<Access>>, DeserializationError> {
+            let components_count = cursor.read_u16::<BigEndian>()?;
+            let components = (0..components_count)
+                .map(|_| ComponentInfo::deserialize_link_configured(&mut cursor, pool, options))
+                .collect::<Result<Vec<ComponentInfo<Access>>, DeserializationError>>()?;
+            Ok(components)
+        }
+    }
+
+    impl Deserializable for ClassVersion {
+        #[inline(always)]
+        fn deserialize(mut cursor: impl ReadBytesExt) -> Result<ClassVersion, DeserializationError> {
+            Ok(ClassVersion {
+                minor: cursor.read_u16::<BigEndian>()?,
+                major: cursor.read_u16::<BigEndian>()?,
+            })
+        }
+    }
+
+    impl DeserializableLinked for Vec<ClassInfo> {
+        #[inline(always)]
+        fn deserialize_link(mut cursor: impl ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<ClassInfo>, DeserializationError> {
+            let count = cursor.read_u16::<BigEndian>()?;
+            let classes = (0..count)
+                .map(|_| ClassInfo::deserialize_link(&mut cursor, pool))
+                .collect::<Result<Vec<ClassInfo>, DeserializationError>>()?;
+            Ok(classes)
+        }
+    }
+
+    impl DeserializableConfigured for Class {
+        #[inline(always)]
+        fn deserialize_configured(mut cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<Class, DeserializationError> {
+            let magick = cursor.read_u32::<BigEndian>()?;
+            if magick != 0xCAFEBABE {
+                return Err(DeserializationError::Parsing("Its not JVM class file.".into()));
+            }
+            let version = ClassVersion::deserialize(&mut cursor)?;
+            let const_pool: Vec<ConstPoolType> = Vec::deserialize_configured(&mut cursor, options)?;
+            let access: BitFlags<ClassAccess> = BitFlags::deserialize(&mut cursor)?;
+            let this_class = ClassInfo::deserialize_link(&mut cursor, &const_pool)?;
+            let super_class = ClassInfo::deserialize_link(&mut cursor, &const_pool).ok();
+            let interfaces: Vec<ClassInfo> = Vec::deserialize_link(&mut cursor, &const_pool)?;
+            let mut fields: Vec<FieldInfo> = Vec::deserialize_link_configured(&mut cursor, &const_pool, options)?;
+            for field in &mut fields {
+                check_duplicates(&field.attributes, || format!("field `{}`", field.name), options)?;
+                if let Err(error) = link_constant_value(field) {
+                    recover(options, error)?;
+                }
+            }
+            let methods: Vec<MethodInfo> = Vec::deserialize_link_configured(&mut cursor, &const_pool, options)?;
+            for method in &methods {
+                check_duplicates(&method.attributes, || format!("method `{}{}`", method.name, method.descriptor), options)?;
+            }
+            let attributes: Vec<Attribute> = Vec::deserialize_link_configured(&mut cursor, &const_pool, options)?;
+            check_duplicates(&attributes, || format!("class `{}`", this_class.0), options)?;
+            Ok(Class {
+                version,
+                const_pool,
+                access,
+                this_class,
+                super_class,
+                interfaces,
+                fields,
+                methods,
+                attributes,
+            })
+        }
+    }
+
+    pub fn deserializable_class(cursor: impl ReadBytesExt) -> Result<Class, DeserializationError> {
+        deserializable_class_with_options(cursor, &ParseOptions::default())
+    }
+
+    pub fn deserializable_class_with_limits(cursor: impl ReadBytesExt, limits: ParseLimits) -> Result<Class, DeserializationError> {
+        deserializable_class_with_options(cursor, &ParseOptions {
+            limits,
+            ..ParseOptions::default()
+        })
+    }
+
+    pub fn deserializable_class_with_options(cursor: impl ReadBytesExt, options: &ParseOptions) -> Result<Class, DeserializationError> {
+        let mut counting = CountingReader {
+            inner: cursor,
+            count: 0,
+            start: 0
+        };
+        Class::deserialize_configured(&mut counting, options).map_err(|error| DeserializationError::AtOffset {
+            offset: counting.start,
+            source: Box::new(error)
+        })
+    }
+
+    // Attributes that fail to parse are kept as unknown and their errors are returned alongside the class; an error
+    // elsewhere in the class ends parsing and is returned last.
+    pub fn deserializable_class_lenient(cursor: impl ReadBytesExt) -> (Option<Class>, Vec<DeserializationError>) {
+        let errors = Rc::new(RefCell::new(vec![]));
+        let options = ParseOptions {
+            recovered_errors: Some(errors.clone()),
+            ..ParseOptions::default()
+        };
+        let class = deserializable_class_with_options(cursor, &options);
+        let mut errors = errors.take();
+        match class {
+            Ok(class) => (Some(class), errors),
+            Err(error) => {
+                errors.push(error);
+                (None, errors)
+            }
+        }
+    }
+
+    /// Parses a class file from any reader. The stream is consumed sequentially, so it does not have to be
+    /// buffered or seekable. Individual structures can be read with the `Deserializable*` traits.
+    ///
+    /// ```
+    /// use class_parser::deserialization::{from_reader, Deserializable};
+    /// use class::const_pool::Utf8Info;
+    ///
+    /// let file = std::fs::File::open("../resources/HelloWorld.class").unwrap();
+    /// let class = from_reader(file).unwrap();
+    /// assert_eq!(class.this_class.0.as_str(), "HelloWorld");
+    ///
+    /// let name = Utf8Info::deserialize(&[0x00, 0x04, b'm', b'a', b'i', b'n'][..]).unwrap();
+    /// assert_eq!(name.as_str(), "main");
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Class, DeserializationError> {
+        deserializable_class(reader)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Class, DeserializationError> {
+        deserializable_class(Cursor::new(bytes))
+    }
+
+    pub fn parse_file(path: impl AsRef<Path>) -> Result<Class, DeserializationError> {
+        deserializable_class(BufReader::new(File::open(path)?))
+    }
+
+    #[cfg(feature = "zip")]
+    pub fn parse_jar(path: impl AsRef<Path>) -> Result<Vec<(String, Class)>, DeserializationError> {
+        parse_jar_with_progress(path, |_, _, _| {})
+    }
+
+    // Reports (index, total, name) before each class entry is parsed; entries under META-INF and resources are skipped.
+    #[cfg(feature = "zip")]
+    pub fn parse_jar_with_progress(path: impl AsRef<Path>, mut progress: impl FnMut(usize, usize, &str))
+        -> Result<Vec<(String, Class)>, DeserializationError> {
+        let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+        // file_names comes from a hash map, so entries are listed by index to keep the archive order.
+        let mut names = vec![];
+        for index in 0..archive.len() {
+            let name = archive.by_index_raw(index)?.name().to_string();
+            if name.ends_with(".class") && !name.starts_with("META-INF/") {
+                names.push(name);
+            }
+        }
+        let total = names.len();
+        let mut classes = Vec::with_capacity(total);
+        for (index, name) in names.into_iter().enumerate() {
+            progress(index, total, &name);
+            let class = deserializable_class(BufReader::new(archive.by_name(&name)?))?;
+            classes.push((name, class));
+        }
+        Ok(classes)
+    }
+
+    // The rayon feature enables sync, so the parsed classes share Arc rather than Rc and can cross threads.
+    #[cfg(feature = "rayon")]
+    pub fn parse_all(inputs: impl rayon::iter::IntoParallelIterator<Item = Vec<u8>>) -> Vec<Result<Class, DeserializationError>> {
+        use rayon::iter::ParallelIterator;
+        inputs.into_par_iter()
+            .map(|bytes| from_bytes(&bytes))
+            .collect()
+    }
+
+    // Class lives in the model crate, so parsing is attached through this trait rather than an inherent method.
+    pub trait ParseClass: Sized {
+        fn parse(bytes: &[u8]) -> Result<Self, DeserializationError>;
+    }
+
+    impl ParseClass for Class {
+        #[inline(always)]
+        fn parse(bytes: &[u8]) -> Result<Class, DeserializationError> {
+            from_bytes(bytes)
+        }
+    }
+}
+
+pub mod visitor {
+    use super::deserialization::{check_duplicate, check_duplicates, recover, link_constant_value, CountingReader, Deserializable,
+                                 DeserializableConfigured, DeserializableLinked, DeserializableLinkedConfigured, ParseOptions};
+    use super::DeserializationError;
+    use byteorder::{BigEndian, ReadBytesExt};
+    use class::attributes::Attribute;
+    use class::components::{ClassAccess, ClassVersion, FieldInfo, MethodAccessSpecifier};
+    use class::const_pool::{ClassInfo, ConstPoolType, Utf8Info};
+    use class::BitFlags;
+    use std::collections::HashSet;
+    use std::io::{self, Read};
+
+    // Events arrive in class file order. Every method has a default that ignores its event.
+    pub trait ClassVisitor {
+        fn visit_version(&mut self, _version: &ClassVersion) {}
+
+        fn visit_const_pool(&mut self, _pool: &[ConstPoolType]) {}
+
+        fn visit_header(&mut self, _access: BitFlags<ClassAccess>, _this_class: &ClassInfo, _super_class: Option<&ClassInfo>,
+                        _interfaces: &[ClassInfo]) {}
+
+        fn visit_field(&mut self, _field: FieldInfo) {}
+
+        // Returning None skips the attributes of the method without parsing them, so they are not checked for duplicates.
+        fn visit_method(&mut self, _access: BitFlags<MethodAccessSpecifier>, _name: &Utf8Info,
+                        _descriptor: &Utf8Info) -> Option<&mut dyn MethodVisitor> {
+            None
+        }
+
+        fn visit_attribute(&mut self, _attribute: Attribute) {}
+
+        fn visit_end(&mut self) {}
+    }
+
+    pub trait MethodVisitor {
+        fn visit_attribute(&mut self, _attribute: Attribute) {}
+
+        fn visit_end(&mut self) {}
+    }
+
+    fn skip_attributes(mut cursor: impl ReadBytesExt) -> Result<(), DeserializationError> {
+        for _ in 0..cursor.read_u16::<BigEndian>()? {
+            cursor.read_u16::<BigEndian>()?;
+            let size = cursor.read_u32::<BigEndian>()? as u64;
+            if io::copy(&mut (&mut cursor).take(size), &mut io::sink())? != size {
+                return Err(DeserializationError::CannotRead(io::ErrorKind::UnexpectedEof.into()));
+            }
+        }
+        Ok(())
+    }
+
+    fn drive(mut cursor: impl ReadBytesExt, visitor: &mut dyn ClassVisitor, options: &ParseOptions) -> Result<(), DeserializationError> {
+        if cursor.read_u32::<BigEndian>()? != 0xCAFEBABE {
+            return Err(DeserializationError::Parsing("Its not JVM class file.".into()));
+        }
+        visitor.visit_version(&ClassVersion::deserialize(&mut cursor)?);
+        let pool: Vec<ConstPoolType> = Vec::deserialize_configured(&mut cursor, options)?;
+        visitor.visit_const_pool(&pool);
+        let access: BitFlags<ClassAccess> = BitFlags::deserialize(&mut cursor)?;
+        let this_class = ClassInfo::deserialize_link(&mut cursor, &pool)?;
+        let super_class = ClassInfo::deserialize_link(&mut cursor, &pool).ok();
+        let interfaces: Vec<ClassInfo> = Vec::deserialize_link(&mut cursor, &pool)?;
+        visitor.visit_header(access, &this_class, super_class.as_ref(), &interfaces);
+        for _ in 0..cursor.read_u16::<BigEndian>()? {
+            let mut field = FieldInfo::deserialize_link_configured(&mut cursor, &pool, options)?;
+            check_duplicates(&field.attributes, || format!("field `{}`", field.name), options)?;
+            if let Err(error) = link_constant_value(&mut field) {
+                recover(options, error)?;
+            }
+            visitor.visit_field(field);
+        }
+        for _ in 0..cursor.read_u16::<BigEndian>()? {
+            let access: BitFlags<MethodAccessSpecifier> = BitFlags::deserialize(&mut cursor)?;
+            let name = Utf8Info::deserialize_link(&mut cursor, &pool)?;
+            let descriptor = Utf8Info::deserialize_link(&mut cursor, &pool)?;
+            match visitor.visit_method(access, &name, &descriptor) {
+                Some(method) => {
+                    let mut seen = HashSet::new();
+                    for _ in 0..cursor.read_u16::<BigEndian>()? {
+                        let attribute = Attribute::deserialize_link_configured(&mut cursor, &pool, options)?;
+                        check_duplicate(&mut seen, &attribute, || format!("method `{name}{descriptor}`"), options)?;
+                        method.visit_attribute(attribute);
+                    }
+                    method.visit_end();
+                }
+                None => skip_attributes(&mut cursor)?
+            }
+        }
+        let mut seen = HashSet::new();
+        for _ in 0..cursor.read_u16::<BigEndian>()? {
+            let attribute = Attribute::deserialize_link_configured(&mut cursor, &pool, options)?;
+            check_duplicate(&mut seen, &attribute, || format!("class `{}`", this_class.0), options)?;
+            visitor.visit_attribute(attribute);
+        }
+        visitor.visit_end();
+        Ok(())
+    }
+
+    // Reads a class without building a Class value; errors carry the offset at which they occurred, as in
+    // deserializable_class_with_options.
+    pub fn visit_class(cursor: impl ReadBytesExt, visitor: &mut dyn ClassVisitor, options: &ParseOptions) -> Result<(), DeserializationError> {
+        let mut counting = CountingReader {
+            inner: cursor,
+            count: 0,
+            start: 0
+        };
+        drive(&mut counting, visitor, options).map_err(|error| DeserializationError::AtOffset {
+            offset: counting.start,
+            source: Box::new(error)
+        })
+    }
+}
+
+pub mod bytecode {
+    use super::*;
+    use super::descriptor::{parse_field_descriptor, parse_method_descriptor, FieldType, MethodDescriptor};
+    use byteorder::{BigEndian, ReadBytesExt};
+    use class::attributes::{CodeAttribute, StackMapFrame, StackMapTableAttribute, VerificationType};
+    use class::const_pool::{ClassInfo, ConstPoolType, Utf8Info};
+    use class::components::{MethodAccessSpecifier, MethodInfo};
+    use class::Class;
+    use std::collections::{BTreeSet, HashMap};
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Instruction {
+        Nop,
+        AconstNull,
+        IconstM1,
+        Iconst0,
+        Iconst1,
+        Iconst2,
+        Iconst3,
+        Iconst4,
+        Iconst5,
+        Lconst0,
+        Lconst1,
+        Fconst0,
+        Fconst1,
+        Fconst2,
+        Dconst0,
+        Dconst1,
+        Bipush(i8),
+        Sipush(i16),
+        Ldc(u8),
+        LdcW(u16),
+        Ldc2W(u16),
+        Iload(u16),
+        Lload(u16),
+        Fload(u16),
+        Dload(u16),
+        Aload(u16),
+        Iload0,
+        Iload1,
+        Iload2,
+        Iload3,
+        Lload0,
+        Lload1,
+        Lload2,
+        Lload3,
+        Fload0,
+        Fload1,
+        Fload2,
+        Fload3,
+        Dload0,
+        Dload1,
+        Dload2,
+        Dload3,
+        Aload0,
+        Aload1,
+        Aload2,
+        Aload3,
+        Iaload,
+        Laload,
+        Faload,
+        Daload,
+        Aaload,
+        Baload,
+        Caload,
+        Saload,
+        Istore(u16),
+        Lstore(u16),
+        Fstore(u16),
+        Dstore(u16),
+        Astore(u16),
+        Istore0,
+        Istore1,
+        Istore2,
+        Istore3,
+        Lstore0,
+        Lstore1,
+        Lstore2,
+        Lstore3,
+        Fstore0,
+        Fstore1,
+        Fstore2,
+        Fstore3,
+        Dstore0,
+        Dstore1,
+        Dstore2,
+        Dstore3,
+        Astore0,
+        Astore1,
+        Astore2,
+        Astore3,
+        Iastore,
+        Lastore,
+        Fastore,
+        Dastore,
+        Aastore,
+        Bastore,
+        Castore,
+        Sastore,
+        Pop,
+        Pop2,
+        Dup,
+        DupX1,
+        DupX2,
+        Dup2,
+        Dup2X1,
+        Dup2X2,
+        Swap,
+        Iadd,
+        Ladd,
+        Fadd,
+        Dadd,
+        Isub,
+        Lsub,
+        Fsub,
+        Dsub,
+        Imul,
+        Lmul,
+        Fmul,
+        Dmul,
+        Idiv,
+        Ldiv,
+        Fdiv,
+        Ddiv,
+        Irem,
+        Lrem,
+        Frem,
+        Drem,
+        Ineg,
+        Lneg,
+        Fneg,
+        Dneg,
+        Ishl,
+        Lshl,
+        Ishr,
+        Lshr,
+        Iushr,
+        Lushr,
+        Iand,
+        Land,
+        Ior,
+        Lor,
+        Ixor,
+        Lxor,
+        Iinc {
+            index: u16,
+            constant: i16
+        },
+        I2l,
+        I2f,
+        I2d,
+        L2i,
+        L2f,
+        L2d,
+        F2i,
+        F2l,
+        F2d,
+        D2i,
+        D2l,
+        D2f,
+        I2b,
+        I2c,
+        I2s,
+        Lcmp,
+        Fcmpl,
+        Fcmpg,
+        Dcmpl,
+        Dcmpg,
+        Ifeq(i16),
+        Ifne(i16),
+        Iflt(i16),
+        Ifge(i16),
+        Ifgt(i16),
+        Ifle(i16),
+        IfIcmpeq(i16),
+        IfIcmpne(i16),
+        IfIcmplt(i16),
+        IfIcmpge(i16),
+        IfIcmpgt(i16),
+        IfIcmple(i16),
+        IfAcmpeq(i16),
+        IfAcmpne(i16),
+        Goto(i16),
+        Jsr(i16),
+        Ret(u16),
+        Tableswitch {
+            default: i32,
+            low: i32,
+            high: i32,
+            offsets: Vec<i32>
+        },
+        Lookupswitch {
+            default: i32,
+            pairs: Vec<(i32, i32)>
+        },
+        Ireturn,
+        Lreturn,
+        Freturn,
+        Dreturn,
+        Areturn,
+        Return,
+        Getstatic(u16),
+        Putstatic(u16),
+        Getfield(u16),
+        Putfield(u16),
+        Invokevirtual(u16),
+        Invokespecial(u16),
+        Invokestatic(u16),
+        Invokeinterface {
+            index: u16,
+            count: u8
+        },
+        Invokedynamic(u16),
+        New(u16),
+        Newarray(u8),
+        Anewarray(u16),
+        Arraylength,
+        Athrow,
+        Checkcast(u16),
+        Instanceof(u16),
+        Monitorenter,
+        Monitorexit,
+        Multianewarray {
+            index: u16,
+            dimensions: u8
+        },
+        Ifnull(i16),
+        Ifnonnull(i16),
+        GotoW(i32),
+        JsrW(i32)
+    }
+
+    impl Instruction {
+        pub fn mnemonic(&self) -> &'static str {
+            match self {
+                Instruction::Nop => "nop",
+                Instruction::AconstNull => "aconst_null",
+                Instruction::IconstM1 => "iconst_m1",
+                Instruction::Iconst0 => "iconst_0",
+                Instruction::Iconst1 => "iconst_1",
+                Instruction::Iconst2 => "iconst_2",
+                Instruction::Iconst3 => "iconst_3",
+                Instruction::Iconst4 => "iconst_4",
+                Instruction::Iconst5 => "iconst_5",
+                Instruction::Lconst0 => "lconst_0",
+                Instruction::Lconst1 => "lconst_1",
+                Instruction::Fconst0 => "fconst_0",
+                Instruction::Fconst1 => "fconst_1",
+                Instruction::Fconst2 => "fconst_2",
+                Instruction::Dconst0 => "dconst_0",
+                Instruction::Dconst1 => "dconst_1",
+                Instruction::Bipush(_) => "bipush",
+                Instruction::Sipush(_) => "sipush",
+                Instruction::Ldc(_) => "ldc",
+                Instruction::LdcW(_) => "ldc_w",
+                Instruction::Ldc2W(_) => "ldc2_w",
+                Instruction::Iload(_) => "iload",
+                Instruction::Lload(_) => "lload",
+                Instruction::Fload(_) => "fload",
+                Instruction::Dload(_) => "dload",
+                Instruction::Aload(_) => "aload",
+                Instruction::Iload0 => "iload_0",
+                Instruction::Iload1 => "iload_1",
+                Instruction::Iload2 => "iload_2",
+                Instruction::Iload3 => "iload_3",
+                Instruction::Lload0 => "lload_0",
+                Instruction::Lload1 => "lload_1",
+                Instruction::Lload2 => "lload_2",
+                Instruction::Lload3 => "lload_3",
+                Instruction::Fload0 => "fload_0",
+                Instruction::Fload1 => "fload_1",
+                Instruction::Fload2 => "fload_2",
+                Instruction::Fload3 => "fload_3",
+                Instruction::Dload0 => "dload_0",
+                Instruction::Dload1 => "dload_1",
+                Instruction::Dload2 => "dload_2",
+                Instruction::Dload3 => "dload_3",
+                Instruction::Aload0 => "aload_0",
+                Instruction::Aload1 => "aload_1",
+                Instruction::Aload2 => "aload_2",
+                Instruction::Aload3 => "aload_3",
+                Instruction::Iaload => "iaload",
+                Instruction::Laload => "laload",
+                Instruction::Faload => "faload",
+                Instruction::Daload => "daload",
+                Instruction::Aaload => "aaload",
+                Instruction::Baload => "baload",
+                Instruction::Caload => "caload",
+                Instruction::Saload => "saload",
+                Instruction::Istore(_) => "istore",
+                Instruction::Lstore(_) => "lstore",
+                Instruction::Fstore(_) => "fstore",
+                Instruction::Dstore(_) => "dstore",
+                Instruction::Astore(_) => "astore",
+                Instruction::Istore0 => "istore_0",
+                Instruction::Istore1 => "istore_1",
+                Instruction::Istore2 => "istore_2",
+                Instruction::Istore3 => "istore_3",
+                Instruction::Lstore0 => "lstore_0",
+                Instruction::Lstore1 => "lstore_1",
+                Instruction::Lstore2 => "lstore_2",
+                Instruction::Lstore3 => "lstore_3",
+                Instruction::Fstore0 => "fstore_0",
+                Instruction::Fstore1 => "fstore_1",
+                Instruction::Fstore2 => "fstore_2",
+                Instruction::Fstore3 => "fstore_3",
+                Instruction::Dstore0 => "dstore_0",
+                Instruction::Dstore1 => "dstore_1",
+                Instruction::Dstore2 => "dstore_2",
+                Instruction::Dstore3 => "dstore_3",
+                Instruction::Astore0 => "astore_0",
+                Instruction::Astore1 => "astore_1",
+                Instruction::Astore2 => "astore_2",
+                Instruction::Astore3 => "astore_3",
+                Instruction::Iastore => "iastore",
+                Instruction::Lastore => "lastore",
+                Instruction::Fastore => "fastore",
+                Instruction::Dastore => "dastore",
+                Instruction::Aastore => "aastore",
+                Instruction::Bastore => "bastore",
+                Instruction::Castore => "castore",
+                Instruction::Sastore => "sastore",
+                Instruction::Pop => "pop",
+                Instruction::Pop2 => "pop2",
+                Instruction::Dup => "dup",
+                Instruction::DupX1 => "dup_x1",
+                Instruction::DupX2 => "dup_x2",
+                Instruction::Dup2 => "dup2",
+                Instruction::Dup2X1 => "dup2_x1",
+                Instruction::Dup2X2 => "dup2_x2",
+                Instruction::Swap => "swap",
+                Instruction::Iadd => "iadd",
+                Instruction::Ladd => "ladd",
+                Instruction::Fadd => "fadd",
+                Instruction::Dadd => "dadd",
+                Instruction::Isub => "isub",
+                Instruction::Lsub => "lsub",
+                Instruction::Fsub => "fsub",
+                Instruction::Dsub => "dsub",
+                Instruction::Imul => "imul",
+                Instruction::Lmul => "lmul",
+                Instruction::Fmul => "fmul",
+                Instruction::Dmul => "dmul",
+                Instruction::Idiv => "idiv",
+                Instruction::Ldiv => "ldiv",
+                Instruction::Fdiv => "fdiv",
+                Instruction::Ddiv => "ddiv",
+                Instruction::Irem => "irem",
+                Instruction::Lrem => "lrem",
+                Instruction::Frem => "frem",
+                Instruction::Drem => "drem",
+                Instruction::Ineg => "ineg",
+                Instruction::Lneg => "lneg",
+                Instruction::Fneg => "fneg",
+                Instruction::Dneg => "dneg",
+                Instruction::Ishl => "ishl",
+                Instruction::Lshl => "lshl",
+                Instruction::Ishr => "ishr",
+                Instruction::Lshr => "lshr",
+                Instruction::Iushr => "iushr",
+                Instruction::Lushr => "lushr",
+                Instruction::Iand => "iand",
+                Instruction::Land => "land",
+                Instruction::Ior => "ior",
+                Instruction::Lor => "lor",
+                Instruction::Ixor => "ixor",
+                Instruction::Lxor => "lxor",
+                Instruction::Iinc { .. } => "iinc",
+                Instruction::I2l => "i2l",
+                Instruction::I2f => "i2f",
+                Instruction::I2d => "i2d",
+                Instruction::L2i => "l2i",
+                Instruction::L2f => "l2f",
+                Instruction::L2d => "l2d",
+                Instruction::F2i => "f2i",
+                Instruction::F2l => "f2l",
+                Instruction::F2d => "f2d",
+                Instruction::D2i => "d2i",
+                Instruction::D2l => "d2l",
+                Instruction::D2f => "d2f",
+                Instruction::I2b => "i2b",
+                Instruction::I2c => "i2c",
+                Instruction::I2s => "i2s",
+                Instruction::Lcmp => "lcmp",
+                Instruction::Fcmpl => "fcmpl",
+                Instruction::Fcmpg => "fcmpg",
+                Instruction::Dcmpl => "dcmpl",
+                Instruction::Dcmpg => "dcmpg",
+                Instruction::Ifeq(_) => "ifeq",
+                Instruction::Ifne(_) => "ifne",
+                Instruction::Iflt(_) => "iflt",
+                Instruction::Ifge(_) => "ifge",
+                Instruction::Ifgt(_) => "ifgt",
+                Instruction::Ifle(_) => "ifle",
+                Instruction::IfIcmpeq(_) => "if_icmpeq",
+                Instruction::IfIcmpne(_) => "if_icmpne",
+                Instruction::IfIcmplt(_) => "if_icmplt",
+                Instruction::IfIcmpge(_) => "if_icmpge",
+                Instruction::IfIcmpgt(_) => "if_icmpgt",
+                Instruction::IfIcmple(_) => "if_icmple",
+                Instruction::IfAcmpeq(_) => "if_acmpeq",
+                Instruction::IfAcmpne(_) => "if_acmpne",
+                Instruction::Goto(_) => "goto",
+                Instruction::Jsr(_) => "jsr",
+                Instruction::Ret(_) => "ret",
+                Instruction::Tableswitch { .. } => "tableswitch",
+                Instruction::Lookupswitch { .. } => "lookupswitch",
+                Instruction::Ireturn => "ireturn",
+                Instruction::Lreturn => "lreturn",
+                Instruction::Freturn => "freturn",
+                Instruction::Dreturn => "dreturn",
+                Instruction::Areturn => "areturn",
+                Instruction::Return => "return",
+                Instruction::Getstatic(_) => "getstatic",
+                Instruction::Putstatic(_) => "putstatic",
+                Instruction::Getfield(_) => "getfield",
+                Instruction::Putfield(_) => "putfield",
+                Instruction::Invokevirtual(_) => "invokevirtual",
+                Instruction::Invokespecial(_) => "invokespecial",
+                Instruction::Invokestatic(_) => "invokestatic",
+                Instruction::Invokeinterface { .. } => "invokeinterface",
+                Instruction::Invokedynamic(_) => "invokedynamic",
+                Instruction::New(_) => "new",
+                Instruction::Newarray(_) => "newarray",
+                Instruction::Anewarray(_) => "anewarray",
+                Instruction::Arraylength => "arraylength",
+                Instruction::Athrow => "athrow",
+                Instruction::Checkcast(_) => "checkcast",
+                Instruction::Instanceof(_) => "instanceof",
+                Instruction::Monitorenter => "monitorenter",
+                Instruction::Monitorexit => "monitorexit",
+                Instruction::Multianewarray { .. } => "multianewarray",
+                Instruction::Ifnull(_) => "ifnull",
+                Instruction::Ifnonnull(_) => "ifnonnull",
+                Instruction::GotoW(_) => "goto_w",
+                Instruction::JsrW(_) => "jsr_w",
+            }
+        }
+
+        pub fn opcode(&self) -> u8 {
+            match self {
+                Instruction::Nop => 0x00,
+                Instruction::AconstNull => 0x01,
+                Instruction::IconstM1 => 0x02,
+                Instruction::Iconst0 => 0x03,
+                Instruction::Iconst1 => 0x04,
+                Instruction::Iconst2 => 0x05,
+                Instruction::Iconst3 => 0x06,
+                Instruction::Iconst4 => 0x07,
+                Instruction::Iconst5 => 0x08,
+                Instruction::Lconst0 => 0x09,
+                Instruction::Lconst1 => 0x0A,
+                Instruction::Fconst0 => 0x0B,
+                Instruction::Fconst1 => 0x0C,
+                Instruction::Fconst2 => 0x0D,
+                Instruction::Dconst0 => 0x0E,
+                Instruction::Dconst1 => 0x0F,
+                Instruction::Bipush(_) => 0x10,
+                Instruction::Sipush(_) => 0x11,
+                Instruction::Ldc(_) => 0x12,
+                Instruction::LdcW(_) => 0x13,
+                Instruction::Ldc2W(_) => 0x14,
+                Instruction::Iload(_) => 0x15,
+                Instruction::Lload(_) => 0x16,
+                Instruction::Fload(_) => 0x17,
+                Instruction::Dload(_) => 0x18,
+                Instruction::Aload(_) => 0x19,
+                Instruction::Iload0 => 0x1A,
+                Instruction::Iload1 => 0x1B,
+                Instruction::Iload2 => 0x1C,
+                Instruction::Iload3 => 0x1D,
+                Instruction::Lload0 => 0x1E,
+                Instruction::Lload1 => 0x1F,
+                Instruction::Lload2 => 0x20,
+                Instruction::Lload3 => 0x21,
+                Instruction::Fload0 => 0x22,
+                Instruction::Fload1 => 0x23,
+                Instruction::Fload2 => 0x24,
+                Instruction::Fload3 => 0x25,
+                Instruction::Dload0 => 0x26,
+                Instruction::Dload1 => 0x27,
+                Instruction::Dload2 => 0x28,
+                Instruction::Dload3 => 0x29,
+                Instruction::Aload0 => 0x2A,
+                Instruction::Aload1 => 0x2B,
+                Instruction::Aload2 => 0x2C,
+                Instruction::Aload3 => 0x2D,
+                Instruction::Iaload => 0x2E,
+                Instruction::Laload => 0x2F,
+                Instruction::Faload => 0x30,
+                Instruction::Daload => 0x31,
+                Instruction::Aaload => 0x32,
+                Instruction::Baload => 0x33,
+                Instruction::Caload => 0x34,
+                Instruction::Saload => 0x35,
+                Instruction::Istore(_) => 0x36,
+                Instruction::Lstore(_) => 0x37,
+                Instruction::Fstore(_) => 0x38,
+                Instruction::Dstore(_) => 0x39,
+                Instruction::Astore(_) => 0x3A,
+                Instruction::Istore0 => 0x3B,
+                Instruction::Istore1 => 0x3C,
+                Instruction::Istore2 => 0x3D,
+                Instruction::Istore3 => 0x3E,
+                Instruction::Lstore0 => 0x3F,
+                Instruction::Lstore1 => 0x40,
+                Instruction::Lstore2 => 0x41,
+                Instruction::Lstore3 => 0x42,
+                Instruction::Fstore0 => 0x43,
+                Instruction::Fstore1 => 0x44,
+                Instruction::Fstore2 => 0x45,
+                Instruction::Fstore3 => 0x46,
+                Instruction::Dstore0 => 0x47,
+                Instruction::Dstore1 => 0x48,
+                Instruction::Dstore2 => 0x49,
+                Instruction::Dstore3 => 0x4A,
+                Instruction::Astore0 => 0x4B,
+                Instruction::Astore1 => 0x4C,
+                Instruction::Astore2 => 0x4D,
+                Instruction::Astore3 => 0x4E,
+                Instruction::Iastore => 0x4F,
+                Instruction::Lastore => 0x50,
+                Instruction::Fastore => 0x51,
+                Instruction::Dastore => 0x52,
+                Instruction::Aastore => 0x53,
+                Instruction::Bastore => 0x54,
+                Instruction::Castore => 0x55,
+                Instruction::Sastore => 0x56,
+                Instruction::Pop => 0x57,
+                Instruction::Pop2 => 0x58,
+                Instruction::Dup => 0x59,
+                Instruction::DupX1 => 0x5A,
+                Instruction::DupX2 => 0x5B,
+                Instruction::Dup2 => 0x5C,
+                Instruction::Dup2X1 => 0x5D,
+                Instruction::Dup2X2 => 0x5E,
+                Instruction::Swap => 0x5F,
+                Instruction::Iadd => 0x60,
+                Instruction::Ladd => 0x61,
+                Instruction::Fadd => 0x62,
+                Instruction::Dadd => 0x63,
+                Instruction::Isub => 0x64,
+                Instruction::Lsub => 0x65,
+                Instruction::Fsub => 0x66,
+                Instruction::Dsub => 0x67,
+                Instruction::Imul => 0x68,
+                Instruction::Lmul => 0x69,
+                Instruction::Fmul => 0x6A,
+                Instruction::Dmul => 0x6B,
+                Instruction::Idiv => 0x6C,
+                Instruction::Ldiv => 0x6D,
+                Instruction::Fdiv => 0x6E,
+                Instruction::Ddiv => 0x6F,
+                Instruction::Irem => 0x70,
+                Instruction::Lrem => 0x71,
+                Instruction::Frem => 0x72,
+                Instruction::Drem => 0x73,
+                Instruction::Ineg => 0x74,
+                Instruction::Lneg => 0x75,
+                Instruction::Fneg => 0x76,
+                Instruction::Dneg => 0x77,
+                Instruction::Ishl => 0x78,
+                Instruction::Lshl => 0x79,
+                Instruction::Ishr => 0x7A,
+                Instruction::Lshr => 0x7B,
+                Instruction::Iushr => 0x7C,
+                Instruction::Lushr => 0x7D,
+                Instruction::Iand => 0x7E,
+                Instruction::Land => 0x7F,
+                Instruction::Ior => 0x80,
+                Instruction::Lor => 0x81,
+                Instruction::Ixor => 0x82,
+                Instruction::Lxor => 0x83,
+                Instruction::Iinc { .. } => 0x84,
+                Instruction::I2l => 0x85,
+                Instruction::I2f => 0x86,
+                Instruction::I2d => 0x87,
+                Instruction::L2i => 0x88,
+                Instruction::L2f => 0x89,
+                Instruction::L2d => 0x8A,
+                Instruction::F2i => 0x8B,
+                Instruction::F2l => 0x8C,
+                Instruction::F2d => 0x8D,
+                Instruction::D2i => 0x8E,
+                Instruction::D2l => 0x8F,
+                Instruction::D2f => 0x90,
+                Instruction::I2b => 0x91,
+                Instruction::I2c => 0x92,
+                Instruction::I2s => 0x93,
+                Instruction::Lcmp => 0x94,
+                Instruction::Fcmpl => 0x95,
+                Instruction::Fcmpg => 0x96,
+                Instruction::Dcmpl => 0x97,
+                Instruction::Dcmpg => 0x98,
+                Instruction::Ifeq(_) => 0x99,
+                Instruction::Ifne(_) => 0x9A,
+                Instruction::Iflt(_) => 0x9B,
+                Instruction::Ifge(_) => 0x9C,
+                Instruction::Ifgt(_) => 0x9D,
+                Instruction::Ifle(_) => 0x9E,
+                Instruction::IfIcmpeq(_) => 0x9F,
+                Instruction::IfIcmpne(_) => 0xA0,
+                Instruction::IfIcmplt(_) => 0xA1,
+                Instruction::IfIcmpge(_) => 0xA2,
+                Instruction::IfIcmpgt(_) => 0xA3,
+                Instruction::IfIcmple(_) => 0xA4,
+                Instruction::IfAcmpeq(_) => 0xA5,
+                Instruction::IfAcmpne(_) => 0xA6,
+                Instruction::Goto(_) => 0xA7,
+                Instruction::Jsr(_) => 0xA8,
+                Instruction::Ret(_) => 0xA9,
+                Instruction::Tableswitch { .. } => 0xAA,
+                Instruction::Lookupswitch { .. } => 0xAB,
+                Instruction::Ireturn => 0xAC,
+                Instruction::Lreturn => 0xAD,
+                Instruction::Freturn => 0xAE,
+                Instruction::Dreturn => 0xAF,
+                Instruction::Areturn => 0xB0,
+                Instruction::Return => 0xB1,
+                Instruction::Getstatic(_) => 0xB2,
+                Instruction::Putstatic(_) => 0xB3,
+                Instruction::Getfield(_) => 0xB4,
+                Instruction::Putfield(_) => 0xB5,
+                Instruction::Invokevirtual(_) => 0xB6,
+                Instruction::Invokespecial(_) => 0xB7,
+                Instruction::Invokestatic(_) => 0xB8,
+                Instruction::Invokeinterface { .. } => 0xB9,
+                Instruction::Invokedynamic(_) => 0xBA,
+                Instruction::New(_) => 0xBB,
+                Instruction::Newarray(_) => 0xBC,
+                Instruction::Anewarray(_) => 0xBD,
+                Instruction::Arraylength => 0xBE,
+                Instruction::Athrow => 0xBF,
+                Instruction::Checkcast(_) => 0xC0,
+                Instruction::Instanceof(_) => 0xC1,
+                Instruction::Monitorenter => 0xC2,
+                Instruction::Monitorexit => 0xC3,
+                Instruction::Multianewarray { .. } => 0xC5,
+                Instruction::Ifnull(_) => 0xC6,
+                Instruction::Ifnonnull(_) => 0xC7,
+                Instruction::GotoW(_) => 0xC8,
+                Instruction::JsrW(_) => 0xC9,
+            }
+        }
+
+        // The constant pool index the instruction refers to, if any.
+        pub fn pool_index(&self) -> Option<u16> {
+            match self {
+                Instruction::Ldc(index) => Some(*index as u16),
+                Instruction::LdcW(index) | Instruction::Ldc2W(index) | Instruction::Getstatic(index) | Instruction::Putstatic(index)
+                | Instruction::Getfield(index) | Instruction::Putfield(index) | Instruction::Invokevirtual(index)
+                | Instruction::Invokespecial(index) | Instruction::Invokestatic(index) | Instruction::Invokedynamic(index)
+                | Instruction::New(index) | Instruction::Anewarray(index) | Instruction::Checkcast(index)
+                | Instruction::Instanceof(index) | Instruction::Invokeinterface { index, .. }
+                | Instruction::Multianewarray { index, .. } => Some(*index),
+                _ => None
+            }
+        }
+
+        // Resolves the operand against the pool of `class`; None for instructions without one or a dangling index.
+        #[inline(always)]
+        pub fn constant<'a>(&self, class: &'a Class) -> Option<&'a ConstPoolType> {
+            class.const_pool_entry(self.pool_index()?)
+        }
+    }
+
+    pub struct Instructions<'a> {
+        code: &'a [u8],
+        pc: usize
+    }
+
+    // Yields (pc, instruction, length in bytes); stops after the first decoding error.
+    impl Iterator for Instructions<'_> {
+        type Item = Result<(u32, Instruction, u32), DeserializationError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.pc >= self.code.len() {
+                return None;
+            }
+            let pc = self.pc;
+            let mut cursor = &self.code[pc..];
+            let result = decode_instruction(&mut cursor, pc);
+            self.pc = match result {
+                Ok(_) => self.code.len() - cursor.len(),
+                Err(_) => self.code.len()
+            };
+            Some(result.map(|instruction| (pc as u32, instruction, (self.pc - pc) as u32)))
+        }
+    }
+
+    pub fn instructions(code: &[u8]) -> Instructions<'_> {
+        Instructions {
+            code,
+            pc: 0
+        }
+    }
+
+    pub fn decode(code: &[u8]) -> Result<Vec<(u32, Instruction)>, DeserializationError> {
+        instructions(code)
+            .map(|item| item.map(|(pc, instruction, _)| (pc, instruction)))
+            .collect()
+    }
+
+    #[inline(always)]
+    fn decode_instruction(cursor: &mut &[u8], pc: usize) -> Result<Instruction, DeserializationError> {
+        decode_operands(cursor, pc).map_err(|error| match error {
+            DeserializationError::CannotRead(_) => DeserializationError::Parsing(format!("Truncated instruction at pc {pc}")),
+            error => error
+        })
+    }
+
+    // Switch operands start at the next offset that is a multiple of four from the start of the code.
+    #[inline(always)]
+    fn skip_padding(cursor: &mut &[u8], pc: usize) -> Result<(), DeserializationError> {
+        for _ in 0..3 - pc % 4 {
+            cursor.read_u8()?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn check_remaining(cursor: &[u8], count: i64, entry_size: i64, pc: usize) -> Result<usize, DeserializationError> {
+        if count < 0 || count * entry_size > cursor.len() as i64 {
+            return Err(DeserializationError::Parsing(format!("Invalid switch size {count} at pc {pc}")));
+        }
+        Ok(count as usize)
+    }
+
+    fn decode_tableswitch(cursor: &mut &[u8], pc: usize) -> Result<Instruction, DeserializationError> {
+        skip_padding(cursor, pc)?;
+        let default = cursor.read_i32::<BigEndian>()?;
+        let low = cursor.read_i32::<BigEndian>()?;
+        let high = cursor.read_i32::<BigEndian>()?;
+        if high < low {
+            return Err(DeserializationError::Parsing(format!("Invalid tableswitch range {low}..={high} at pc {pc}")));
+        }
+        let count = check_remaining(cursor, high as i64 - low as i64 + 1, 4, pc)?;
+        let offsets = (0..count)
+            .map(|_| cursor.read_i32::<BigEndian>())
+            .collect::<Result<Vec<i32>, std::io::Error>>()?;
+        Ok(Instruction::Tableswitch {
+            default,
+            low,
+            high,
+            offsets
+        })
+    }
+
+    fn decode_lookupswitch(cursor: &mut &[u8], pc: usize) -> Result<Instruction, DeserializationError> {
+        skip_padding(cursor, pc)?;
+        let default = cursor.read_i32::<BigEndian>()?;
+        let count = check_remaining(cursor, cursor.read_i32::<BigEndian>()? as i64, 8, pc)?;
+        let pairs = (0..count)
+            .map(|_| Ok((cursor.read_i32::<BigEndian>()?, cursor.read_i32::<BigEndian>()?)))
+            .collect::<Result<Vec<(i32, i32)>, std::io::Error>>()?;
+        Ok(Instruction::Lookupswitch {
+            default,
+            pairs
+        })
+    }
+
+    fn decode_wide(cursor: &mut &[u8], pc: usize) -> Result<Instruction, DeserializationError> {
+        Ok(match cursor.read_u8()? {
+            0x15 => Instruction::Iload(cursor.read_u16::<BigEndian>()?),
+            0x16 => Instruction::Lload(cursor.read_u16::<BigEndian>()?),
+            0x17 => Instruction::Fload(cursor.read_u16::<BigEndian>()?),
+            0x18 => Instruction::Dload(cursor.read_u16::<BigEndian>()?),
+            0x19 => Instruction::Aload(cursor.read_u16::<BigEndian>()?),
+            0x36 => Instruction::Istore(cursor.read_u16::<BigEndian>()?),
+            0x37 => Instruction::Lstore(cursor.read_u16::<BigEndian>()?),
+            0x38 => Instruction::Fstore(cursor.read_u16::<BigEndian>()?),
+            0x39 => Instruction::Dstore(cursor.read_u16::<BigEndian>()?),
+            0x3A => Instruction::Astore(cursor.read_u16::<BigEndian>()?),
+            0xA9 => Instruction::Ret(cursor.read_u16::<BigEndian>()?),
+            0x84 => Instruction::Iinc {
+                index: cursor.read_u16::<BigEndian>()?,
+                constant: cursor.read_i16::<BigEndian>()?
+            },
+            unexpected => return Err(DeserializationError::Parsing(format!("Invalid wide opcode {unexpected:#04x} at pc {pc}")))
+        })
+    }
+
+    fn decode_operands(cursor: &mut &[u8], pc: usize) -> Result<Instruction, DeserializationError> {
+        Ok(match cursor.read_u8()? {
+            0x00 => Instruction::Nop,
+            0x01 => Instruction::AconstNull,
+            0x02 => Instruction::IconstM1,
+            0x03 => Instruction::Iconst0,
+            0x04 => Instruction::Iconst1,
+            0x05 => Instruction::Iconst2,
+            0x06 => Instruction::Iconst3,
+            0x07 => Instruction::Iconst4,
+            0x08 => Instruction::Iconst5,
+            0x09 => Instruction::Lconst0,
+            0x0A => Instruction::Lconst1,
+            0x0B => Instruction::Fconst0,
+            0x0C => Instruction::Fconst1,
+            0x0D => Instruction::Fconst2,
+            0x0E => Instruction::Dconst0,
+            0x0F => Instruction::Dconst1,
+            0x10 => Instruction::Bipush(cursor.read_i8()?),
+            0x11 => Instruction::Sipush(cursor.read_i16::<BigEndian>()?),
+            0x12 => Instruction::Ldc(cursor.read_u8()?),
+            0x13 => Instruction::LdcW(cursor.read_u16::<BigEndian>()?),
+            0x14 => Instruction::Ldc2W(cursor.read_u16::<BigEndian>()?),
+            0x15 => Instruction::Iload(cursor.read_u8()? as u16),
+            0x16 => Instruction::Lload(cursor.read_u8()? as u16),
+            0x17 => Instruction::Fload(cursor.read_u8()? as u16),
+            0x18 => Instruction::Dload(cursor.read_u8()? as u16),
+            0x19 => Instruction::Aload(cursor.read_u8()? as u16),
+            0x1A => Instruction::Iload0,
+            0x1B => Instruction::Iload1,
+            0x1C => Instruction::Iload2,
+            0x1D => Instruction::Iload3,
+            0x1E => Instruction::Lload0,
+            0x1F => Instruction::Lload1,
+            0x20 => Instruction::Lload2,
+            0x21 => Instruction::Lload3,
+            0x22 => Instruction::Fload0,
+            0x23 => Instruction::Fload1,
+            0x24 => Instruction::Fload2,
+            0x25 => Instruction::Fload3,
+            0x26 => Instruction::Dload0,
+            0x27 => Instruction::Dload1,
+            0x28 => Instruction::Dload2,
+            0x29 => Instruction::Dload3,
+            0x2A => Instruction::Aload0,
+            0x2B => Instruction::Aload1,
+            0x2C => Instruction::Aload2,
+            0x2D => Instruction::Aload3,
+            0x2E => Instruction::Iaload,
+            0x2F => Instruction::Laload,
+            0x30 => Instruction::Faload,
+            0x31 => Instruction::Daload,
+            0x32 => Instruction::Aaload,
+            0x33 => Instruction::Baload,
+            0x34 => Instruction::Caload,
+            0x35 => Instruction::Saload,
+            0x36 => Instruction::Istore(cursor.read_u8()? as u16),
+            0x37 => Instruction::Lstore(cursor.read_u8()? as u16),
+            0x38 => Instruction::Fstore(cursor.read_u8()? as u16),
+            0x39 => Instruction::Dstore(cursor.read_u8()? as u16),
+            0x3A => Instruction::Astore(cursor.read_u8()? as u16),
+            0x3B => Instruction::Istore0,
+            0x3C => Instruction::Istore1,
+            0x3D => Instruction::Istore2,
+            0x3E => Instruction::Istore3,
+            0x3F => Instruction::Lstore0,
+            0x40 => Instruction::Lstore1,
+            0x41 => Instruction::Lstore2,
+            0x42 => Instruction::Lstore3,
+            0x43 => Instruction::Fstore0,
+            0x44 => Instruction::Fstore1,
+            0x45 => Instruction::Fstore2,
+            0x46 => Instruction::Fstore3,
+            0x47 => Instruction::Dstore0,
+            0x48 => Instruction::Dstore1,
+            0x49 => Instruction::Dstore2,
+            0x4A => Instruction::Dstore3,
+            0x4B => Instruction::Astore0,
+            0x4C => Instruction::Astore1,
+            0x4D => Instruction::Astore2,
+            0x4E => Instruction::Astore3,
+            0x4F => Instruction::Iastore,
+            0x50 => Instruction::Lastore,
+            0x51 => Instruction::Fastore,
+            0x52 => Instruction::Dastore,
+            0x53 => Instruction::Aastore,
+            0x54 => Instruction::Bastore,
+            0x55 => Instruction::Castore,
+            0x56 => Instruction::Sastore,
+            0x57 => Instruction::Pop,
+            0x58 => Instruction::Pop2,
+            0x59 => Instruction::Dup,
+            0x5A => Instruction::DupX1,
+            0x5B => Instruction::DupX2,
+            0x5C => Instruction::Dup2,
+            0x5D => Instruction::Dup2X1,
+            0x5E => Instruction::Dup2X2,
+            0x5F => Instruction::Swap,
+            0x60 => Instruction::Iadd,
+            0x61 => Instruction::Ladd,
+            0x62 => Instruction::Fadd,
+            0x63 => Instruction::Dadd,
+            0x64 => Instruction::Isub,
+            0x65 => Instruction::Lsub,
+            0x66 => Instruction::Fsub,
+            0x67 => Instruction::Dsub,
+            0x68 => Instruction::Imul,
+            0x69 => Instruction::Lmul,
+            0x6A => Instruction::Fmul,
+            0x6B => Instruction::Dmul,
+            0x6C => Instruction::Idiv,
+            0x6D => Instruction::Ldiv,
+            0x6E => Instruction::Fdiv,
+            0x6F => Instruction::Ddiv,
+            0x70 => Instruction::Irem,
+            0x71 => Instruction::Lrem,
+            0x72 => Instruction::Frem,
+            0x73 => Instruction::Drem,
+            0x74 => Instruction::Ineg,
+            0x75 => Instruction::Lneg,
+            0x76 => Instruction::Fneg,
+            0x77 => Instruction::Dneg,
+            0x78 => Instruction::Ishl,
+            0x79 => Instruction::Lshl,
+            0x7A => Instruction::Ishr,
+            0x7B => Instruction::Lshr,
+            0x7C => Instruction::Iushr,
+            0x7D => Instruction::Lushr,
+            0x7E => Instruction::Iand,
+            0x7F => Instruction::Land,
+            0x80 => Instruction::Ior,
+            0x81 => Instruction::Lor,
+            0x82 => Instruction::Ixor,
+            0x83 => Instruction::Lxor,
+            0x84 => Instruction::Iinc {
+                index: cursor.read_u8()? as u16,
+                constant: cursor.read_i8()? as i16
+            },
+            0x85 => Instruction::I2l,
+            0x86 => Instruction::I2f,
+            0x87 => Instruction::I2d,
+            0x88 => Instruction::L2i,
+            0x89 => Instruction::L2f,
+            0x8A => Instruction::L2d,
+            0x8B => Instruction::F2i,
+            0x8C => Instruction::F2l,
+            0x8D => Instruction::F2d,
+            0x8E => Instruction::D2i,
+            0x8F => Instruction::D2l,
+            0x90 => Instruction::D2f,
+            0x91 => Instruction::I2b,
+            0x92 => Instruction::I2c,
+            0x93 => Instruction::I2s,
+            0x94 => Instruction::Lcmp,
+            0x95 => Instruction::Fcmpl,
+            0x96 => Instruction::Fcmpg,
+            0x97 => Instruction::Dcmpl,
+            0x98 => Instruction::Dcmpg,
+            0x99 => Instruction::Ifeq(cursor.read_i16::<BigEndian>()?),
+            0x9A => Instruction::Ifne(cursor.read_i16::<BigEndian>()?),
+            0x9B => Instruction::Iflt(cursor.read_i16::<BigEndian>()?),
+            0x9C => Instruction::Ifge(cursor.read_i16::<BigEndian>()?),
+            0x9D => Instruction::Ifgt(cursor.read_i16::<BigEndian>()?),
+            0x9E => Instruction::Ifle(cursor.read_i16::<BigEndian>()?),
+            0x9F => Instruction::IfIcmpeq(cursor.read_i16::<BigEndian>()?),
+            0xA0 => Instruction::IfIcmpne(cursor.read_i16::<BigEndian>()?),
+            0xA1 => Instruction::IfIcmplt(cursor.read_i16::<BigEndian>()?),
+            0xA2 => Instruction::IfIcmpge(cursor.read_i16::<BigEndian>()?),
+            0xA3 => Instruction::IfIcmpgt(cursor.read_i16::<BigEndian>()?),
+            0xA4 => Instruction::IfIcmple(cursor.read_i16::<BigEndian>()?),
+            0xA5 => Instruction::IfAcmpeq(cursor.read_i16::<BigEndian>()?),
+            0xA6 => Instruction::IfAcmpne(cursor.read_i16::<BigEndian>()?),
+            0xA7 => Instruction::Goto(cursor.read_i16::<BigEndian>()?),
+            0xA8 => Instruction::Jsr(cursor.read_i16::<BigEndian>()?),
+            0xA9 => Instruction::Ret(cursor.read_u8()? as u16),
+            0xAA => decode_tableswitch(cursor, pc)?,
+            0xAB => decode_lookupswitch(cursor, pc)?,
+            0xAC => Instruction::Ireturn,
+            0xAD => Instruction::Lreturn,
+            0xAE => Instruction::Freturn,
+            0xAF => Instruction::Dreturn,
+            0xB0 => Instruction::Areturn,
+            0xB1 => Instruction::Return,
+            0xB2 => Instruction::Getstatic(cursor.read_u16::<BigEndian>()?),
+            0xB3 => Instruction::Putstatic(cursor.read_u16::<BigEndian>()?),
+            0xB4 => Instruction::Getfield(cursor.read_u16::<BigEndian>()?),
+            0xB5 => Instruction::Putfield(cursor.read_u16::<BigEndian>()?),
+            0xB6 => Instruction::Invokevirtual(cursor.read_u16::<BigEndian>()?),
+            0xB7 => Instruction::Invokespecial(cursor.read_u16::<BigEndian>()?),
+            0xB8 => Instruction::Invokestatic(cursor.read_u16::<BigEndian>()?),
+            0xB9 => {
+                let index = cursor.read_u16::<BigEndian>()?;
+                let count = cursor.read_u8()?;
+                cursor.read_u8()?;
+                Instruction::Invokeinterface {
+                    index,
+                    count
+                }
+            }
+            0xBA => {
+                let index = cursor.read_u16::<BigEndian>()?;
+                cursor.read_u16::<BigEndian>()?;
+                Instruction::Invokedynamic(index)
+            }
+            0xBB => Instruction::New(cursor.read_u16::<BigEndian>()?),
+            0xBC => Instruction::Newarray(cursor.read_u8()?),
+            0xBD => Instruction::Anewarray(cursor.read_u16::<BigEndian>()?),
+            0xBE => Instruction::Arraylength,
+            0xBF => Instruction::Athrow,
+            0xC0 => Instruction::Checkcast(cursor.read_u16::<BigEndian>()?),
+            0xC1 => Instruction::Instanceof(cursor.read_u16::<BigEndian>()?),
+            0xC2 => Instruction::Monitorenter,
+            0xC3 => Instruction::Monitorexit,
+            0xC4 => decode_wide(cursor, pc)?,
+            0xC5 => Instruction::Multianewarray {
+                index: cursor.read_u16::<BigEndian>()?,
+                dimensions: cursor.read_u8()?
+            },
+            0xC6 => Instruction::Ifnull(cursor.read_i16::<BigEndian>()?),
+            0xC7 => Instruction::Ifnonnull(cursor.read_i16::<BigEndian>()?),
+            0xC8 => Instruction::GotoW(cursor.read_i32::<BigEndian>()?),
+            0xC9 => Instruction::JsrW(cursor.read_i32::<BigEndian>()?),
+            unexpected => return Err(DeserializationError::Parsing(format!("Invalid opcode {unexpected:#04x} at pc {pc}")))
+        })
+    }
+
+    #[inline(always)]
+    fn const_pool_entry(class: &Class, index: u16) -> Result<&ConstPoolType, DeserializationError> {
+        class.const_pool_entry(index).ok_or(DeserializationError::Link)
+    }
+
+    #[inline(always)]
+    fn type_slots(descriptor: &str) -> i32 {
+        match descriptor.as_bytes().first() {
+            Some(b'J' | b'D') => 2,
+            Some(b'V') => 0,
+            _ => 1
+        }
+    }
+
+    // Returns the operand stack slots taken by the arguments and by the return value.
+    fn method_slots(descriptor: &str) -> Result<(i32, i32), DeserializationError> {
+        let invalid = || DeserializationError::Parsing(format!("Invalid method descriptor `{descriptor}`"));
+        let (arguments, result) = descriptor.strip_prefix('(')
+            .and_then(|descriptor| descriptor.split_once(')'))
+            .ok_or_else(invalid)?;
+        let mut slots = 0;
+        let mut rest = arguments.as_bytes();
+        while let Some(&first) = rest.first() {
+            let dimensions = rest.iter().take_while(|byte| **byte == b'[').count();
+            let length = match rest.get(dimensions) {
+                Some(b'L') => rest.iter().position(|byte| *byte == b';').ok_or_else(invalid)? + 1,
+                Some(b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z') => dimensions + 1,
+                _ => return Err(invalid())
+            };
+            slots += if dimensions == 0 && matches!(first, b'J' | b'D') { 2 } else { 1 };
+            rest = &rest[length..];
+        }
+        Ok((slots, type_slots(result)))
+    }
+
+    #[inline(always)]
+    fn field_slots(class: &Class, index: u16) -> Result<i32, DeserializationError> {
+        match const_pool_entry(class, index)? {
+            ConstPoolType::Field(field) => Ok(type_slots(&field.name_and_type.descriptor)),
+            _ => Err(DeserializationError::Link)
+        }
+    }
+
+    #[inline(always)]
+    fn invoke_slots(class: &Class, index: u16) -> Result<i32, DeserializationError> {
+        let descriptor = match const_pool_entry(class, index)? {
+            ConstPoolType::MethodRef(method) | ConstPoolType::InterfaceMethodRef(method) => &method.name_and_type.descriptor,
+            ConstPoolType::InvokeDynamic(dynamic) => &dynamic.name_and_type.descriptor,
+            _ => return Err(DeserializationError::Link)
+        };
+        let (arguments, result) = method_slots(descriptor)?;
+        Ok(result - arguments)
+    }
+
+    // Net change of the operand stack depth in slots; long and double values take two. Field and invoke instructions
+    // take their type from the constant pool entry they name, so an index to a missing or different entry fails with
+    // DeserializationError::Link instead of panicking; compute_max_stack hands that on for a malformed method.
+    pub fn stack_effect(instruction: &Instruction, class: &Class) -> Result<i32, DeserializationError> {
+        Ok(match instruction {
+            Instruction::Lconst0 | Instruction::Lconst1 | Instruction::Dconst0 | Instruction::Dconst1
+            | Instruction::Ldc2W(_) | Instruction::Lload(_) | Instruction::Dload(_) | Instruction::Lload0
+            | Instruction::Lload1 | Instruction::Lload2 | Instruction::Lload3 | Instruction::Dload0
+            | Instruction::Dload1 | Instruction::Dload2 | Instruction::Dload3 | Instruction::Dup2 | Instruction::Dup2X1
+            | Instruction::Dup2X2 => 2,
+            Instruction::AconstNull | Instruction::IconstM1 | Instruction::Iconst0 | Instruction::Iconst1
+            | Instruction::Iconst2 | Instruction::Iconst3 | Instruction::Iconst4 | Instruction::Iconst5
+            | Instruction::Fconst0 | Instruction::Fconst1 | Instruction::Fconst2 | Instruction::Bipush(_)
+            | Instruction::Sipush(_) | Instruction::Ldc(_) | Instruction::LdcW(_) | Instruction::Iload(_)
+            | Instruction::Fload(_) | Instruction::Aload(_) | Instruction::Iload0 | Instruction::Iload1
+            | Instruction::Iload2 | Instruction::Iload3 | Instruction::Fload0 | Instruction::Fload1
+            | Instruction::Fload2 | Instruction::Fload3 | Instruction::Aload0 | Instruction::Aload1
+            | Instruction::Aload2 | Instruction::Aload3 | Instruction::Dup | Instruction::DupX1 | Instruction::DupX2
+            | Instruction::I2l | Instruction::I2d | Instruction::F2l | Instruction::F2d | Instruction::Jsr(_)
+            | Instruction::New(_) | Instruction::JsrW(_) => 1,
+            Instruction::Nop | Instruction::Laload | Instruction::Daload | Instruction::Swap | Instruction::Ineg
+            | Instruction::Lneg | Instruction::Fneg | Instruction::Dneg | Instruction::Iinc { .. } | Instruction::I2f
+            | Instruction::L2d | Instruction::F2i | Instruction::D2l | Instruction::I2b | Instruction::I2c
+            | Instruction::I2s | Instruction::Goto(_) | Instruction::Ret(_) | Instruction::Return
+            | Instruction::Newarray(_) | Instruction::Anewarray(_) | Instruction::Arraylength
+            | Instruction::Checkcast(_) | Instruction::Instanceof(_) | Instruction::GotoW(_) => 0,
+            Instruction::Iaload | Instruction::Faload | Instruction::Aaload | Instruction::Baload | Instruction::Caload
+            | Instruction::Saload | Instruction::Istore(_) | Instruction::Fstore(_) | Instruction::Astore(_)
+            | Instruction::Istore0 | Instruction::Istore1 | Instruction::Istore2 | Instruction::Istore3
+            | Instruction::Fstore0 | Instruction::Fstore1 | Instruction::Fstore2 | Instruction::Fstore3
+            | Instruction::Astore0 | Instruction::Astore1 | Instruction::Astore2 | Instruction::Astore3
+            | Instruction::Pop | Instruction::Iadd | Instruction::Fadd | Instruction::Isub | Instruction::Fsub
+            | Instruction::Imul | Instruction::Fmul | Instruction::Idiv | Instruction::Fdiv | Instruction::Irem
+            | Instruction::Frem | Instruction::Ishl | Instruction::Lshl | Instruction::Ishr | Instruction::Lshr
+            | Instruction::Iushr | Instruction::Lushr | Instruction::Iand | Instruction::Ior | Instruction::Ixor
+            | Instruction::L2i | Instruction::L2f | Instruction::D2i | Instruction::D2f | Instruction::Fcmpl
+            | Instruction::Fcmpg | Instruction::Ifeq(_) | Instruction::Ifne(_) | Instruction::Iflt(_)
+            | Instruction::Ifge(_) | Instruction::Ifgt(_) | Instruction::Ifle(_) | Instruction::Tableswitch { .. }
+            | Instruction::Lookupswitch { .. } | Instruction::Ireturn | Instruction::Freturn | Instruction::Areturn
+            | Instruction::Athrow | Instruction::Monitorenter | Instruction::Monitorexit | Instruction::Ifnull(_)
+            | Instruction::Ifnonnull(_) => -1,
+            Instruction::Lstore(_) | Instruction::Dstore(_) | Instruction::Lstore0 | Instruction::Lstore1
+            | Instruction::Lstore2 | Instruction::Lstore3 | Instruction::Dstore0 | Instruction::Dstore1
+            | Instruction::Dstore2 | Instruction::Dstore3 | Instruction::Pop2 | Instruction::Ladd | Instruction::Dadd
+            | Instruction::Lsub | Instruction::Dsub | Instruction::Lmul | Instruction::Dmul | Instruction::Ldiv
+            | Instruction::Ddiv | Instruction::Lrem | Instruction::Drem | Instruction::Land | Instruction::Lor
+            | Instruction::Lxor | Instruction::IfIcmpeq(_) | Instruction::IfIcmpne(_) | Instruction::IfIcmplt(_)
+            | Instruction::IfIcmpge(_) | Instruction::IfIcmpgt(_) | Instruction::IfIcmple(_) | Instruction::IfAcmpeq(_)
+            | Instruction::IfAcmpne(_) | Instruction::Lreturn | Instruction::Dreturn => -2,
+            Instruction::Iastore | Instruction::Fastore | Instruction::Aastore | Instruction::Bastore
+            | Instruction::Castore | Instruction::Sastore | Instruction::Lcmp | Instruction::Dcmpl | Instruction::Dcmpg => -3,
+            Instruction::Lastore | Instruction::Dastore => -4,
+            Instruction::Getstatic(index) => field_slots(class, *index)?,
+            Instruction::Putstatic(index) => -field_slots(class, *index)?,
+            Instruction::Getfield(index) => field_slots(class, *index)? - 1,
+            Instruction::Putfield(index) => -field_slots(class, *index)? - 1,
+            Instruction::Invokevirtual(index) | Instruction::Invokespecial(index) => invoke_slots(class, *index)? - 1,
+            Instruction::Invokeinterface { index, .. } => invoke_slots(class, *index)? - 1,
+            Instruction::Invokestatic(index) | Instruction::Invokedynamic(index) => invoke_slots(class, *index)?,
+            Instruction::Multianewarray { dimensions, .. } => 1 - *dimensions as i32
+        })
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum InvokeKind {
+        Virtual,
+        Special,
+        Static,
+        Interface
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Callee {
+        pub owner: String,
+        pub name: String,
+        pub descriptor: String,
+        pub kind: InvokeKind
+    }
+
+    pub trait MethodBytecode {
+        fn callees(&self, class: &Class) -> Result<Vec<Callee>, DeserializationError>;
+    }
+
+    // invokedynamic call sites have no static owner and are not reported.
+    impl MethodBytecode for MethodInfo {
+        fn callees(&self, class: &Class) -> Result<Vec<Callee>, DeserializationError> {
+            let code = match self.code() {
+                Some(code) => code,
+                None => return Ok(vec![])
+            };
+            decode(&code.code)?.into_iter()
+                .filter_map(|(_, instruction)| match instruction {
+                    Instruction::Invokevirtual(index) => Some((index, InvokeKind::Virtual)),
+                    Instruction::Invokespecial(index) => Some((index, InvokeKind::Special)),
+                    Instruction::Invokestatic(index) => Some((index, InvokeKind::Static)),
+                    Instruction::Invokeinterface { index, .. } => Some((index, InvokeKind::Interface)),
+                    _ => None
+                })
+                .map(|(index, kind)| match const_pool_entry(class, index)? {
+                    ConstPoolType::MethodRef(method) | ConstPoolType::InterfaceMethodRef(method) => Ok(Callee {
+                        owner: method.class.0.replace('/', "."),
+                        name: method.name_and_type.name.to_string(),
+                        descriptor: method.name_and_type.descriptor.to_string(),
+                        kind
+                    }),
+                    _ => Err(DeserializationError::Link)
+                })
+                .collect()
+        }
+    }
+
+    // Operands are written big-endian; local indices and iinc operands that do not fit a byte use the wide form.
+    fn assemble_instruction(instruction: &Instruction, pc: usize, out: &mut Vec<u8>) -> Result<(), SerializationError> {
+        let opcode = instruction.opcode();
+        match instruction {
+            Instruction::Bipush(value) => out.extend([opcode, *value as u8]),
+            Instruction::Sipush(value) => {
+                out.push(opcode);
+                out.extend(value.to_be_bytes());
+            }
+            Instruction::Ldc(index) | Instruction::Newarray(index) => out.extend([opcode, *index]),
+            Instruction::LdcW(index) | Instruction::Ldc2W(index) | Instruction::Getstatic(index)
+            | Instruction::Putstatic(index) | Instruction::Getfield(index) | Instruction::Putfield(index)
+            | Instruction::Invokevirtual(index) | Instruction::Invokespecial(index) | Instruction::Invokestatic(index)
+            | Instruction::New(index) | Instruction::Anewarray(index) | Instruction::Checkcast(index)
+            | Instruction::Instanceof(index) => {
+                out.push(opcode);
+                out.extend(index.to_be_bytes());
+            }
+            Instruction::Iload(index) | Instruction::Lload(index) | Instruction::Fload(index)
+            | Instruction::Dload(index) | Instruction::Aload(index) | Instruction::Istore(index)
+            | Instruction::Lstore(index) | Instruction::Fstore(index) | Instruction::Dstore(index)
+            | Instruction::Astore(index) | Instruction::Ret(index) => match u8::try_from(*index) {
+                Ok(index) => out.extend([opcode, index]),
+                Err(_) => {
+                    out.extend([0xC4, opcode]);
+                    out.extend(index.to_be_bytes());
+                }
+            },
+            Instruction::Iinc { index, constant } => match (u8::try_from(*index), i8::try_from(*constant)) {
+                (Ok(index), Ok(constant)) => out.extend([opcode, index, constant as u8]),
+                _ => {
+                    out.extend([0xC4, opcode]);
+                    out.extend(index.to_be_bytes());
+                    out.extend(constant.to_be_bytes());
+                }
+            },
+            Instruction::Ifeq(offset) | Instruction::Ifne(offset) | Instruction::Iflt(offset)
+            | Instruction::Ifge(offset) | Instruction::Ifgt(offset) | Instruction::Ifle(offset)
+            | Instruction::IfIcmpeq(offset) | Instruction::IfIcmpne(offset) | Instruction::IfIcmplt(offset)
+            | Instruction::IfIcmpge(offset) | Instruction::IfIcmpgt(offset) | Instruction::IfIcmple(offset)
+            | Instruction::IfAcmpeq(offset) | Instruction::IfAcmpne(offset) | Instruction::Goto(offset)
+            | Instruction::Jsr(offset) | Instruction::Ifnull(offset) | Instruction::Ifnonnull(offset) => {
+                out.push(opcode);
+                out.extend(offset.to_be_bytes());
+            }
+            Instruction::GotoW(offset) | Instruction::JsrW(offset) => {
+                out.push(opcode);
+                out.extend(offset.to_be_bytes());
+            }
+            Instruction::Tableswitch { default, low, high, offsets } => {
+                if *high as i64 - *low as i64 + 1 != offsets.len() as i64 {
+                    return Err(SerializationError::Unsupported(format!("tableswitch at pc {pc} has {} offsets for {low}..={high}", offsets.len())));
+                }
+                out.push(opcode);
+                out.extend(std::iter::repeat_n(0, 3 - pc % 4));
+                [*default, *low, *high].iter().chain(offsets).for_each(|value| out.extend(value.to_be_bytes()));
+            }
+            Instruction::Lookupswitch { default, pairs } => {
+                out.push(opcode);
+                out.extend(std::iter::repeat_n(0, 3 - pc % 4));
+                out.extend(default.to_be_bytes());
+                out.extend((pairs.len() as i32).to_be_bytes());
+                for (key, offset) in pairs {
+                    out.extend(key.to_be_bytes());
+                    out.extend(offset.to_be_bytes());
+                }
+            }
+            Instruction::Invokeinterface { index, count } => {
+                out.push(opcode);
+                out.extend(index.to_be_bytes());
+                out.extend([*count, 0]);
+            }
+            Instruction::Invokedynamic(index) => {
+                out.push(opcode);
+                out.extend(index.to_be_bytes());
+                out.extend([0, 0]);
+            }
+            Instruction::Multianewarray { index, dimensions } => {
+                out.push(opcode);
+                out.extend(index.to_be_bytes());
+                out.push(*dimensions);
+            }
+            _ => out.push(opcode)
+        }
+        Ok(())
+    }
+
+    pub fn assemble(instructions: &[Instruction]) -> Result<Vec<u8>, SerializationError> {
+        let mut code = vec![];
+        for instruction in instructions {
+            assemble_instruction(instruction, code.len(), &mut code)?;
+        }
+        Ok(code)
+    }
+
+    #[inline(always)]
+    fn local_slots(instruction: &Instruction) -> Option<u32> {
+        Some(match instruction {
+            Instruction::Iload(index) | Instruction::Fload(index) | Instruction::Aload(index) | Instruction::Istore(index)
+            | Instruction::Fstore(index) | Instruction::Astore(index) | Instruction::Ret(index)
+            | Instruction::Iinc { index, .. } => *index as u32 + 1,
+            Instruction::Lload(index) | Instruction::Dload(index) | Instruction::Lstore(index)
+            | Instruction::Dstore(index) => *index as u32 + 2,
+            Instruction::Iload0 | Instruction::Fload0 | Instruction::Aload0 | Instruction::Istore0 | Instruction::Fstore0
+            | Instruction::Astore0 => 1,
+            Instruction::Iload1 | Instruction::Fload1 | Instruction::Aload1 | Instruction::Istore1 | Instruction::Fstore1
+            | Instruction::Astore1 | Instruction::Lload0 | Instruction::Dload0 | Instruction::Lstore0
+            | Instruction::Dstore0 => 2,
+            Instruction::Iload2 | Instruction::Fload2 | Instruction::Aload2 | Instruction::Istore2 | Instruction::Fstore2
+            | Instruction::Astore2 | Instruction::Lload1 | Instruction::Dload1 | Instruction::Lstore1
+            | Instruction::Dstore1 => 3,
+            Instruction::Iload3 | Instruction::Fload3 | Instruction::Aload3 | Instruction::Istore3 | Instruction::Fstore3
+            | Instruction::Astore3 | Instruction::Lload2 | Instruction::Dload2 | Instruction::Lstore2
+            | Instruction::Dstore2 => 4,
+            Instruction::Lload3 | Instruction::Dload3 | Instruction::Lstore3 | Instruction::Dstore3 => 5,
+            _ => return None
+        })
+    }
+
+    // Returns the branch targets of an instruction and whether execution can fall through to the next one.
+    fn successors(instruction: &Instruction, pc: u32) -> (Vec<i64>, bool) {
+        let target = |offset: i32| pc as i64 + offset as i64;
+        match instruction {
+            Instruction::Goto(offset) => (vec![target(*offset as i32)], false),
+            Instruction::GotoW(offset) => (vec![target(*offset)], false),
+            Instruction::Ifeq(offset) | Instruction::Ifne(offset) | Instruction::Iflt(offset) | Instruction::Ifge(offset)
+            | Instruction::Ifgt(offset) | Instruction::Ifle(offset) | Instruction::IfIcmpeq(offset)
+            | Instruction::IfIcmpne(offset) | Instruction::IfIcmplt(offset) | Instruction::IfIcmpge(offset)
+            | Instruction::IfIcmpgt(offset) | Instruction::IfIcmple(offset) | Instruction::IfAcmpeq(offset)
+            | Instruction::IfAcmpne(offset) | Instruction::Ifnull(offset) | Instruction::Ifnonnull(offset)
+            | Instruction::Jsr(offset) => (vec![target(*offset as i32)], true),
+            Instruction::JsrW(offset) => (vec![target(*offset)], true),
+            Instruction::Tableswitch { default, offsets, .. } =>
+                (offsets.iter().chain([default]).map(|offset| target(*offset)).collect(), false),
+            Instruction::Lookupswitch { default, pairs } =>
+                (pairs.iter().map(|(_, offset)| offset).chain([default]).map(|offset| target(*offset)).collect(), false),
+            Instruction::Ireturn | Instruction::Lreturn | Instruction::Freturn | Instruction::Dreturn | Instruction::Areturn
+            | Instruction::Return | Instruction::Athrow | Instruction::Ret(_) => (vec![], false),
+            _ => (vec![], true)
+        }
+    }
+
+    // The slot an instruction loads from or stores to, including the implicit index of the short forms.
+    fn local_index(instruction: &Instruction) -> Option<u16> {
+        Some(match instruction {
+            Instruction::Iload(index) | Instruction::Lload(index) | Instruction::Fload(index) | Instruction::Dload(index)
+            | Instruction::Aload(index) | Instruction::Istore(index) | Instruction::Lstore(index)
+            | Instruction::Fstore(index) | Instruction::Dstore(index) | Instruction::Astore(index) => *index,
+            Instruction::Iload0 | Instruction::Lload0 | Instruction::Fload0 | Instruction::Dload0 | Instruction::Aload0
+            | Instruction::Istore0 | Instruction::Lstore0 | Instruction::Fstore0 | Instruction::Dstore0
+            | Instruction::Astore0 => 0,
+            Instruction::Iload1 | Instruction::Lload1 | Instruction::Fload1 | Instruction::Dload1 | Instruction::Aload1
+            | Instruction::Istore1 | Instruction::Lstore1 | Instruction::Fstore1 | Instruction::Dstore1
+            | Instruction::Astore1 => 1,
+            Instruction::Iload2 | Instruction::Lload2 | Instruction::Fload2 | Instruction::Dload2 | Instruction::Aload2
+            | Instruction::Istore2 | Instruction::Lstore2 | Instruction::Fstore2 | Instruction::Dstore2
+            | Instruction::Astore2 => 2,
+            Instruction::Iload3 | Instruction::Lload3 | Instruction::Fload3 | Instruction::Dload3 | Instruction::Aload3
+            | Instruction::Istore3 | Instruction::Lstore3 | Instruction::Fstore3 | Instruction::Dstore3
+            | Instruction::Astore3 => 3,
+            _ => return None
+        })
+    }
+
+    #[inline(always)]
+    fn object_type(name: &str) -> VerificationType {
+        VerificationType::Object(ClassInfo(Utf8Info::from(name)))
+    }
+
+    #[inline(always)]
+    fn is_wide(value: &VerificationType) -> bool {
+        matches!(value, VerificationType::Long | VerificationType::Double)
+    }
+
+    fn value_type(field_type: &FieldType) -> VerificationType {
+        match field_type {
+            FieldType::Long => VerificationType::Long,
+            FieldType::Float => VerificationType::Float,
+            FieldType::Double => VerificationType::Double,
+            FieldType::Object(name) => object_type(name),
+            FieldType::Array(_) => object_type(&field_type.to_string()),
+            _ => VerificationType::Integer
+        }
+    }
+
+    #[inline(always)]
+    fn method_descriptor(descriptor: &str) -> Result<MethodDescriptor, DeserializationError> {
+        parse_method_descriptor(descriptor).map_err(|error| DeserializationError::Parsing(error.to_string()))
+    }
+
+    #[inline(always)]
+    fn field_type(descriptor: &str) -> Result<VerificationType, DeserializationError> {
+        parse_field_descriptor(descriptor)
+            .map(|field_type| value_type(&field_type))
+            .map_err(|error| DeserializationError::Parsing(error.to_string()))
+    }
+
+    #[inline(always)]
+    fn class_operand(class: &Class, index: u16) -> Result<ClassInfo, DeserializationError> {
+        match const_pool_entry(class, index)? {
+            ConstPoolType::Class(info) => Ok(info.clone()),
+            _ => Err(DeserializationError::Link)
+        }
+    }
+
+    fn constant_type(class: &Class, index: u16) -> Result<VerificationType, DeserializationError> {
+        Ok(match const_pool_entry(class, index)? {
+            ConstPoolType::Int(_) => VerificationType::Integer,
+            ConstPoolType::Float(_) => VerificationType::Float,
+            ConstPoolType::Long(_) => VerificationType::Long,
+            ConstPoolType::Double(_) => VerificationType::Double,
+            ConstPoolType::String(_) => object_type("java/lang/String"),
+            ConstPoolType::Class(_) => object_type("java/lang/Class"),
+            ConstPoolType::MethodType(_) => object_type("java/lang/invoke/MethodType"),
+            ConstPoolType::MethodHandle(_) => object_type("java/lang/invoke/MethodHandle"),
+            ConstPoolType::Dynamic(dynamic) => field_type(&dynamic.name_and_type.descriptor)?,
+            _ => return Err(DeserializationError::Link)
+        })
+    }
+
+    // The element loaded by aaload from an array reference; null stays null.
+    fn component_type(array: &VerificationType) -> VerificationType {
+        match array {
+            VerificationType::Object(class) => class.0.strip_prefix('[')
+                .and_then(|component| parse_field_descriptor(component).ok())
+                .map_or(VerificationType::Top, |component| value_type(&component)),
+            VerificationType::Null => VerificationType::Null,
+            _ => VerificationType::Top
+        }
+    }
+
+    #[inline(always)]
+    fn primitive_array(atype: u8, pc: u32) -> Result<VerificationType, DeserializationError> {
+        let descriptor = match atype {
+            4 => "[Z",
+            5 => "[C",
+            6 => "[F",
+            7 => "[D",
+            8 => "[B",
+            9 => "[S",
+            10 => "[I",
+            11 => "[J",
+            unexpected => return Err(DeserializationError::Parsing(format!("Invalid newarray type {unexpected} at pc {pc}")))
+        };
+        Ok(object_type(descriptor))
+    }
+
+    // Other classes are not loaded, so two different references can only be merged into java/lang/Object.
+    fn merge_type(current: &VerificationType, incoming: &VerificationType) -> VerificationType {
+        match (current, incoming) {
+            _ if current == incoming => current.clone(),
+            (VerificationType::Null, VerificationType::Object(_)) => incoming.clone(),
+            (VerificationType::Object(_), VerificationType::Null) => current.clone(),
+            (VerificationType::Object(_), VerificationType::Object(_)) => object_type("java/lang/Object"),
+            _ => VerificationType::Top
+        }
+    }
+
+    // Locals and stack hold one entry per slot with a long or double followed by Top, as the verifier sees them.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TypeState {
+        locals: Vec<VerificationType>,
+        stack: Vec<VerificationType>
+    }
+
+    impl TypeState {
+        fn initial(class: &Class, method: &MethodInfo) -> Result<TypeState, DeserializationError> {
+            let descriptor = method_descriptor(&method.descriptor)?;
+            let mut state = TypeState { locals: vec![], stack: vec![] };
+            if !method.access.contains(MethodAccessSpecifier::Static) {
+                let is_constructor = method.name.as_str() == "<init>" && class.this_class.0.as_str() != "java/lang/Object";
+                state.locals.push(if is_constructor {
+                    VerificationType::UninitializedThis
+                } else {
+                    VerificationType::Object(class.this_class.clone())
+                });
+            }
+            for parameter in &descriptor.parameters {
+                state.store(state.locals.len() as u16, value_type(parameter));
+            }
+            Ok(state)
+        }
+
+        fn pop(&mut self, slots: usize, pc: u32) -> Result<Vec<VerificationType>, DeserializationError> {
+            let depth = self.stack.len().checked_sub(slots)
+                .ok_or_else(|| DeserializationError::Parsing(format!("Operand stack underflow at pc {pc}")))?;
+            Ok(self.stack.split_off(depth))
+        }
+
+        fn push(&mut self, value: VerificationType) {
+            let wide = is_wide(&value);
+            self.stack.push(value);
+            if wide {
+                self.stack.push(VerificationType::Top);
+            }
+        }
+
+        #[inline(always)]
+        fn replace(&mut self, slots: usize, value: VerificationType, pc: u32) -> Result<(), DeserializationError> {
+            self.pop(slots, pc)?;
+            self.push(value);
+            Ok(())
+        }
+
+        // Copies the top `slots` slots below the `skip` slots under them, covering the whole dup family.
+        fn duplicate(&mut self, slots: usize, skip: usize, pc: u32) -> Result<(), DeserializationError> {
+            let copied = self.stack.len().checked_sub(slots)
+                .filter(|start| *start >= skip)
+                .map(|start| self.stack[start..].to_vec())
+                .ok_or_else(|| DeserializationError::Parsing(format!("Operand stack underflow at pc {pc}")))?;
+            let at = self.stack.len() - slots - skip;
+            self.stack.splice(at..at, copied);
+            Ok(())
+        }
+
+        fn load(&self, index: u16, pc: u32) -> Result<VerificationType, DeserializationError> {
+            self.locals.get(index as usize).cloned()
+                .ok_or_else(|| DeserializationError::Parsing(format!("Local {index} is read before it is set at pc {pc}")))
+        }
+
+        fn store(&mut self, index: u16, value: VerificationType) {
+            let index = index as usize;
+            let wide = is_wide(&value);
+            let end = index + if wide { 2 } else { 1 };
+            if self.locals.len() < end {
+                self.locals.resize(end, VerificationType::Top);
+            }
+            // Overwriting the second half of a long or double leaves the first half unusable.
+            if index > 0 && is_wide(&self.locals[index - 1]) {
+                self.locals[index - 1] = VerificationType::Top;
+            }
+            if wide {
+                self.locals[index + 1] = VerificationType::Top;
+            }
+            self.locals[index] = value;
+        }
+
+        // A local that is not read again before being overwritten is out of scope for javac, so it becomes Top; the
+        // parameters stay in scope for the whole method.
+        fn forget(&mut self, live: &[bool], parameters: usize) {
+            for (slot, value) in self.locals.iter_mut().enumerate().skip(parameters) {
+                if !live.get(slot).copied().unwrap_or(false) {
+                    *value = VerificationType::Top;
+                }
+            }
+        }
+
+        // A constructor call turns every copy of the uninitialized reference into an instance of its class.
+        fn initialize(&mut self, uninitialized: &VerificationType, initialized: VerificationType) {
+            for value in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+                if value == uninitialized {
+                    *value = initialized.clone();
+                }
+            }
+        }
+
+        // Returns whether the state changed, in which case the instruction has to be interpreted again.
+        fn merge(&mut self, incoming: &TypeState, pc: u32) -> Result<bool, DeserializationError> {
+            if self.stack.len() != incoming.stack.len() {
+                return Err(DeserializationError::Parsing(format!("Paths reaching pc {pc} have different stack depths")));
+            }
+            let merged = TypeState {
+                locals: self.locals.iter().zip(&incoming.locals).map(|(current, incoming)| merge_type(current, incoming)).collect(),
+                stack: self.stack.iter().zip(&incoming.stack).map(|(current, incoming)| merge_type(current, incoming)).collect()
+            };
+            let changed = merged != *self;
+            *self = merged;
+            Ok(changed)
+        }
+    }
+
+    // Drops the Top that follows each long and double, giving the entries a frame lists.
+    fn frame_types(slots: &[VerificationType]) -> Vec<VerificationType> {
+        let mut types = vec![];
+        let mut slot = 0;
+        while let Some(value) = slots.get(slot) {
+            slot += if is_wide(value) { 2 } else { 1 };
+            types.push(value.clone());
+        }
+        types
+    }
+
+    // The slots each instruction may read before they are overwritten. A handler's live slots are live across the
+    // range it covers, as the exception can be thrown before the instruction stores anything.
+    fn live_locals(decoded: &[(u32, Instruction)], successors: &[Vec<usize>], handlers: &[Vec<usize>]) -> Vec<Vec<bool>> {
+        let slots = decoded.iter().filter_map(|(_, instruction)| local_slots(instruction)).max().unwrap_or(0) as usize;
+        let mut live = vec![vec![false; slots]; decoded.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for position in (0..decoded.len()).rev() {
+                let mut current = vec![false; slots];
+                for next in &successors[position] {
+                    current.iter_mut().zip(&live[*next]).for_each(|(slot, next)| *slot |= *next);
+                }
+                let instruction = &decoded[position].1;
+                match (local_index(instruction), instruction) {
+                    (Some(index), _) => {
+                        let read = instruction.mnemonic().contains("load");
+                        let end = local_slots(instruction).unwrap_or(index as u32 + 1) as usize;
+                        current[index as usize..end].iter_mut().for_each(|slot| *slot = read);
+                    }
+                    (None, Instruction::Iinc { index, .. } | Instruction::Ret(index)) => current[*index as usize] = true,
+                    _ => {}
+                }
+                for handler in &handlers[position] {
+                    current.iter_mut().zip(&live[*handler]).for_each(|(slot, handler)| *slot |= *handler);
+                }
+                if current != live[position] {
+                    live[position] = current;
+                    changed = true;
+                }
+            }
+        }
+        live
+    }
+
+    fn interpret(state: &mut TypeState, pc: u32, instruction: &Instruction, class: &Class,
+        allocations: &HashMap<u16, u16>) -> Result<(), DeserializationError> {
+        if let Some(index) = local_index(instruction) {
+            let mnemonic = instruction.mnemonic();
+            let typed = match mnemonic.as_bytes()[0] {
+                b'i' => Some(VerificationType::Integer),
+                b'l' => Some(VerificationType::Long),
+                b'f' => Some(VerificationType::Float),
+                b'd' => Some(VerificationType::Double),
+                _ => None
+            };
+            if mnemonic.contains("load") {
+                let value = match typed {
+                    Some(value) => value,
+                    None => state.load(index, pc)?
+                };
+                state.push(value);
+            } else {
+                let value = match typed {
+                    Some(value) => {
+                        state.pop(if is_wide(&value) { 2 } else { 1 }, pc)?;
+                        value
+                    }
+                    None => state.pop(1, pc)?.remove(0)
+                };
+                state.store(index, value);
+            }
+            return Ok(());
+        }
+        match instruction {
+            Instruction::Nop | Instruction::Iinc { .. } | Instruction::Goto(_) | Instruction::GotoW(_)
+            | Instruction::Ireturn | Instruction::Lreturn | Instruction::Freturn | Instruction::Dreturn
+            | Instruction::Areturn | Instruction::Return | Instruction::Athrow => {}
+            Instruction::AconstNull => state.push(VerificationType::Null),
+            Instruction::IconstM1 | Instruction::Iconst0 | Instruction::Iconst1 | Instruction::Iconst2 | Instruction::Iconst3
+            | Instruction::Iconst4 | Instruction::Iconst5 | Instruction::Bipush(_)
+            | Instruction::Sipush(_) => state.push(VerificationType::Integer),
+            Instruction::Lconst0 | Instruction::Lconst1 => state.push(VerificationType::Long),
+            Instruction::Fconst0 | Instruction::Fconst1 | Instruction::Fconst2 => state.push(VerificationType::Float),
+            Instruction::Dconst0 | Instruction::Dconst1 => state.push(VerificationType::Double),
+            Instruction::Ldc(index) => state.push(constant_type(class, *index as u16)?),
+            Instruction::LdcW(index) | Instruction::Ldc2W(index) => state.push(constant_type(class, *index)?),
+            Instruction::Ineg | Instruction::F2i | Instruction::I2b | Instruction::I2c | Instruction::I2s
+            | Instruction::Arraylength | Instruction::Instanceof(_) => state.replace(1, VerificationType::Integer, pc)?,
+            Instruction::Iaload | Instruction::Baload | Instruction::Caload | Instruction::Saload | Instruction::Iadd
+            | Instruction::Isub | Instruction::Imul | Instruction::Idiv | Instruction::Irem | Instruction::Ishl
+            | Instruction::Ishr | Instruction::Iushr | Instruction::Iand | Instruction::Ior | Instruction::Ixor
+            | Instruction::L2i | Instruction::D2i | Instruction::Fcmpl
+            | Instruction::Fcmpg => state.replace(2, VerificationType::Integer, pc)?,
+            Instruction::Lcmp | Instruction::Dcmpl | Instruction::Dcmpg => state.replace(4, VerificationType::Integer, pc)?,
+            Instruction::I2l | Instruction::F2l => state.replace(1, VerificationType::Long, pc)?,
+            Instruction::Laload | Instruction::Lneg | Instruction::D2l => state.replace(2, VerificationType::Long, pc)?,
+            Instruction::Lshl | Instruction::Lshr | Instruction::Lushr => state.replace(3, VerificationType::Long, pc)?,
+            Instruction::Ladd | Instruction::Lsub | Instruction::Lmul | Instruction::Ldiv | Instruction::Lrem
+            | Instruction::Land | Instruction::Lor | Instruction::Lxor => state.replace(4, VerificationType::Long, pc)?,
+            Instruction::Fneg | Instruction::I2f => state.replace(1, VerificationType::Float, pc)?,
+            Instruction::Faload | Instruction::Fadd | Instruction::Fsub | Instruction::Fmul | Instruction::Fdiv
+            | Instruction::Frem | Instruction::L2f | Instruction::D2f => state.replace(2, VerificationType::Float, pc)?,
+            Instruction::I2d | Instruction::F2d => state.replace(1, VerificationType::Double, pc)?,
+            Instruction::Daload | Instruction::Dneg | Instruction::L2d => state.replace(2, VerificationType::Double, pc)?,
+            Instruction::Dadd | Instruction::Dsub | Instruction::Dmul | Instruction::Ddiv
+            | Instruction::Drem => state.replace(4, VerificationType::Double, pc)?,
+            Instruction::Aaload => {
+                let array = state.pop(2, pc)?.remove(0);
+                state.push(component_type(&array));
+            }
+            Instruction::Pop | Instruction::Ifeq(_) | Instruction::Ifne(_) | Instruction::Iflt(_) | Instruction::Ifge(_)
+            | Instruction::Ifgt(_) | Instruction::Ifle(_) | Instruction::Ifnull(_) | Instruction::Ifnonnull(_)
+            | Instruction::Tableswitch { .. } | Instruction::Lookupswitch { .. } | Instruction::Monitorenter
+            | Instruction::Monitorexit => {
+                state.pop(1, pc)?;
+            }
+            Instruction::Pop2 | Instruction::IfIcmpeq(_) | Instruction::IfIcmpne(_) | Instruction::IfIcmplt(_)
+            | Instruction::IfIcmpge(_) | Instruction::IfIcmpgt(_) | Instruction::IfIcmple(_) | Instruction::IfAcmpeq(_)
+            | Instruction::IfAcmpne(_) => {
+                state.pop(2, pc)?;
+            }
+            Instruction::Iastore | Instruction::Fastore | Instruction::Aastore | Instruction::Bastore | Instruction::Castore
+            | Instruction::Sastore => {
+                state.pop(3, pc)?;
+            }
+            Instruction::Lastore | Instruction::Dastore => {
+                state.pop(4, pc)?;
+            }
+            Instruction::Dup => state.duplicate(1, 0, pc)?,
+            Instruction::DupX1 => state.duplicate(1, 1, pc)?,
+            Instruction::DupX2 => state.duplicate(1, 2, pc)?,
+            Instruction::Dup2 => state.duplicate(2, 0, pc)?,
+            Instruction::Dup2X1 => state.duplicate(2, 1, pc)?,
+            Instruction::Dup2X2 => state.duplicate(2, 2, pc)?,
+            Instruction::Swap => {
+                let top = state.pop(2, pc)?;
+                state.stack.extend(top.into_iter().rev());
+            }
+            Instruction::Getstatic(index) | Instruction::Getfield(index) | Instruction::Putstatic(index)
+            | Instruction::Putfield(index) => {
+                let value = match const_pool_entry(class, *index)? {
+                    ConstPoolType::Field(field) => field_type(&field.name_and_type.descriptor)?,
+                    _ => return Err(DeserializationError::Link)
+                };
+                let slots = if is_wide(&value) { 2 } else { 1 };
+                match instruction {
+                    Instruction::Getstatic(_) => state.push(value),
+                    Instruction::Getfield(_) => state.replace(1, value, pc)?,
+                    Instruction::Putstatic(_) => {
+                        state.pop(slots, pc)?;
+                    }
+                    _ => {
+                        state.pop(slots + 1, pc)?;
+                    }
+                }
+            }
+            Instruction::Invokevirtual(index) | Instruction::Invokespecial(index) | Instruction::Invokestatic(index)
+            | Instruction::Invokeinterface { index, .. } | Instruction::Invokedynamic(index) => {
+                let (name, descriptor) = match const_pool_entry(class, *index)? {
+                    ConstPoolType::MethodRef(method) | ConstPoolType::InterfaceMethodRef(method) =>
+                        (&method.name_and_type.name, &method.name_and_type.descriptor),
+                    ConstPoolType::InvokeDynamic(dynamic) => (&dynamic.name_and_type.name, &dynamic.name_and_type.descriptor),
+                    _ => return Err(DeserializationError::Link)
+                };
+                let descriptor = method_descriptor(descriptor)?;
+                state.pop(descriptor.argument_slot_size() as usize, pc)?;
+                if !matches!(instruction, Instruction::Invokestatic(_) | Instruction::Invokedynamic(_)) {
+                    let receiver = state.pop(1, pc)?.remove(0);
+                    if name.as_str() == "<init>" {
+                        let initialized = match &receiver {
+                            VerificationType::UninitializedThis => class.this_class.clone(),
+                            VerificationType::Uninitialized(offset) => match allocations.get(offset) {
+                                Some(index) => class_operand(class, *index)?,
+                                None => return Err(DeserializationError::Parsing(format!("No new instruction at pc {offset}")))
+                            },
+                            _ => return Err(DeserializationError::Parsing(format!("Constructor call on an initialized object at pc {pc}")))
+                        };
+                        state.initialize(&receiver, VerificationType::Object(initialized));
+                    }
+                }
+                if let Some(return_type) = &descriptor.return_type {
+                    state.push(value_type(return_type));
+                }
+            }
+            Instruction::New(_) => state.push(VerificationType::Uninitialized(pc as u16)),
+            Instruction::Newarray(atype) => state.replace(1, primitive_array(*atype, pc)?, pc)?,
+            Instruction::Anewarray(index) => {
+                let component = class_operand(class, *index)?;
+                let name = if component.0.starts_with('[') { format!("[{}", component.0) } else { format!("[L{};", component.0) };
+                state.replace(1, object_type(&name), pc)?;
+            }
+            Instruction::Checkcast(index) => state.replace(1, VerificationType::Object(class_operand(class, *index)?), pc)?,
+            Instruction::Multianewarray { index, dimensions } =>
+                state.replace(*dimensions as usize, VerificationType::Object(class_operand(class, *index)?), pc)?,
+            Instruction::Jsr(_) | Instruction::JsrW(_) | Instruction::Ret(_) =>
+                return Err(DeserializationError::Parsing(format!("Subroutine at pc {pc} cannot be described by stack map frames"))),
+            _ => return Err(DeserializationError::Parsing(format!("Unexpected {} at pc {pc}", instruction.mnemonic())))
+        }
+        Ok(())
+    }
+
+    // Picks the most compact frame form describing the state relative to the locals of the previous frame.
+    fn encode_frame(offset_delta: u16, previous: &[VerificationType], locals: Vec<VerificationType>,
+        mut stack: Vec<VerificationType>) -> StackMapFrame {
+        match (stack.len(), locals.len() as isize - previous.len() as isize) {
+            (0, 0) if locals == previous && offset_delta < 64 => StackMapFrame::Same { offset_delta },
+            (0, 0) if locals == previous => StackMapFrame::SameExtended { offset_delta },
+            (1, 0) if locals == previous && offset_delta < 64 =>
+                StackMapFrame::SameLocals1StackItem { offset_delta, stack: stack.remove(0) },
+            (1, 0) if locals == previous => StackMapFrame::SameLocals1StackItemExtended { offset_delta, stack: stack.remove(0) },
+            (0, 1..=3) if locals.starts_with(previous) => StackMapFrame::Append { offset_delta, locals: locals[previous.len()..].to_vec() },
+            (0, -3..=-1) if previous.starts_with(&locals) =>
+                StackMapFrame::Chop { offset_delta, count: (previous.len() - locals.len()) as u8 },
+            _ => StackMapFrame::Full { offset_delta, locals, stack }
+        }
+    }
+
+    pub trait CodeAnalysis {
+        fn compute_max_locals(&self, descriptor: &MethodDescriptor, is_static: bool) -> Result<u16, DeserializationError>;
+        fn compute_max_stack(&self, class: &Class) -> Result<u16, DeserializationError>;
+        fn recompute_stack_map(&self, class: &Class, method: &MethodInfo) -> Result<StackMapTableAttribute, DeserializationError>;
+    }
+
+    impl CodeAnalysis for CodeAttribute {
+        fn compute_max_locals(&self, descriptor: &MethodDescriptor, is_static: bool) -> Result<u16, DeserializationError> {
+            let parameters = descriptor.argument_slot_size() as u32 + if is_static { 0 } else { 1 };
+            let locals = instructions(&self.code)
+                .map(|item| item.map(|(_, instruction, _)| local_slots(&instruction).unwrap_or(0)))
+                .try_fold(parameters, |max, slots| slots.map(|slots| max.max(slots)))?;
+            u16::try_from(locals).map_err(|_| DeserializationError::Parsing(format!("{locals} locals exceed the class file limit")))
+        }
+
+        // Follows every path through the code, including exception handlers which start with the thrown object on
+        // the stack. A subroutine return from jsr/ret resumes after the jsr with the stack it had before the call.
+        fn compute_max_stack(&self, class: &Class) -> Result<u16, DeserializationError> {
+            let decoded = decode(&self.code)?;
+            let positions: HashMap<u32, usize> = decoded.iter().enumerate().map(|(position, (pc, _))| (*pc, position)).collect();
+            let mut depths: Vec<Option<i32>> = vec![None; decoded.len()];
+            let mut pending: Vec<(i64, i32)> = vec![(0, 0)];
+            pending.extend(self.exceptions.iter().map(|handler| (handler.handler_pc as i64, 1)));
+            let mut max = 0;
+            while let Some((pc, depth)) = pending.pop() {
+                let position = *u32::try_from(pc).ok()
+                    .and_then(|pc| positions.get(&pc))
+                    .ok_or_else(|| DeserializationError::Parsing(format!("Branch to invalid pc {pc}")))?;
+                if depths[position].is_some() {
+                    continue;
+                }
+                depths[position] = Some(depth);
+                let (pc, instruction) = &decoded[position];
+                let next_depth = depth + stack_effect(instruction, class)?;
+                if next_depth < 0 {
+                    return Err(DeserializationError::Parsing(format!("Operand stack underflow at pc {pc}")));
+                }
+                max = max.max(next_depth);
+                let (targets, falls_through) = successors(instruction, *pc);
+                pending.extend(targets.into_iter().map(|target| (target, next_depth)));
+                if falls_through {
+                    let is_jsr = matches!(instruction, Instruction::Jsr(_) | Instruction::JsrW(_));
+                    let next = decoded.get(position + 1)
+                        .ok_or_else(|| DeserializationError::Parsing(format!("Execution falls off the end of the code at pc {pc}")))?;
+                    pending.push((next.0 as i64, if is_jsr { depth } else { next_depth }));
+                }
+            }
+            u16::try_from(max).map_err(|_| DeserializationError::Parsing(format!("Operand stack depth {max} exceeds the class file limit")))
+        }
+
+        // Interprets the code over verification types, merging every path into a branch target or handler, and writes
+        // a frame at each of them. The method supplies what a Code attribute does not: the descriptor, whether there
+        // is a `this` and whether it starts uninitialized in a constructor. A local holds the type last assigned rather
+        // than the declared one, as the LocalVariableTable is optional, so such a frame differs from javac's while
+        // verifying the same. Other classes are not loaded, so two reference types merge to java/lang/Object. Locals
+        // that are not read again are dropped, as javac's scopes would. Unreachable code gets no frames and
+        // subroutines (jsr/ret) are rejected.
+        fn recompute_stack_map(&self, class: &Class, method: &MethodInfo) -> Result<StackMapTableAttribute, DeserializationError> {
+            let decoded = decode(&self.code)?;
+            let positions: HashMap<u32, usize> = decoded.iter().enumerate().map(|(position, (pc, _))| (*pc, position)).collect();
+            let position_of = |pc: i64| u32::try_from(pc).ok()
+                .and_then(|pc| positions.get(&pc).copied())
+                .ok_or_else(|| DeserializationError::Parsing(format!("Branch to invalid pc {pc}")));
+            let allocations: HashMap<u16, u16> = decoded.iter()
+                .filter_map(|(pc, instruction)| match instruction {
+                    Instruction::New(index) => Some((*pc as u16, *index)),
+                    _ => None
+                })
+                .collect();
+            let mut next = Vec::with_capacity(decoded.len());
+            let mut covering = Vec::with_capacity(decoded.len());
+            for (position, (pc, instruction)) in decoded.iter().enumerate() {
+                let (branches, falls_through) = successors(instruction, *pc);
+                let mut following = branches.into_iter().map(position_of).collect::<Result<Vec<usize>, _>>()?;
+                if falls_through && position + 1 < decoded.len() {
+                    following.push(position + 1);
+                }
+                next.push(following);
+                covering.push(self.exceptions.iter()
+                    .filter(|handler| handler.start_pc as u32 <= *pc && *pc < handler.end_pc as u32)
+                    .map(|handler| position_of(handler.handler_pc as i64))
+                    .collect::<Result<Vec<usize>, _>>()?);
+            }
+            let live = live_locals(&decoded, &next, &covering);
+            let initial = TypeState::initial(class, method)?;
+            let parameters = initial.locals.len();
+            let mut states: Vec<Option<TypeState>> = vec![None; decoded.len()];
+            let mut targets = BTreeSet::new();
+            for handler in &self.exceptions {
+                targets.insert(position_of(handler.handler_pc as i64)?);
+            }
+            let mut pending = vec![];
+            if !decoded.is_empty() {
+                states[0] = Some(initial.clone());
+                pending.push(0);
+            }
+            while let Some(position) = pending.pop() {
+                let (pc, instruction) = &decoded[position];
+                let Some(before) = states[position].clone() else { continue };
+                let mut after = before.clone();
+                interpret(&mut after, *pc, instruction, class, &allocations)?;
+                let mut edges = vec![];
+                for handler in self.exceptions.iter().filter(|handler| handler.start_pc as u32 <= *pc && *pc < handler.end_pc as u32) {
+                    let thrown = handler.catch_type.clone().map_or_else(|| object_type("java/lang/Throwable"), VerificationType::Object);
+                    edges.push((position_of(handler.handler_pc as i64)?, TypeState { locals: before.locals.clone(), stack: vec![thrown] }));
+                }
+                let (branches, falls_through) = successors(instruction, *pc);
+                for branch in branches {
+                    let target = position_of(branch)?;
+                    targets.insert(target);
+                    edges.push((target, after.clone()));
+                }
+                if falls_through {
+                    if position + 1 == decoded.len() {
+                        return Err(DeserializationError::Parsing(format!("Execution falls off the end of the code at pc {pc}")));
+                    }
+                    edges.push((position + 1, after));
+                } else if position + 1 < decoded.len() {
+                    targets.insert(position + 1);
+                }
+                for (target, mut state) in edges {
+                    state.forget(&live[target], parameters);
+                    let changed = match states[target].as_mut() {
+                        Some(current) => current.merge(&state, decoded[target].0)?,
+                        None => {
+                            states[target] = Some(state);
+                            true
+                        }
+                    };
+                    if changed {
+                        pending.push(target);
+                    }
+                }
+            }
+
+            let mut previous = frame_types(&initial.locals);
+            let mut last_pc: Option<u32> = None;
+            let mut frames = vec![];
+            for position in targets {
+                let Some(state) = &states[position] else { continue };
+                let pc = decoded[position].0;
+                let offset_delta = last_pc.map_or(pc, |last_pc| pc - last_pc - 1) as u16;
+                last_pc = Some(pc);
+                let mut locals = frame_types(&state.locals);
+                while locals.last() == Some(&VerificationType::Top) {
+                    locals.pop();
+                }
+                frames.push(encode_frame(offset_delta, &previous, locals.clone(), frame_types(&state.stack)));
+                previous = locals;
+            }
+            Ok(StackMapTableAttribute { frames })
+        }
+    }
+}
+
+pub mod disassembler {
+    use super::bytecode::{decode, Instruction};
+    use super::descriptor::{parse_field_descriptor, parse_method_descriptor, to_java_type};
+    use class::attributes::CodeAttribute;
+    use class::components::{ClassAccess, ComponentInfo, MethodAccessSpecifier};
+    use class::const_pool::{ComponentRef, ConstPoolType};
+    use class::{BitFlag, BitFlags, Class};
+    use std::fmt::{Debug, Write};
+
+    #[inline(always)]
+    fn flag_names<T: Debug>(bits: u16, flags: impl Iterator<Item = T>) -> String {
+        let names = flags.map(|flag| format!("ACC_{flag:?}").to_uppercase()).collect::<Vec<String>>();
+        format!("({bits:#06x}) {}", names.join(", "))
+    }
+
+    #[inline(always)]
+    fn member_name(name: &str) -> String {
+        if name.starts_with('<') { format!("\"{name}\"") } else { name.to_string() }
+    }
+
+    #[inline(always)]
+    fn component_ref(reference: &ComponentRef) -> String {
+        format!("{}.{}:{}", reference.class.0, member_name(&reference.name_and_type.name), reference.name_and_type.descriptor)
+    }
+
+    fn describe_entry(entry: &ConstPoolType) -> String {
+        match entry {
+            ConstPoolType::Utf8(value) => value.to_string(),
+            ConstPoolType::Float(value) => format!("float {value:?}f"),
+            ConstPoolType::Double(value) => format!("double {value:?}d"),
+            ConstPoolType::Int(value) => format!("int {value}"),
+            ConstPoolType::Long(value) => format!("long {value}l"),
+            ConstPoolType::String(value) => format!("String {value}"),
+            ConstPoolType::Class(class) => format!("class {}", class.0),
+            ConstPoolType::NameAndType(name_and_type) =>
+                format!("NameAndType {}:{}", member_name(&name_and_type.name), name_and_type.descriptor),
+            ConstPoolType::Field(field) => format!("Field {}", component_ref(field)),
+            ConstPoolType::MethodRef(method) => format!("Method {}", component_ref(method)),
+            ConstPoolType::InterfaceMethodRef(method) => format!("InterfaceMethod {}", component_ref(method)),
+            ConstPoolType::MethodHandle(handle) => format!("MethodHandle {}:{}", handle.reference_kind, component_ref(&handle.reference)),
+            ConstPoolType::MethodType(method_type) => format!("MethodType {}", method_type.0),
+            ConstPoolType::Dynamic(dynamic) => format!("Dynamic #{}:{}:{}", dynamic.bootstrap_method_attr_index,
+                member_name(&dynamic.name_and_type.name), dynamic.name_and_type.descriptor),
+            ConstPoolType::InvokeDynamic(dynamic) => format!("InvokeDynamic #{}:{}:{}", dynamic.bootstrap_method_attr_index,
+                member_name(&dynamic.name_and_type.name), dynamic.name_and_type.descriptor),
+            ConstPoolType::Module(module) => format!("Module {}", module.0),
+            ConstPoolType::Package(package) => format!("Package {}", package.0),
+            ConstPoolType::Placeholder => "<unusable>".to_string()
+        }
+    }
+
+    #[inline(always)]
+    fn pool_operand(operand: String, class: &Class, index: u16) -> String {
+        let comment = class.const_pool_entry(index).map_or("<invalid index>".to_string(), describe_entry);
+        format!("{operand:<18} // {comment}")
+    }
+
+    #[inline(always)]
+    fn array_type(atype: u8) -> String {
+        match atype {
+            4 => "boolean".to_string(),
+            5 => "char".to_string(),
+            6 => "float".to_string(),
+            7 => "double".to_string(),
+            8 => "byte".to_string(),
+            9 => "short".to_string(),
+            10 => "int".to_string(),
+            11 => "long".to_string(),
+            unexpected => unexpected.to_string()
+        }
+    }
+
+    fn operands(instruction: &Instruction, pc: u32, class: &Class) -> String {
+        let target = |offset: i32| (pc as i64 + offset as i64).to_string();
+        match instruction {
+            Instruction::Bipush(value) => value.to_string(),
+            Instruction::Sipush(value) => value.to_string(),
+            Instruction::Ldc(index) => pool_operand(format!("#{index}"), class, *index as u16),
+            Instruction::LdcW(index) | Instruction::Ldc2W(index) | Instruction::Getstatic(index)
+            | Instruction::Putstatic(index) | Instruction::Getfield(index) | Instruction::Putfield(index)
+            | Instruction::Invokevirtual(index) | Instruction::Invokespecial(index) | Instruction::Invokestatic(index)
+            | Instruction::Invokedynamic(index) | Instruction::New(index) | Instruction::Anewarray(index)
+            | Instruction::Checkcast(index) | Instruction::Instanceof(index) => pool_operand(format!("#{index}"), class, *index),
+            Instruction::Invokeinterface { index, count } => pool_operand(format!("#{index}, {count}"), class, *index),
+            Instruction::Multianewarray { index, dimensions } => pool_operand(format!("#{index}, {dimensions}"), class, *index),
+            Instruction::Iload(index) | Instruction::Lload(index) | Instruction::Fload(index) | Instruction::Dload(index)
+            | Instruction::Aload(index) | Instruction::Istore(index) | Instruction::Lstore(index)
+            | Instruction::Fstore(index) | Instruction::Dstore(index) | Instruction::Astore(index)
+            | Instruction::Ret(index) => index.to_string(),
+            Instruction::Iinc { index, constant } => format!("{index}, {constant}"),
+            Instruction::Ifeq(offset) | Instruction::Ifne(offset) | Instruction::Iflt(offset) | Instruction::Ifge(offset)
+            | Instruction::Ifgt(offset) | Instruction::Ifle(offset) | Instruction::IfIcmpeq(offset)
+            | Instruction::IfIcmpne(offset) | Instruction::IfIcmplt(offset) | Instruction::IfIcmpge(offset)
+            | Instruction::IfIcmpgt(offset) | Instruction::IfIcmple(offset) | Instruction::IfAcmpeq(offset)
+            | Instruction::IfAcmpne(offset) | Instruction::Goto(offset) | Instruction::Jsr(offset)
+            | Instruction::Ifnull(offset) | Instruction::Ifnonnull(offset) => target(*offset as i32),
+            Instruction::GotoW(offset) | Instruction::JsrW(offset) => target(*offset),
+            Instruction::Newarray(atype) => array_type(*atype),
+            Instruction::Tableswitch { default, low, high, offsets } => {
+                let mut operands = format!("{{ // {low} to {high}\n");
+                for (key, offset) in (*low as i64..).zip(offsets) {
+                    let _ = writeln!(operands, "{:>24}: {}", key, target(*offset));
+                }
+                let _ = write!(operands, "{:>24}: {}\n{:>11}", "default", target(*default), "}");
+                operands
+            }
+            Instruction::Lookupswitch { default, pairs } => {
+                let mut operands = format!("{{ // {}\n", pairs.len());
+                for (key, offset) in pairs {
+                    let _ = writeln!(operands, "{:>24}: {}", key, target(*offset));
+                }
+                let _ = write!(operands, "{:>24}: {}\n{:>11}", "default", target(*default), "}");
+                operands
+            }
+            _ => String::new()
+        }
+    }
+
+    fn disassemble_code(out: &mut String, code: &CodeAttribute, class: &Class) {
+        let _ = writeln!(out, "    Code:\n      stack={}, locals={}", code.max_stack, code.max_local);
+        match decode(&code.code) {
+            Ok(instructions) => for (pc, instruction) in instructions {
+                let operands = operands(&instruction, pc, class);
+                let line = format!("{:>10}: {:<13} {operands}", pc, instruction.mnemonic());
+                let _ = writeln!(out, "{}", line.trim_end());
+            },
+            Err(error) => {
+                let _ = writeln!(out, "      // {error}");
+            }
+        }
+    }
+
+    // Source keywords of the flags; flags without one, such as synthetic or bridge, are left out as javap does.
+    #[inline(always)]
+    fn keywords<T: Debug>(flags: impl Iterator<Item = T>) -> Vec<String> {
+        flags.map(|flag| format!("{flag:?}").to_lowercase())
+            .filter_map(|flag| match flag.as_str() {
+                "public" | "private" | "protected" | "static" | "final" | "synchronized" | "volatile" | "transient" | "native"
+                | "abstract" => Some(flag),
+                "strict" => Some("strictfp".to_string()),
+                _ => None
+            })
+            .collect()
+    }
+
+    // Falls back to the plain name when the descriptor does not parse.
+    fn declaration<Access: BitFlag<Numeric = u16> + Debug>(component: &ComponentInfo<Access>, class: &Class, is_method: bool) -> String {
+        let mut words = keywords(component.access.iter());
+        if !is_method {
+            match parse_field_descriptor(&component.descriptor) {
+                Ok(field_type) => words.extend([to_java_type(&field_type), component.name.to_string()]),
+                Err(_) => words.push(component.name.to_string())
+            }
+            return format!("{};", words.join(" "));
+        }
+        if component.name.as_str() == "<clinit>" {
+            return "static {};".to_string();
+        }
+        let descriptor = match parse_method_descriptor(&component.descriptor) {
+            Ok(descriptor) => descriptor,
+            Err(_) => {
+                words.push(component.name.to_string());
+                return format!("{};", words.join(" "));
+            }
+        };
+        let mut parameters = descriptor.parameters.iter().map(to_java_type).collect::<Vec<String>>();
+        let varargs = BitFlags::<MethodAccessSpecifier>::from_bits_truncate(component.access.bits()).contains(MethodAccessSpecifier::Varargs);
+        if let Some(last) = parameters.last_mut().filter(|last| varargs && last.ends_with("[]")) {
+            last.truncate(last.len() - 2);
+            last.push_str("...");
+        }
+        if component.name.as_str() == "<init>" {
+            words.push(class.this_class.0.replace('/', "."));
+        } else {
+            words.push(descriptor.return_type.as_ref().map_or("void".to_string(), to_java_type));
+            words.push(component.name.to_string());
+        }
+        format!("{}({});", words.join(" "), parameters.join(", "))
+    }
+
+    fn disassemble_component<Access: BitFlag<Numeric = u16> + Debug>(out: &mut String, component: &ComponentInfo<Access>, class: &Class,
+                                                                   is_method: bool, verbose: bool) {
+        if verbose {
+            let _ = writeln!(out, "  {}", declaration(component, class, is_method));
+        } else {
+            let _ = writeln!(out, "  {}", component.name);
+        }
+        let _ = writeln!(out, "    descriptor: {}", component.descriptor);
+        let _ = writeln!(out, "    flags: {}", flag_names(component.access.bits(), component.access.iter()));
+        if let Some(code) = component.code() {
+            disassemble_code(out, code, class);
+        }
+    }
+
+    // Renders a javap-like listing; names are shown in their internal form and bytecode that cannot be decoded is noted inline.
+    pub fn disassemble(class: &Class) -> String {
+        render(class, false)
+    }
+
+    // Like `javap -v`: adds the constant pool and declares every member with Java types and keywords.
+    pub fn disassemble_verbose(class: &Class) -> String {
+        render(class, true)
+    }
+
+    fn render(class: &Class, verbose: bool) -> String {
+        let name = |internal: &str| if verbose { internal.replace('/', ".") } else { internal.to_string() };
+        let mut out = String::new();
+        let mut header = vec![];
+        if class.access.contains(ClassAccess::Public) {
+            header.push("public");
+        }
+        if class.access.contains(ClassAccess::Final) {
+            header.push("final");
+        }
+        if class.access.contains(ClassAccess::Abstract) && !class.access.contains(ClassAccess::Interface) {
+            header.push("abstract");
+        }
+        header.push(if class.access.contains(ClassAccess::Module) {
+            "module"
+        } else if class.access.contains(ClassAccess::Annotation) {
+            "@interface"
+        } else if class.access.contains(ClassAccess::Interface) {
+            "interface"
+        } else {
+            "class"
+        });
+        let _ = write!(out, "{} {}", header.join(" "), name(&class.this_class.0));
+        if let Some(super_class) = class.super_class.as_ref().filter(|super_class| super_class.0.as_str() != "java/lang/Object") {
+            let _ = write!(out, " extends {}", name(&super_class.0));
+        }
+        if !class.interfaces.is_empty() {
+            let interfaces = class.interfaces.iter().map(|interface| name(&interface.0)).collect::<Vec<String>>();
+            let _ = write!(out, " implements {}", interfaces.join(", "));
+        }
+        let _ = writeln!(out, "\n  minor version: {}\n  major version: {}", class.version.minor, class.version.major);
+        let _ = writeln!(out, "  flags: {}", flag_names(class.access.bits(), class.access.iter()));
+        let _ = writeln!(out, "  this_class: {}", class.this_class.0);
+        if let Some(super_class) = &class.super_class {
+            let _ = writeln!(out, "  super_class: {}", super_class.0);
+        }
+        let _ = writeln!(out, "  interfaces: {}, fields: {}, methods: {}, attributes: {}",
+            class.interfaces.len(), class.fields.len(), class.methods.len(), class.attributes.len());
+        if verbose {
+            out.push_str("Constant pool:\n");
+            for (index, entry) in class.const_pool.iter().enumerate().filter(|(_, entry)| **entry != ConstPoolType::Placeholder) {
+                let _ = writeln!(out, "{:>6} = {entry}", format!("#{}", index + 1));
+            }
+        }
+        out.push_str("{\n");
+        for (position, field) in class.fields.iter().enumerate() {
+            if position > 0 {
+                out.push('\n');
+            }
+            disassemble_component(&mut out, field, class, false, verbose);
+        }
+        for (position, method) in class.methods.iter().enumerate() {
+            if position > 0 || !class.fields.is_empty() {
+                out.push('\n');
+            }
+            disassemble_component(&mut out, method, class, true, verbose);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+// The javap-style printer, also reachable under the name of its role.
+pub use disassembler as fmt;
+
+pub mod serialization {
+    use super::*;
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+    use std::io::Write;
+    use byteorder::{BigEndian, WriteBytesExt};
+    use class::const_pool::{ConstPoolType, ClassInfo, ComponentRef, ModuleInfo, PackageInfo, Utf8Info};
+    use class::attributes::*;
+    use class::components::*;
+    use class::{BitFlag, BitFlags};
+    use class::Class;
+
+    pub trait Serializable {
+        fn serialize(&self, out: impl WriteBytesExt) -> Result<(), SerializationError>;
+    }
+
+    pub trait SerializableLinked {
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError>;
+    }
+
+    type ComponentKey = (Utf8Info, Utf8Info, Utf8Info);
+
+    #[derive(PartialEq, Eq, Hash)]
+    enum ConstPoolKey {
+        Utf8(Utf8Info),
+        Float(u32),
+        Double(u64),
+        Int(i32),
+        Long(i64),
+        String(Utf8Info),
+        Class(Utf8Info),
+        NameAndType(Utf8Info, Utf8Info),
+        Field(ComponentKey),
+        MethodRef(ComponentKey),
+        InterfaceMethodRef(ComponentKey),
+        MethodHandle(u8, ComponentKey),
+        MethodType(Utf8Info),
+        Dynamic(u16, Utf8Info, Utf8Info),
+        InvokeDynamic(u16, Utf8Info, Utf8Info),
+        Module(Utf8Info),
+        Package(Utf8Info),
+    }
+
+    #[inline(always)]
+    fn component_key(component: &ComponentRef) -> ComponentKey {
+        (component.class.0.clone(), component.name_and_type.name.clone(), component.name_and_type.descriptor.clone())
+    }
+
+    fn const_pool_key(entry: &ConstPoolType) -> Option<ConstPoolKey> {
+        Some(match entry {
+            ConstPoolType::Utf8(value) => ConstPoolKey::Utf8(value.clone()),
+            ConstPoolType::Float(value) => ConstPoolKey::Float(value.to_bits()),
+            ConstPoolType::Double(value) => ConstPoolKey::Double(value.to_bits()),
+            ConstPoolType::Int(value) => ConstPoolKey::Int(*value),
+            ConstPoolType::Long(value) => ConstPoolKey::Long(*value),
+            ConstPoolType::String(value) => ConstPoolKey::String(value.clone()),
+            ConstPoolType::Class(class) => ConstPoolKey::Class(class.0.clone()),
+            ConstPoolType::NameAndType(name_and_type) => ConstPoolKey::NameAndType(name_and_type.name.clone(), name_and_type.descriptor.clone()),
+            ConstPoolType::Field(component) => ConstPoolKey::Field(component_key(component)),
+            ConstPoolType::MethodRef(component) => ConstPoolKey::MethodRef(component_key(component)),
+            ConstPoolType::InterfaceMethodRef(component) => ConstPoolKey::InterfaceMethodRef(component_key(component)),
+            ConstPoolType::MethodHandle(handle) => ConstPoolKey::MethodHandle(handle.reference_kind, component_key(&handle.reference)),
+            ConstPoolType::MethodType(method_type) => ConstPoolKey::MethodType(method_type.0.clone()),
+            ConstPoolType::Dynamic(dynamic) => ConstPoolKey::Dynamic(dynamic.bootstrap_method_attr_index,
+                                                                     dynamic.name_and_type.name.clone(), dynamic.name_and_type.descriptor.clone()),
+            ConstPoolType::InvokeDynamic(dynamic) => ConstPoolKey::InvokeDynamic(dynamic.bootstrap_method_attr_index,
+                                                                                 dynamic.name_and_type.name.clone(), dynamic.name_and_type.descriptor.clone()),
+            ConstPoolType::Module(module) => ConstPoolKey::Module(module.0.clone()),
+            ConstPoolType::Package(package) => ConstPoolKey::Package(package.0.clone()),
+            ConstPoolType::Placeholder => return None
+        })
+    }
+
+    // Keeps the entries of the parsed pool at their original indices and appends anything else a class refers to.
+    pub struct ConstPoolWriter {
+        entries: Vec<ConstPoolType>,
+        indices: HashMap<ConstPoolKey, u16>
+    }
+
+    impl ConstPoolWriter {
+        pub fn new(pool: &[ConstPoolType]) -> ConstPoolWriter {
+            let mut indices = HashMap::new();
+            for (position, entry) in pool.iter().enumerate() {
+                if let Some(key) = const_pool_key(entry) {
+                    indices.entry(key).or_insert(position as u16 + 1);
+                }
+            }
+            ConstPoolWriter {
+                entries: pool.to_vec(),
+                indices
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn index(&mut self, entry: ConstPoolType) -> Result<u16, SerializationError> {
+            let key = const_pool_key(&entry)
+                .ok_or_else(|| SerializationError::Unsupported("Reference to a constant pool placeholder".into()))?;
+            if let Some(index) = self.indices.get(&key) {
+                return Ok(*index);
+            }
+            let wide = matches!(entry, ConstPoolType::Long(_) | ConstPoolType::Double(_));
+            let index = self.entries.len() + 1;
+            if index + wide as usize >= u16::MAX as usize {
+                return Err(SerializationError::ConstPoolOverflow);
+            }
+            self.entries.push(entry);
+            if wide {
+                self.entries.push(ConstPoolType::Placeholder);
+            }
+            self.indices.insert(key, index as u16);
+            Ok(index as u16)
+        }
+
+        // A string taken from the parsed model finds the entry it was read from, even one spelled non-canonically.
+        pub fn utf8(&mut self, value: impl Into<Utf8Info>) -> Result<u16, SerializationError> {
+            self.index(ConstPoolType::Utf8(value.into()))
+        }
+
+        pub fn class(&mut self, class: &ClassInfo) -> Result<u16, SerializationError> {
+            self.index(ConstPoolType::Class(class.clone()))
+        }
+
+        pub fn into_entries(self) -> Vec<ConstPoolType> {
+            self.entries
+        }
+
+        fn contains(&self, entry: &ConstPoolType) -> bool {
+            const_pool_key(entry).is_some_and(|key| self.indices.contains_key(&key))
+        }
+
+        // Entries may append further entries while being written, so this runs until the pool stops growing.
+        fn serialize_entries(&mut self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            let mut position = 0;
+            while position < self.entries.len() {
+                let entry = self.entries[position].clone();
+                entry.serialize_link(&mut out, self)?;
+                position += 1;
+            }
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    fn write_count(mut out: impl WriteBytesExt, count: usize) -> Result<(), SerializationError> {
+        let count = u16::try_from(count).map_err(|_| SerializationError::Unsupported(format!("Too many entries: {count}")))?;
+        out.write_u16::<BigEndian>(count)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_index(mut out: impl WriteBytesExt, index: Result<u16, SerializationError>) -> Result<(), SerializationError> {
+        out.write_u16::<BigEndian>(index?)?;
+        Ok(())
+    }
+
+    impl Serializable for Utf8Info {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ConstantValueAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let value = ConstValueType::deserialize_link(&mut cursor, pool)?;
-            Ok(ConstantValueAttribute {
-                value
-            })
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            let data = match self.raw() {
+                Some(raw) => Cow::Borrowed(raw),
+                None => Cow::Owned(mutf8::encode_modified_utf8(self))
+            };
+            write_count(&mut out, data.len())?;
+            out.write_all(&data)?;
+            Ok(())
         }
     }
 
-    impl DeserializableLinked for ExceptionEntry {
+    impl SerializableLinked for ComponentRef {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ExceptionEntry, DeserializationError> {
-            let start_pc = cursor.read_u16::<BigEndian>()?;
-            let end_pc = cursor.read_u16::<BigEndian>()?;
-            let handler_pc = cursor.read_u16::<BigEndian>()?;
-            let catch_type: Option<ClassInfo> = match find_const_pool_element(cursor, pool)? {
-                Some(value) => Some(match value {
-                    ConstPoolType::Class(info) => Ok(info.clone()),
-                    _ => Err(DeserializationError::Link)
-                }?),
-                None => None
-            };
-            Ok(ExceptionEntry {
-                start_pc,
-                end_pc,
-                handler_pc,
-                catch_type,
-            })
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(&mut out, pool.class(&self.class))?;
+            write_index(&mut out, pool.index(ConstPoolType::NameAndType(self.name_and_type.clone())))
         }
     }
 
-    impl DeserializableLinked for SourceFileAttribute {
+    impl SerializableLinked for ConstPoolType {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<SourceFileAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let file = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            Ok(SourceFileAttribute {
-                file,
-            })
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                ConstPoolType::Utf8(value) => {
+                    out.write_u8(1)?;
+                    value.serialize(&mut out)
+                }
+                ConstPoolType::Int(value) => {
+                    out.write_u8(3)?;
+                    Ok(out.write_i32::<BigEndian>(*value)?)
+                }
+                ConstPoolType::Float(value) => {
+                    out.write_u8(4)?;
+                    Ok(out.write_f32::<BigEndian>(*value)?)
+                }
+                ConstPoolType::Long(value) => {
+                    out.write_u8(5)?;
+                    Ok(out.write_i64::<BigEndian>(*value)?)
+                }
+                ConstPoolType::Double(value) => {
+                    out.write_u8(6)?;
+                    Ok(out.write_f64::<BigEndian>(*value)?)
+                }
+                ConstPoolType::Class(class) => {
+                    out.write_u8(7)?;
+                    write_index(&mut out, pool.utf8(&class.0))
+                }
+                ConstPoolType::String(value) => {
+                    out.write_u8(8)?;
+                    write_index(&mut out, pool.utf8(value))
+                }
+                ConstPoolType::Field(component) => {
+                    out.write_u8(9)?;
+                    component.serialize_link(&mut out, pool)
+                }
+                ConstPoolType::MethodRef(component) => {
+                    out.write_u8(10)?;
+                    component.serialize_link(&mut out, pool)
+                }
+                ConstPoolType::InterfaceMethodRef(component) => {
+                    out.write_u8(11)?;
+                    component.serialize_link(&mut out, pool)
+                }
+                ConstPoolType::NameAndType(name_and_type) => {
+                    out.write_u8(12)?;
+                    write_index(&mut out, pool.utf8(&name_and_type.name))?;
+                    write_index(&mut out, pool.utf8(&name_and_type.descriptor))
+                }
+                ConstPoolType::MethodHandle(handle) => {
+                    out.write_u8(15)?;
+                    out.write_u8(handle.reference_kind)?;
+                    let reference = handle.reference.clone();
+                    let interface = ConstPoolType::InterfaceMethodRef(reference.clone());
+                    let entry = match handle.reference_kind {
+                        1..=4 => ConstPoolType::Field(reference),
+                        6 | 7 if pool.contains(&interface) => interface,
+                        9 => interface,
+                        _ => ConstPoolType::MethodRef(reference)
+                    };
+                    write_index(&mut out, pool.index(entry))
+                }
+                ConstPoolType::MethodType(method_type) => {
+                    out.write_u8(16)?;
+                    write_index(&mut out, pool.utf8(&method_type.0))
+                }
+                ConstPoolType::Dynamic(dynamic) | ConstPoolType::InvokeDynamic(dynamic) => {
+                    out.write_u8(if matches!(self, ConstPoolType::Dynamic(_)) { 17 } else { 18 })?;
+                    out.write_u16::<BigEndian>(dynamic.bootstrap_method_attr_index)?;
+                    write_index(&mut out, pool.index(ConstPoolType::NameAndType(dynamic.name_and_type.clone())))
+                }
+                ConstPoolType::Module(module) => {
+                    out.write_u8(19)?;
+                    write_index(&mut out, pool.utf8(&module.0))
+                }
+                ConstPoolType::Package(package) => {
+                    out.write_u8(20)?;
+                    write_index(&mut out, pool.utf8(&package.0))
+                }
+                ConstPoolType::Placeholder => Ok(())
+            }
         }
     }
 
-    impl Deserializable for UnknownAttribute {
+    impl SerializableLinked for ClassInfo {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<UnknownAttribute, DeserializationError> {
-            let size = cursor.read_u32::<BigEndian>()?;
-            let mut buffer = vec![0u8; size as usize];
-            let _ = cursor.read_exact(&mut buffer)?;
-            Ok(UnknownAttribute {
-                size
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.class(self))
         }
     }
 
-    impl Deserializable for SyntheticAttribute {
+    impl SerializableLinked for Option<ClassInfo> {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<SyntheticAttribute, DeserializationError> {
-            let size = cursor.read_u32::<BigEndian>()?;
-            if size != 0 {
-                return Err(DeserializationError::Parsing("Synthetic attribute must be zero-sized".into()));
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                Some(class) => class.serialize_link(out, pool),
+                None => Ok(out.write_u16::<BigEndian>(0)?)
             }
-            Ok(SyntheticAttribute {})
         }
     }
 
-    impl DeserializableLinked for LocalVariableEntry {
+    impl<T: SerializableLinked> SerializableLinked for Vec<T> {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableEntry, DeserializationError> {
-            Ok(LocalVariableEntry {
-                start_pc: cursor.read_u16::<BigEndian>()?,
-                length: cursor.read_u16::<BigEndian>()?,
-                name: Utf8Info::deserialize_link(&mut cursor, pool)?,
-                descriptor: Utf8Info::deserialize_link(&mut cursor, pool)?,
-                index: cursor.read_u16::<BigEndian>()?,
-            })
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_count(&mut out, self.len())?;
+            self.iter().try_for_each(|item| item.serialize_link(&mut out, pool))
         }
     }
 
-    impl DeserializableLinked for LocalVariableTableAttribute {
+    impl Serializable for BitFlags<ModuleAccess> {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<LocalVariableTableAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let length = cursor.read_u16::<BigEndian>()?;
-            let variables = (0..length)
-                .map(|_| LocalVariableEntry::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<LocalVariableEntry>, DeserializationError>>()?;
-            Ok(LocalVariableTableAttribute {
-                variables
-            })
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<RequiresAccess> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<ExportsAccess> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<ClassAccess> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<AccessSpecifier> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<MethodAccessSpecifier> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    impl Serializable for BitFlags<ClassAccessSpecifier> {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            Ok(out.write_u16::<BigEndian>(self.bits())?)
+        }
+    }
+
+    #[inline(always)]
+    fn const_value_entry(value: &ConstValueType) -> ConstPoolType {
+        match value {
+            ConstValueType::Float(value) => ConstPoolType::Float(*value),
+            ConstValueType::Double(value) => ConstPoolType::Double(*value),
+            ConstValueType::Int(value) => ConstPoolType::Int(*value),
+            ConstValueType::Long(value) => ConstPoolType::Long(*value),
+            ConstValueType::Short(value) => ConstPoolType::Int(*value as i32),
+            ConstValueType::Byte(value) => ConstPoolType::Int(*value as i32),
+            ConstValueType::Boolean(value) => ConstPoolType::Int(*value as i32),
+            ConstValueType::Char(value) => ConstPoolType::Int(*value as i32),
+            ConstValueType::String(value) => ConstPoolType::String(value.clone())
+        }
+    }
+
+    impl SerializableLinked for ConstantValueAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.index(const_value_entry(&self.value)))
+        }
+    }
+
+    impl SerializableLinked for ExceptionEntry {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            out.write_u16::<BigEndian>(self.start_pc)?;
+            out.write_u16::<BigEndian>(self.end_pc)?;
+            out.write_u16::<BigEndian>(self.handler_pc)?;
+            self.catch_type.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for CodeAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            out.write_u16::<BigEndian>(self.max_stack)?;
+            out.write_u16::<BigEndian>(self.max_local)?;
+            out.write_u32::<BigEndian>(self.code.len() as u32)?;
+            out.write_all(&self.code)?;
+            self.exceptions.serialize_link(&mut out, pool)?;
+            self.attributes.serialize_link(&mut out, pool)
+        }
+    }
+
+    impl SerializableLinked for ExceptionsAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.exceptions_classes.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ClassEntry {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.inner_class_info.serialize_link(&mut out, pool)?;
+            self.outer_class_info.serialize_link(&mut out, pool)?;
+            write_index(&mut out, pool.utf8(&self.name))?;
+            self.access.serialize(out)
+        }
+    }
+
+    impl SerializableLinked for InnerClassesAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.classes.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for SourceFileAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.utf8(&self.file))
+        }
+    }
+
+    impl Serializable for LineNumberTableAttribute {
+        #[inline(always)]
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            write_count(&mut out, self.lines.len())?;
+            for line in &self.lines {
+                out.write_u16::<BigEndian>(line.start_pc)?;
+                out.write_u16::<BigEndian>(line.line)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl SerializableLinked for LocalVariableEntry {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            out.write_u16::<BigEndian>(self.start_pc)?;
+            out.write_u16::<BigEndian>(self.length)?;
+            write_index(&mut out, pool.utf8(&self.name))?;
+            write_index(&mut out, pool.utf8(&self.descriptor))?;
+            Ok(out.write_u16::<BigEndian>(self.index)?)
+        }
+    }
+
+    impl SerializableLinked for LocalVariableTypeEntry {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            out.write_u16::<BigEndian>(self.start_pc)?;
+            out.write_u16::<BigEndian>(self.length)?;
+            write_index(&mut out, pool.utf8(&self.name))?;
+            write_index(&mut out, pool.utf8(&self.signature))?;
+            Ok(out.write_u16::<BigEndian>(self.index)?)
+        }
+    }
+
+    impl SerializableLinked for VerificationType {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                VerificationType::Top => Ok(out.write_u8(0)?),
+                VerificationType::Integer => Ok(out.write_u8(1)?),
+                VerificationType::Float => Ok(out.write_u8(2)?),
+                VerificationType::Double => Ok(out.write_u8(3)?),
+                VerificationType::Long => Ok(out.write_u8(4)?),
+                VerificationType::Null => Ok(out.write_u8(5)?),
+                VerificationType::UninitializedThis => Ok(out.write_u8(6)?),
+                VerificationType::Object(class) => {
+                    out.write_u8(7)?;
+                    class.serialize_link(out, pool)
+                }
+                VerificationType::Uninitialized(offset) => {
+                    out.write_u8(8)?;
+                    Ok(out.write_u16::<BigEndian>(*offset)?)
+                }
+            }
+        }
+    }
+
+    impl SerializableLinked for StackMapFrame {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                StackMapFrame::Same { offset_delta } if *offset_delta < 64 => Ok(out.write_u8(*offset_delta as u8)?),
+                StackMapFrame::Same { offset_delta } | StackMapFrame::SameExtended { offset_delta } => {
+                    out.write_u8(251)?;
+                    Ok(out.write_u16::<BigEndian>(*offset_delta)?)
+                }
+                StackMapFrame::SameLocals1StackItem { offset_delta, stack } if *offset_delta < 64 => {
+                    out.write_u8(64 + *offset_delta as u8)?;
+                    stack.serialize_link(out, pool)
+                }
+                StackMapFrame::SameLocals1StackItem { offset_delta, stack }
+                | StackMapFrame::SameLocals1StackItemExtended { offset_delta, stack } => {
+                    out.write_u8(247)?;
+                    out.write_u16::<BigEndian>(*offset_delta)?;
+                    stack.serialize_link(out, pool)
+                }
+                StackMapFrame::Chop { offset_delta, count: count @ 1..=3 } => {
+                    out.write_u8(251 - count)?;
+                    Ok(out.write_u16::<BigEndian>(*offset_delta)?)
+                }
+                StackMapFrame::Append { offset_delta, locals } if (1..=3).contains(&locals.len()) => {
+                    out.write_u8(251 + locals.len() as u8)?;
+                    out.write_u16::<BigEndian>(*offset_delta)?;
+                    locals.iter().try_for_each(|local| local.serialize_link(&mut out, pool))
+                }
+                StackMapFrame::Full { offset_delta, locals, stack } => {
+                    out.write_u8(255)?;
+                    out.write_u16::<BigEndian>(*offset_delta)?;
+                    locals.serialize_link(&mut out, pool)?;
+                    stack.serialize_link(out, pool)
+                }
+                StackMapFrame::Chop { count, .. } => Err(SerializationError::Unsupported(format!("Chop frame removing {count} locals"))),
+                StackMapFrame::Append { locals, .. } =>
+                    Err(SerializationError::Unsupported(format!("Append frame adding {} locals", locals.len())))
+            }
+        }
+    }
+
+    impl SerializableLinked for StackMapTableAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.frames.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for LocalVariableTableAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.variables.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for LocalVariableTypeTableAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.variables.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for PermittedSubclassesAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.classes.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModuleInfo {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.index(ConstPoolType::Module(self.clone())))
+        }
+    }
+
+    impl SerializableLinked for PackageInfo {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.index(ConstPoolType::Package(self.clone())))
+        }
+    }
+
+    #[inline(always)]
+    fn serialize_version(version: &Option<Utf8Info>, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+        match version {
+            Some(version) => write_index(out, pool.utf8(version)),
+            None => Ok(out.write_u16::<BigEndian>(0)?)
+        }
+    }
+
+    impl SerializableLinked for ModuleRequires {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.module.serialize_link(&mut out, pool)?;
+            self.access.serialize(&mut out)?;
+            serialize_version(&self.version, out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModuleExports {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.package.serialize_link(&mut out, pool)?;
+            self.access.serialize(&mut out)?;
+            self.to.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModuleProvides {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.service.serialize_link(&mut out, pool)?;
+            self.implementations.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModuleAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.name.serialize_link(&mut out, pool)?;
+            self.access.serialize(&mut out)?;
+            serialize_version(&self.version, &mut out, pool)?;
+            self.requires.serialize_link(&mut out, pool)?;
+            self.exports.serialize_link(&mut out, pool)?;
+            self.opens.serialize_link(&mut out, pool)?;
+            self.uses.serialize_link(&mut out, pool)?;
+            self.provides.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModulePackagesAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.packages.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for ModuleMainClassAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.main_class.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for NestHostAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.host_class.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for NestMembersAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.classes.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for RecordComponentInfo {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(&mut out, pool.utf8(&self.name))?;
+            write_index(&mut out, pool.utf8(&self.descriptor))?;
+            self.attributes.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for RecordAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.components.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for BootstrapMethod {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(&mut out, pool.index(ConstPoolType::MethodHandle(self.method.clone())))?;
+            write_count(&mut out, self.arguments.len())?;
+            for argument in &self.arguments {
+                write_index(&mut out, pool.index(argument.clone()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl SerializableLinked for BootstrapMethodsAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.methods.serialize_link(out, pool)
+        }
+    }
+
+    impl SerializableLinked for SignatureAttribute {
+        #[inline(always)]
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            write_index(out, pool.utf8(&self.signature))
+        }
+    }
+
+    #[inline(always)]
+    fn element_tag(value: &ConstValueType) -> u8 {
+        match value {
+            ConstValueType::Byte(_) => b'B',
+            ConstValueType::Char(_) => b'C',
+            ConstValueType::Double(_) => b'D',
+            ConstValueType::Float(_) => b'F',
+            ConstValueType::Int(_) => b'I',
+            ConstValueType::Long(_) => b'J',
+            ConstValueType::Short(_) => b'S',
+            ConstValueType::Boolean(_) => b'Z',
+            ConstValueType::String(_) => b's'
+        }
+    }
+
+    fn serialize_element_value(value: &ElementValue, out: &mut dyn Write, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+        match value {
+            ElementValue::Const(ConstValueType::String(value)) => {
+                out.write_u8(b's')?;
+                write_index(out, pool.utf8(value))
+            }
+            ElementValue::Const(constant) => {
+                out.write_u8(element_tag(constant))?;
+                write_index(out, pool.index(const_value_entry(constant)))
+            }
+            ElementValue::Enum { type_name, const_name } => {
+                out.write_u8(b'e')?;
+                write_index(&mut *out, pool.utf8(type_name))?;
+                write_index(out, pool.utf8(const_name))
+            }
+            ElementValue::Class(class) => {
+                out.write_u8(b'c')?;
+                write_index(out, pool.utf8(class))
+            }
+            ElementValue::Annotation(annotation) => {
+                out.write_u8(b'@')?;
+                serialize_annotation(annotation, out, pool)
+            }
+            ElementValue::Array(values) => {
+                out.write_u8(b'[')?;
+                write_count(&mut *out, values.len())?;
+                values.iter().try_for_each(|value| serialize_element_value(value, &mut *out, pool))
+            }
+        }
+    }
+
+    fn serialize_annotation(annotation: &Annotation, out: &mut dyn Write, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+        write_index(&mut *out, pool.utf8(&annotation.type_descriptor))?;
+        write_count(&mut *out, annotation.elements.len())?;
+        for (name, value) in &annotation.elements {
+            write_index(&mut *out, pool.utf8(name))?;
+            serialize_element_value(value, &mut *out, pool)?;
+        }
+        Ok(())
+    }
+
+    impl SerializableLinked for Annotation {
+        #[inline(always)]
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            serialize_annotation(self, &mut out, pool)
         }
     }
 
-    impl Deserializable for LineNumberEntry {
+    impl SerializableLinked for AnnotationDefaultAttribute {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<LineNumberEntry, DeserializationError> {
-            Ok(LineNumberEntry {
-                start_pc: cursor.read_u16::<BigEndian>()?,
-                line: cursor.read_u16::<BigEndian>()?,
-            })
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            serialize_element_value(&self.value, &mut out, pool)
         }
     }
 
-    impl Deserializable for LineNumberTableAttribute {
+    impl SerializableLinked for RuntimeVisibleAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<LineNumberTableAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let length = cursor.read_u16::<BigEndian>()?;
-            let lines = (0..length)
-                .map(|_| LineNumberEntry::deserialize(&mut cursor))
-                .collect::<Result<Vec<LineNumberEntry>, DeserializationError>>()?;
-            Ok(LineNumberTableAttribute {
-                lines
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.annotations.serialize_link(out, pool)
         }
     }
 
-    impl DeserializableLinked for ExceptionsAttribute {
+    impl SerializableLinked for RuntimeInvisibleAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ExceptionsAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let length = cursor.read_u16::<BigEndian>()?;
-            let exceptions_classes = (0..length)
-                .map(|_| ClassInfo::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<ClassInfo>, DeserializationError>>()?;
-            Ok(ExceptionsAttribute {
-                exceptions_classes
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.annotations.serialize_link(out, pool)
         }
     }
 
-    impl Deserializable for BitFlags<AccessSpecifier> {
+    fn serialize_parameter_annotations(parameters: &[Vec<Annotation>], mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+        let count = u8::try_from(parameters.len())
+            .map_err(|_| SerializationError::Unsupported(format!("Too many annotated parameters: {}", parameters.len())))?;
+        out.write_u8(count)?;
+        parameters.iter().try_for_each(|annotations| annotations.serialize_link(&mut out, pool))
+    }
+
+    impl SerializableLinked for RuntimeVisibleParameterAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<BitFlags<AccessSpecifier>, DeserializationError> {
-            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
-                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            serialize_parameter_annotations(&self.parameters, out, pool)
         }
     }
 
-    impl Deserializable for BitFlags<ClassAccessSpecifier> {
+    impl SerializableLinked for RuntimeInvisibleParameterAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<BitFlags<ClassAccessSpecifier>, DeserializationError> {
-            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
-                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            serialize_parameter_annotations(&self.parameters, out, pool)
         }
     }
 
-    impl Deserializable for BitFlags<ClassAccess> {
+    fn serialize_target_info(target_info: &TargetInfo, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+        match target_info {
+            TargetInfo::TypeParameter { index } | TargetInfo::FormalParameter { index } => out.write_u8(*index)?,
+            TargetInfo::Supertype { index } | TargetInfo::Throws { index } => out.write_u16::<BigEndian>(*index)?,
+            TargetInfo::TypeParameterBound { type_parameter_index, bound_index } => {
+                out.write_u8(*type_parameter_index)?;
+                out.write_u8(*bound_index)?;
+            }
+            TargetInfo::Empty => {}
+            TargetInfo::LocalVariable(targets) => {
+                write_count(&mut out, targets.len())?;
+                for target in targets {
+                    out.write_u16::<BigEndian>(target.start_pc)?;
+                    out.write_u16::<BigEndian>(target.length)?;
+                    out.write_u16::<BigEndian>(target.index)?;
+                }
+            }
+            TargetInfo::Catch { exception_table_index } => out.write_u16::<BigEndian>(*exception_table_index)?,
+            TargetInfo::Offset(offset) => out.write_u16::<BigEndian>(*offset)?,
+            TargetInfo::TypeArgument { offset, type_argument_index } => {
+                out.write_u16::<BigEndian>(*offset)?;
+                out.write_u8(*type_argument_index)?;
+            }
+        }
+        Ok(())
+    }
+
+    impl SerializableLinked for TypeAnnotation {
         #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<BitFlags<ClassAccess>, DeserializationError> {
-            BitFlags::from_bits(cursor.read_u16::<BigEndian>()?)
-                .map_err(|_| DeserializationError::Parsing("Unable to parse bit flag.".into()))
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            out.write_u8(self.target_type)?;
+            serialize_target_info(&self.target_info, &mut out)?;
+            let length = u8::try_from(self.target_path.len())
+                .map_err(|_| SerializationError::Unsupported(format!("Type path too long: {}", self.target_path.len())))?;
+            out.write_u8(length)?;
+            for entry in &self.target_path {
+                out.write_u8(entry.kind)?;
+                out.write_u8(entry.type_argument_index)?;
+            }
+            serialize_annotation(&self.annotation, &mut out, pool)
         }
     }
 
-    impl DeserializableLinked for ClassEntry {
+    impl SerializableLinked for RuntimeVisibleTypeAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ClassEntry, DeserializationError> {
-            let inner_class_info: Option<ClassInfo> = ClassInfo::deserialize_link(&mut cursor, pool).ok();
-            let outer_class_info: Option<ClassInfo> = ClassInfo::deserialize_link(&mut cursor, pool).ok();
-            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            let access: BitFlags<ClassAccessSpecifier> = BitFlags::deserialize(&mut cursor)?;
-            Ok(ClassEntry {
-                inner_class_info,
-                outer_class_info,
-                name,
-                access,
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.annotations.serialize_link(out, pool)
         }
     }
 
-    impl DeserializableLinked for InnerClassesAttribute {
+    impl SerializableLinked for RuntimeInvisibleTypeAnnotationsAttribute {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<InnerClassesAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let length = cursor.read_u16::<BigEndian>()?;
-            let classes = (0..length)
-                .map(|_| ClassEntry::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<ClassEntry>, DeserializationError>>()?;
-            Ok(InnerClassesAttribute {
-                classes
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.annotations.serialize_link(out, pool)
         }
     }
 
-    impl DeserializableLinked for CodeAttribute {
+    #[inline(always)]
+    fn serialize_attribute_body(name: &str, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter,
+                                serialize: impl FnOnce(&mut Vec<u8>, &mut ConstPoolWriter) -> Result<(), SerializationError>) -> Result<(), SerializationError> {
+        write_index(&mut out, pool.utf8(name))?;
+        let mut body = Vec::new();
+        serialize(&mut body, pool)?;
+        let size = u32::try_from(body.len())
+            .map_err(|_| SerializationError::Unsupported(format!("Attribute `{name}` is too long")))?;
+        out.write_u32::<BigEndian>(size)?;
+        out.write_all(&body)?;
+        Ok(())
+    }
+
+    impl SerializableLinked for UnknownAttribute {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<CodeAttribute, DeserializationError> {
-            let _ = cursor.read_u32::<BigEndian>()?;
-            let max_stack = cursor.read_u16::<BigEndian>()?;
-            let max_local = cursor.read_u16::<BigEndian>()?;
-            let code_length = cursor.read_u32::<BigEndian>()?;
-            if code_length == 0 {
-                return Err(DeserializationError::Link);
-            }
-            let code = (0..code_length)
-                .map(|_| cursor.read_u8())
-                .collect::<Result<Vec<u8>, Error>>()?;
-            let exception_table_length = cursor.read_u16::<BigEndian>()?;
-            let exceptions = (0..exception_table_length)
-                .map(|_| ExceptionEntry::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<ExceptionEntry>, DeserializationError>>()?;
-            let attributes: Vec<CodeAttributes> = Vec::deserialize_link(&mut cursor, pool)?;
-            Ok(CodeAttribute {
-                max_stack,
-                max_local,
-                code,
-                exceptions,
-                attributes,
-            })
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            serialize_attribute_body(&self.name, out, pool, |body, _| Ok(body.write_all(&self.data)?))
         }
     }
 
-    impl DeserializableLinked for Attribute {
+    impl SerializableLinked for Attribute {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Attribute, DeserializationError> {
-            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            match name.as_str() {
-                "Synthetic" => Ok(Attribute::Synthetic(SyntheticAttribute::deserialize(&mut cursor)?)),
-                "SourceFile" => Ok(Attribute::SourceFile(SourceFileAttribute::deserialize_link(&mut cursor, pool)?)),
-                "LineNumberTable" => Ok(Attribute::LineNumberTable(LineNumberTableAttribute::deserialize(&mut cursor)?)),
-                "LocalVariableTable" => Ok(Attribute::LocalVariableTable(LocalVariableTableAttribute::deserialize_link(&mut cursor, pool)?)),
-                "Deprecated" => Ok(Attribute::Deprecated(DeprecatedAttribute {})),
-                "InnerClasses" => Ok(Attribute::InnerClasses(InnerClassesAttribute::deserialize_link(&mut cursor, pool)?)),
-                "Exceptions" => Ok(Attribute::Exceptions(ExceptionsAttribute::deserialize_link(&mut cursor, pool)?)),
-                "Code" => Ok(Attribute::Code(CodeAttribute::deserialize_link(&mut cursor, pool)?)),
-                "ConstantValue" => Ok(Attribute::ConstantValue(ConstantValueAttribute::deserialize_link(&mut cursor, pool)?)),
-                _ => Ok(Attribute::Unknown(UnknownAttribute::deserialize(&mut cursor)?))
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                Attribute::Code(code) => serialize_attribute_body("Code", out, pool, |body, pool| code.serialize_link(body, pool)),
+                Attribute::Exceptions(exceptions) => serialize_attribute_body("Exceptions", out, pool, |body, pool| exceptions.serialize_link(body, pool)),
+                Attribute::InnerClasses(classes) => serialize_attribute_body("InnerClasses", out, pool, |body, pool| classes.serialize_link(body, pool)),
+                Attribute::SourceFile(file) => serialize_attribute_body("SourceFile", out, pool, |body, pool| file.serialize_link(body, pool)),
+                Attribute::LineNumberTable(lines) => serialize_attribute_body("LineNumberTable", out, pool, |body, _| lines.serialize(body)),
+                Attribute::LocalVariableTable(variables) => serialize_attribute_body("LocalVariableTable", out, pool, |body, pool| variables.serialize_link(body, pool)),
+                Attribute::Deprecated(_) => serialize_attribute_body("Deprecated", out, pool, |_, _| Ok(())),
+                Attribute::ConstantValue(constant) => serialize_attribute_body("ConstantValue", out, pool, |body, pool| constant.serialize_link(body, pool)),
+                Attribute::Synthetic(_) => serialize_attribute_body("Synthetic", out, pool, |_, _| Ok(())),
+                Attribute::Signature(signature) => serialize_attribute_body("Signature", out, pool, |body, pool| signature.serialize_link(body, pool)),
+                Attribute::RuntimeVisibleAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeVisibleAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::RuntimeInvisibleAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeInvisibleAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::RuntimeVisibleParameterAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeVisibleParameterAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::RuntimeInvisibleParameterAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeInvisibleParameterAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::RuntimeVisibleTypeAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeVisibleTypeAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::RuntimeInvisibleTypeAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeInvisibleTypeAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                Attribute::AnnotationDefault(default) => serialize_attribute_body("AnnotationDefault", out, pool, |body, pool| default.serialize_link(body, pool)),
+                Attribute::PermittedSubclasses(permitted) =>
+                    serialize_attribute_body("PermittedSubclasses", out, pool, |body, pool| permitted.serialize_link(body, pool)),
+                Attribute::NestHost(host) => serialize_attribute_body("NestHost", out, pool, |body, pool| host.serialize_link(body, pool)),
+                Attribute::NestMembers(members) => serialize_attribute_body("NestMembers", out, pool, |body, pool| members.serialize_link(body, pool)),
+                Attribute::Module(module) => serialize_attribute_body("Module", out, pool, |body, pool| module.serialize_link(body, pool)),
+                Attribute::ModulePackages(packages) =>
+                    serialize_attribute_body("ModulePackages", out, pool, |body, pool| packages.serialize_link(body, pool)),
+                Attribute::ModuleMainClass(main) => serialize_attribute_body("ModuleMainClass", out, pool, |body, pool| main.serialize_link(body, pool)),
+                Attribute::Record(record) => serialize_attribute_body("Record", out, pool, |body, pool| record.serialize_link(body, pool)),
+                Attribute::BootstrapMethods(bootstrap) =>
+                    serialize_attribute_body("BootstrapMethods", out, pool, |body, pool| bootstrap.serialize_link(body, pool)),
+                Attribute::Custom { name, data, .. } => serialize_attribute_body(name, out, pool, |body, _| Ok(body.write_all(data)?)),
+                Attribute::Unknown(unknown) => unknown.serialize_link(out, pool)
             }
         }
     }
 
-    impl DeserializableLinked for CodeAttributes {
+    impl SerializableLinked for CodeAttributes {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<CodeAttributes, DeserializationError> {
-            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            match name.as_str() {
-                "LineNumberTable" => Ok(CodeAttributes::LineNumberTable(LineNumberTableAttribute::deserialize(&mut cursor)?)),
-                "LocalVariableTable" => Ok(CodeAttributes::LocalVariableTable(LocalVariableTableAttribute::deserialize_link(&mut cursor, pool)?)),
-                _ => Ok(CodeAttributes::Unknown(UnknownAttribute::deserialize(&mut cursor)?))
+        fn serialize_link(&self, out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            match self {
+                CodeAttributes::LineNumberTable(lines) => serialize_attribute_body("LineNumberTable", out, pool, |body, _| lines.serialize(body)),
+                CodeAttributes::LocalVariableTable(variables) =>
+                    serialize_attribute_body("LocalVariableTable", out, pool, |body, pool| variables.serialize_link(body, pool)),
+                CodeAttributes::LocalVariableTypeTable(variables) =>
+                    serialize_attribute_body("LocalVariableTypeTable", out, pool, |body, pool| variables.serialize_link(body, pool)),
+                CodeAttributes::StackMapTable(frames) => serialize_attribute_body("StackMapTable", out, pool, |body, pool| frames.serialize_link(body, pool)),
+                CodeAttributes::RuntimeVisibleTypeAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeVisibleTypeAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                CodeAttributes::RuntimeInvisibleTypeAnnotations(annotations) =>
+                    serialize_attribute_body("RuntimeInvisibleTypeAnnotations", out, pool, |body, pool| annotations.serialize_link(body, pool)),
+                CodeAttributes::Unknown(unknown) => unknown.serialize_link(out, pool)
             }
         }
     }
 
-    impl DeserializableLinked for Vec<Attribute> {
+    impl<Access: BitFlag> SerializableLinked for ComponentInfo<Access> where BitFlags<Access>: Serializable {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<Attribute>, DeserializationError> {
-            let attributes_count = cursor.read_u16::<BigEndian>()?;
-            let attributes = (0..attributes_count)
-                .map(|_| Attribute::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<Attribute>, DeserializationError>>()?;
-            Ok(attributes)
+        fn serialize_link(&self, mut out: impl WriteBytesExt, pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+            self.access.serialize(&mut out)?;
+            write_index(&mut out, pool.utf8(&self.name))?;
+            write_index(&mut out, pool.utf8(&self.descriptor))?;
+            self.attributes.serialize_link(out, pool)
         }
     }
 
-    impl DeserializableLinked for Vec<CodeAttributes> {
+    impl Serializable for ClassVersion {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<CodeAttributes>, DeserializationError> {
-            let attributes_count = cursor.read_u16::<BigEndian>()?;
-            let attributes = (0..attributes_count)
-                .map(|_| CodeAttributes::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<CodeAttributes>, DeserializationError>>()?;
-            Ok(attributes)
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            out.write_u16::<BigEndian>(self.minor)?;
+            Ok(out.write_u16::<BigEndian>(self.major)?)
         }
     }
 
-    impl DeserializableLinked for ComponentInfo {
+    impl Serializable for Class {
         #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<ComponentInfo, DeserializationError> {
-            let access = BitFlags::deserialize(&mut cursor)?;
-            let name = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            let descriptor = Utf8Info::deserialize_link(&mut cursor, pool)?;
-            let attributes: Vec<Attribute> = Vec::deserialize_link(&mut cursor, pool)?;
-            Ok(ComponentInfo {
-                access,
-                name,
-                descriptor,
-                attributes,
-            })
+        fn serialize(&self, mut out: impl WriteBytesExt) -> Result<(), SerializationError> {
+            let mut pool = ConstPoolWriter::new(&self.const_pool);
+            let mut body = Vec::new();
+            self.access.serialize(&mut body)?;
+            self.this_class.serialize_link(&mut body, &mut pool)?;
+            self.super_class.serialize_link(&mut body, &mut pool)?;
+            self.interfaces.serialize_link(&mut body, &mut pool)?;
+            self.fields.serialize_link(&mut body, &mut pool)?;
+            self.methods.serialize_link(&mut body, &mut pool)?;
+            self.attributes.serialize_link(&mut body, &mut pool)?;
+            let mut entries = Vec::new();
+            pool.serialize_entries(&mut entries)?;
+
+            out.write_u32::<BigEndian>(0xCAFEBABE)?;
+            self.version.serialize(&mut out)?;
+            write_count(&mut out, pool.len() + 1)?;
+            out.write_all(&entries)?;
+            out.write_all(&body)?;
+            Ok(())
         }
     }
 
-    impl DeserializableLinked for Vec<ComponentInfo> {
-        #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<ComponentInfo>, DeserializationError> {
-            let components_count = cursor.read_u16::<BigEndian>()?;
-            let components = (0..components_count)
-                .map(|_| ComponentInfo::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<ComponentInfo>, DeserializationError>>()?;
-            Ok(components)
+    pub fn serialize_class(class: &Class, out: impl Write) -> Result<(), SerializationError> {
+        class.serialize(out)
+    }
+
+    pub fn roundtrip_bytes(input: &[u8]) -> Result<Vec<u8>, SerializationError> {
+        let class = deserialization::deserializable_class(input)?;
+        let mut output = Vec::with_capacity(input.len());
+        serialize_class(&class, &mut output)?;
+        Ok(output)
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct AttributeStats {
+        pub count: usize,
+        pub bytes: usize
+    }
+
+    // Sizes are those of the class as this crate writes it. Attribute bytes include the six byte header, and the
+    // attributes of a Code attribute are counted on their own as well as inside it.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct ClassStats {
+        pub size: usize,
+        pub const_pool: BTreeMap<&'static str, usize>,
+        pub method_code: Vec<(String, usize)>,
+        pub attributes: BTreeMap<String, AttributeStats>
+    }
+
+    #[inline(always)]
+    fn tag_name(entry: &ConstPoolType) -> Option<&'static str> {
+        Some(match entry {
+            ConstPoolType::Utf8(_) => "Utf8",
+            ConstPoolType::Int(_) => "Integer",
+            ConstPoolType::Float(_) => "Float",
+            ConstPoolType::Long(_) => "Long",
+            ConstPoolType::Double(_) => "Double",
+            ConstPoolType::Class(_) => "Class",
+            ConstPoolType::String(_) => "String",
+            ConstPoolType::Field(_) => "Fieldref",
+            ConstPoolType::MethodRef(_) => "Methodref",
+            ConstPoolType::InterfaceMethodRef(_) => "InterfaceMethodref",
+            ConstPoolType::NameAndType(_) => "NameAndType",
+            ConstPoolType::MethodHandle(_) => "MethodHandle",
+            ConstPoolType::MethodType(_) => "MethodType",
+            ConstPoolType::Dynamic(_) => "Dynamic",
+            ConstPoolType::InvokeDynamic(_) => "InvokeDynamic",
+            ConstPoolType::Module(_) => "Module",
+            ConstPoolType::Package(_) => "Package",
+            ConstPoolType::Placeholder => return None
+        })
+    }
+
+    fn count_attribute(stats: &mut ClassStats, name: &str, attribute: &impl SerializableLinked,
+                       pool: &mut ConstPoolWriter) -> Result<(), SerializationError> {
+        let mut bytes = Vec::new();
+        attribute.serialize_link(&mut bytes, pool)?;
+        let entry = stats.attributes.entry(name.to_string()).or_default();
+        entry.count += 1;
+        entry.bytes += bytes.len();
+        Ok(())
+    }
+
+    // Class lives in the model crate, so the metrics are attached through this trait.
+    pub trait ClassMetrics {
+        fn stats(&self) -> Result<ClassStats, SerializationError>;
+    }
+
+    impl ClassMetrics for Class {
+        fn stats(&self) -> Result<ClassStats, SerializationError> {
+            let mut bytes = Vec::new();
+            serialize_class(self, &mut bytes)?;
+            let mut stats = ClassStats {
+                size: bytes.len(),
+                ..ClassStats::default()
+            };
+            for tag in self.const_pool.iter().filter_map(tag_name) {
+                *stats.const_pool.entry(tag).or_default() += 1;
+            }
+            stats.method_code = self.methods.iter()
+                .filter_map(|method| Some((format!("{}{}", method.name, method.descriptor), method.code_size()?)))
+                .collect();
+            let mut pool = ConstPoolWriter::new(&self.const_pool);
+            let attributes = self.fields.iter().flat_map(|field| &field.attributes)
+                .chain(self.methods.iter().flat_map(|method| &method.attributes))
+                .chain(&self.attributes);
+            for attribute in attributes {
+                count_attribute(&mut stats, attribute.name(), attribute, &mut pool)?;
+                if let Attribute::Code(code) = attribute {
+                    for nested in &code.attributes {
+                        count_attribute(&mut stats, nested.name(), nested, &mut pool)?;
+                    }
+                }
+            }
+            Ok(stats)
         }
     }
+}
 
-    impl Deserializable for ClassVersion {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<ClassVersion, DeserializationError> {
-            Ok(ClassVersion {
-                minor: cursor.read_u16::<BigEndian>()?,
-                major: cursor.read_u16::<BigEndian>()?,
+pub mod builder {
+    use super::*;
+    use super::bytecode::{assemble, CodeAnalysis, Instruction};
+    use super::descriptor::parse_method_descriptor;
+    use super::serialization::ConstPoolWriter;
+    use class::Shared;
+    use class::attributes::{Attribute, CodeAttribute, ConstantValueAttribute, ConstValueType};
+    use class::const_pool::{ClassInfo, ComponentRef, ConstPoolType, NameAndTypeInfoStruct, Utf8Info};
+    use class::components::{AccessSpecifier, ClassAccess, ClassVersion, FieldInfo, MethodAccessSpecifier, MethodInfo};
+    use class::BitFlags;
+    use class::Class;
+
+    // Every entry interns the entries it refers to first, so the resulting pool is complete on its own.
+    pub struct ConstPoolBuilder {
+        writer: ConstPoolWriter
+    }
+
+    impl Default for ConstPoolBuilder {
+        fn default() -> Self {
+            ConstPoolBuilder {
+                writer: ConstPoolWriter::new(&[])
+            }
+        }
+    }
+
+    impl ConstPoolBuilder {
+        pub fn new() -> ConstPoolBuilder {
+            ConstPoolBuilder::default()
+        }
+
+        pub fn utf8(&mut self, value: &str) -> Result<u16, SerializationError> {
+            self.writer.utf8(value)
+        }
+
+        pub fn class(&mut self, name: &str) -> Result<u16, SerializationError> {
+            self.utf8(name)?;
+            self.writer.class(&ClassInfo(Utf8Info::from(name)))
+        }
+
+        pub fn string(&mut self, value: &str) -> Result<u16, SerializationError> {
+            self.utf8(value)?;
+            self.writer.index(ConstPoolType::String(Utf8Info::from(value)))
+        }
+
+        pub fn int(&mut self, value: i32) -> Result<u16, SerializationError> {
+            self.writer.index(ConstPoolType::Int(value))
+        }
+
+        pub fn long(&mut self, value: i64) -> Result<u16, SerializationError> {
+            self.writer.index(ConstPoolType::Long(value))
+        }
+
+        pub fn float(&mut self, value: f32) -> Result<u16, SerializationError> {
+            self.writer.index(ConstPoolType::Float(value))
+        }
+
+        pub fn double(&mut self, value: f64) -> Result<u16, SerializationError> {
+            self.writer.index(ConstPoolType::Double(value))
+        }
+
+        pub fn name_and_type(&mut self, name: &str, descriptor: &str) -> Result<u16, SerializationError> {
+            self.utf8(name)?;
+            self.utf8(descriptor)?;
+            self.writer.index(ConstPoolType::NameAndType(Shared::new(name_and_type(name, descriptor))))
+        }
+
+        pub fn field_ref(&mut self, class: &str, name: &str, descriptor: &str) -> Result<u16, SerializationError> {
+            let reference = self.component_ref(class, name, descriptor)?;
+            self.writer.index(ConstPoolType::Field(reference))
+        }
+
+        pub fn method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> Result<u16, SerializationError> {
+            let reference = self.component_ref(class, name, descriptor)?;
+            self.writer.index(ConstPoolType::MethodRef(reference))
+        }
+
+        pub fn interface_method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> Result<u16, SerializationError> {
+            let reference = self.component_ref(class, name, descriptor)?;
+            self.writer.index(ConstPoolType::InterfaceMethodRef(reference))
+        }
+
+        // Interns the entry a ConstantValue attribute refers to; the narrow integral types are stored as int.
+        pub fn constant(&mut self, value: &ConstValueType) -> Result<u16, SerializationError> {
+            match value {
+                ConstValueType::Float(value) => self.float(*value),
+                ConstValueType::Double(value) => self.double(*value),
+                ConstValueType::Int(value) => self.int(*value),
+                ConstValueType::Long(value) => self.long(*value),
+                ConstValueType::Short(value) => self.int(*value as i32),
+                ConstValueType::Byte(value) => self.int(*value as i32),
+                ConstValueType::Boolean(value) => self.int(*value as i32),
+                ConstValueType::Char(value) => self.int(*value as i32),
+                ConstValueType::String(value) => self.string(value)
+            }
+        }
+
+        pub fn build(self) -> Vec<ConstPoolType> {
+            self.writer.into_entries()
+        }
+
+        fn component_ref(&mut self, class: &str, name: &str, descriptor: &str) -> Result<ComponentRef, SerializationError> {
+            self.class(class)?;
+            self.name_and_type(name, descriptor)?;
+            Ok(ComponentRef {
+                class: ClassInfo(Utf8Info::from(class)),
+                name_and_type: Shared::new(name_and_type(name, descriptor))
             })
         }
     }
 
-    impl DeserializableLinked for Vec<ClassInfo> {
-        #[inline(always)]
-        fn deserialize_link(mut cursor: impl Read + ReadBytesExt, pool: &[ConstPoolType]) -> Result<Vec<ClassInfo>, DeserializationError> {
-            let count = cursor.read_u16::<BigEndian>()?;
-            let classes = (0..count)
-                .map(|_| ClassInfo::deserialize_link(&mut cursor, pool))
-                .collect::<Result<Vec<ClassInfo>, DeserializationError>>()?;
-            Ok(classes)
+    #[inline(always)]
+    fn name_and_type(name: &str, descriptor: &str) -> NameAndTypeInfoStruct {
+        NameAndTypeInfoStruct {
+            name: Utf8Info::from(name),
+            descriptor: Utf8Info::from(descriptor)
         }
     }
 
-    impl Deserializable for Class {
-        #[inline(always)]
-        fn deserialize(mut cursor: impl Read + ReadBytesExt) -> Result<Class, DeserializationError> {
-            let magick = cursor.read_u32::<BigEndian>()?;
-            if magick != 0xCAFEBABE {
-                return Err(DeserializationError::Parsing("Its not JVM class file.".into()));
+    pub struct MethodBuilder {
+        access: BitFlags<MethodAccessSpecifier>,
+        name: String,
+        descriptor: String,
+        instructions: Vec<Instruction>,
+        max_stack: Option<u16>,
+        max_locals: Option<u16>
+    }
+
+    impl MethodBuilder {
+        pub fn new(name: &str, descriptor: &str) -> MethodBuilder {
+            MethodBuilder {
+                access: MethodAccessSpecifier::Public.into(),
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                instructions: vec![],
+                max_stack: None,
+                max_locals: None
             }
-            let version = ClassVersion::deserialize(&mut cursor)?;
-            let const_pool: Vec<ConstPoolType> = Vec::deserialize(&mut cursor)?;
-            let access: BitFlags<ClassAccess> = BitFlags::deserialize(&mut cursor)?;
-            let this_class = ClassInfo::deserialize_link(&mut cursor, &const_pool)?;
-            let super_class = ClassInfo::deserialize_link(&mut cursor, &const_pool).ok();
-            let interfaces: Vec<ClassInfo> = Vec::deserialize_link(&mut cursor, &const_pool)?;
-            let fields: Vec<FieldInfo> = Vec::deserialize_link(&mut cursor, &const_pool)?;
-            let methods: Vec<MethodInfo> = Vec::deserialize_link(&mut cursor, &const_pool)?;
-            let attributes: Vec<Attribute> = Vec::deserialize_link(&mut cursor, &const_pool)?;
-            Ok(Class {
-                version,
-                const_pool,
-                access,
-                this_class,
-                super_class,
-                interfaces,
-                fields,
-                methods,
-                attributes,
+        }
+
+        pub fn access(mut self, access: BitFlags<MethodAccessSpecifier>) -> MethodBuilder {
+            self.access = access;
+            self
+        }
+
+        pub fn instr(mut self, instruction: Instruction) -> MethodBuilder {
+            self.instructions.push(instruction);
+            self
+        }
+
+        pub fn max_stack(mut self, max_stack: u16) -> MethodBuilder {
+            self.max_stack = Some(max_stack);
+            self
+        }
+
+        pub fn max_locals(mut self, max_locals: u16) -> MethodBuilder {
+            self.max_locals = Some(max_locals);
+            self
+        }
+
+        // Without a class the instructions cannot refer to the constant pool; use ClassBuilder::method_builder for that.
+        pub fn build(self) -> Result<MethodInfo, SerializationError> {
+            self.assemble(&ClassBuilder::new("").build()?)
+        }
+
+        // Limits that were not set explicitly are computed from the code, which resolves invocations against the class.
+        fn assemble(self, class: &Class) -> Result<MethodInfo, SerializationError> {
+            let mut code = CodeAttribute {
+                max_stack: 0,
+                max_local: 0,
+                code: assemble(&self.instructions)?,
+                exceptions: vec![],
+                attributes: vec![]
+            };
+            code.max_stack = match self.max_stack {
+                Some(max_stack) => max_stack,
+                None => code.compute_max_stack(class)?
+            };
+            code.max_local = match self.max_locals {
+                Some(max_locals) => max_locals,
+                None => {
+                    let descriptor = parse_method_descriptor(&self.descriptor)
+                        .map_err(|error| SerializationError::Unsupported(error.to_string()))?;
+                    code.compute_max_locals(&descriptor, self.access.contains(MethodAccessSpecifier::Static))?
+                }
+            };
+            Ok(MethodInfo {
+                access: self.access,
+                name: Utf8Info::from(self.name),
+                descriptor: Utf8Info::from(self.descriptor),
+                attributes: vec![Attribute::Code(code)]
             })
         }
     }
 
-    pub fn deserializable_class(mut cursor: impl Read + ReadBytesExt) -> Result<Class, DeserializationError> {
-        Class::deserialize(&mut cursor)
+    pub struct FieldBuilder {
+        access: BitFlags<AccessSpecifier>,
+        name: String,
+        descriptor: String,
+        constant_value: Option<ConstValueType>
     }
-}
 
+    impl FieldBuilder {
+        pub fn new(name: &str, descriptor: &str) -> FieldBuilder {
+            FieldBuilder {
+                access: AccessSpecifier::Public.into(),
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                constant_value: None
+            }
+        }
+
+        pub fn access(mut self, access: BitFlags<AccessSpecifier>) -> FieldBuilder {
+            self.access = access;
+            self
+        }
+
+        // Only honoured by the JVM on static fields.
+        pub fn constant_value(mut self, value: ConstValueType) -> FieldBuilder {
+            self.constant_value = Some(value);
+            self
+        }
+
+        pub fn build(self) -> FieldInfo {
+            FieldInfo {
+                access: self.access,
+                name: Utf8Info::from(self.name),
+                descriptor: Utf8Info::from(self.descriptor),
+                attributes: self.constant_value.into_iter()
+                    .map(|value| Attribute::ConstantValue(ConstantValueAttribute { value }))
+                    .collect()
+            }
+        }
+    }
+
+    pub struct ClassBuilder {
+        version: ClassVersion,
+        access: BitFlags<ClassAccess>,
+        name: String,
+        super_class: Option<String>,
+        interfaces: Vec<String>,
+        fields: Vec<FieldInfo>,
+        methods: Vec<MethodInfo>,
+        method_builders: Vec<MethodBuilder>,
+        pool: ConstPoolBuilder
+    }
+
+    impl ClassBuilder {
+        // Starts a public class targeting Java 11 that extends java/lang/Object.
+        pub fn new(name: &str) -> ClassBuilder {
+            ClassBuilder {
+                version: ClassVersion {
+                    minor: 0,
+                    major: 55
+                },
+                access: ClassAccess::Public | ClassAccess::Super,
+                name: name.to_string(),
+                super_class: Some("java/lang/Object".to_string()),
+                interfaces: vec![],
+                fields: vec![],
+                methods: vec![],
+                method_builders: vec![],
+                pool: ConstPoolBuilder::new()
+            }
+        }
+
+        pub fn version(mut self, major: u16, minor: u16) -> ClassBuilder {
+            self.version = ClassVersion { minor, major };
+            self
+        }
+
+        pub fn access(mut self, access: BitFlags<ClassAccess>) -> ClassBuilder {
+            self.access = access;
+            self
+        }
+
+        pub fn super_class(mut self, super_class: Option<&str>) -> ClassBuilder {
+            self.super_class = super_class.map(str::to_string);
+            self
+        }
+
+        pub fn interface(mut self, interface: &str) -> ClassBuilder {
+            self.interfaces.push(interface.to_string());
+            self
+        }
+
+        pub fn field(mut self, field: FieldInfo) -> ClassBuilder {
+            self.fields.push(field);
+            self
+        }
+
+        pub fn method(mut self, method: MethodInfo) -> ClassBuilder {
+            self.methods.push(method);
+            self
+        }
 
+        // The method is assembled by build, so its instructions may use indices from pool.
+        pub fn method_builder(mut self, method: MethodBuilder) -> ClassBuilder {
+            self.method_builders.push(method);
+            self
+        }
 
+        pub fn pool(&mut self) -> &mut ConstPoolBuilder {
+            &mut self.pool
+        }
 
+        pub fn build(mut self) -> Result<Class, SerializationError> {
+            self.pool.class(&self.name)?;
+            if let Some(super_class) = &self.super_class {
+                self.pool.class(super_class)?;
+            }
+            for interface in &self.interfaces {
+                self.pool.class(interface)?;
+            }
+            let names = self.fields.iter().map(|field| (&field.name, &field.descriptor))
+                .chain(self.methods.iter().map(|method| (&method.name, &method.descriptor)))
+                .map(|(name, descriptor)| (name.as_str(), descriptor.as_str()))
+                .chain(self.method_builders.iter().map(|method| (method.name.as_str(), method.descriptor.as_str())));
+            for (name, descriptor) in names {
+                self.pool.utf8(name)?;
+                self.pool.utf8(descriptor)?;
+            }
+            if self.methods.iter().any(|method| method.code().is_some()) || !self.method_builders.is_empty() {
+                self.pool.utf8("Code")?;
+            }
+            let constants = self.fields.iter().flat_map(|field| &field.attributes).filter_map(|attribute| match attribute {
+                Attribute::ConstantValue(constant) => Some(&constant.value),
+                _ => None
+            });
+            for value in constants {
+                self.pool.utf8("ConstantValue")?;
+                self.pool.constant(value)?;
+            }
+            let class_info = |name: &str| ClassInfo(Utf8Info::from(name));
+            let mut class = Class {
+                version: self.version,
+                const_pool: self.pool.build(),
+                access: self.access,
+                this_class: class_info(&self.name),
+                super_class: self.super_class.as_deref().map(class_info),
+                interfaces: self.interfaces.iter().map(|interface| class_info(interface)).collect(),
+                fields: self.fields,
+                methods: self.methods,
+                attributes: vec![]
+            };
+            for method in self.method_builders {
+                let method = method.assemble(&class)?;
+                class.methods.push(method);
+            }
+            Ok(class)
+        }
+    }
+}
@@ -1,12 +1,44 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::fs::File;
-    use std::io::BufReader;
-    use class_parser::deserialization::deserializable_class;
+    use std::io::{BufReader, Read};
+    use class_parser::deserialization::{deserializable_class, deserializable_class_with_options,
+                                          canonical_attribute_name, decode_attribute, deserializable_class_lenient, deserializable_class_with_limits, from_bytes, parse_file,
+                                          AttributeRegistry, LazyAttributes, ParseClass, ParseLimits, ParseOptions, StringInterner};
+    use class_parser::{DescriptorError, DeserializationError, SerializationError, ValidationError};
+    use class_parser::serialization::{roundtrip_bytes, serialize_class, AttributeStats, ClassMetrics};
+    use class_parser::fmt::{disassemble, disassemble_verbose};
+    use class_parser::descriptor::{parse_field_descriptor, parse_method_descriptor, to_java_type, FieldType, MethodDescriptor, ReturnKind};
+    use class_parser::signature::{parse_class_signature, parse_field_signature, parse_method_signature, JavaType, ReferenceType, TypeArgument};
+    use class_parser::validation::{validate, validate_const_pool};
+    use class_parser::visitor::{visit_class, ClassVisitor, MethodVisitor};
+    use class_parser::builder::{ClassBuilder, ConstPoolBuilder, FieldBuilder, MethodBuilder};
+    use class_parser::hierarchy::Hierarchy;
+    use class_parser::diff::{diff, AttributeChange, ClassChange, MemberChange};
+    use class_parser::bytecode::{assemble, decode, instructions, stack_effect, Callee, CodeAnalysis, Instruction, InvokeKind, MethodBytecode};
+    use class_parser::mutf8::{decode_modified_utf8, encode_modified_utf8};
     use std::path::PathBuf;
-    use class::components::ClassAccess;
+    use std::rc::Rc;
+    use class::components::{AccessSpecifier, ClassAccess, ClassVersion, ComponentInfo, FieldInfo, MethodAccessSpecifier};
+    use class::attributes::{Attribute, CodeAttribute, CodeAttributes, ConstantValueAttribute, ConstValueType, CustomAttribute,
+                            DeprecatedAttribute, ElementValue, LineNumberEntry, LineNumberTableAttribute, LocalVariableTarget,
+                            ModuleMainClassAttribute, ModulePackagesAttribute, RequiresAccess, StackMapFrame, SyntheticAttribute,
+                            TargetInfo, TypeAnnotation, TypePathEntry, UnknownAttribute, VerificationType};
+    use class::{BitFlags, Class};
+    use class::const_pool::{ClassInfo, ConstPoolType, PackageInfo, Utf8Info};
     //use class::const_pool::ConstPoolType::Class;
 
+    fn load_class(file_name: &str) -> Class {
+        let f = File::open(PathBuf::from(env!("TEST_RESOURCES_PATH")).join(file_name)).unwrap();
+        let mut cursor = BufReader::new(f);
+        deserializable_class(&mut cursor).unwrap()
+    }
+
+    fn method_code<'a>(class: &'a Class, name: &str) -> &'a CodeAttribute {
+        class.methods.iter().find(|m| m.name.as_str() == name).unwrap().code().unwrap()
+    }
+
     #[test]
     fn load_simple_class_file() {
         let f = File::open(PathBuf::from(env!("TEST_RESOURCES_PATH")).join("HelloWorld.class")).unwrap();
@@ -23,5 +55,1978 @@ mod tests {
         assert_eq!(class.attributes.len(), 1);
         assert_eq!(class.access, ClassAccess::Public | ClassAccess::Super);
     }
-}
 
+    #[test]
+    fn code_attribute_nested_attributes() {
+        let class = load_class("HelloWorld.class");
+        let code = method_code(&class, "main");
+        let lines = code.line_number_table().unwrap();
+        assert_eq!(lines.lines.len(), 2);
+        assert_eq!(lines.lines[0].start_pc, 0);
+        assert_eq!(lines.lines[0].line, 4);
+        assert_eq!(lines.lines[1].start_pc, 8);
+        assert_eq!(lines.lines[1].line, 5);
+        assert!(code.local_variable_table().is_none());
+    }
+
+    #[test]
+    fn source_line_for_pc() {
+        let class = load_class("HelloWorld.class");
+        let code = method_code(&class, "main");
+        assert_eq!(code.source_line_for_pc(0), Some(4));
+        assert_eq!(code.source_line_for_pc(5), Some(4));
+        assert_eq!(code.source_line_for_pc(8), Some(5));
+        assert_eq!(code.source_line_for_pc(100), Some(5));
+
+        let unsorted = CodeAttribute {
+            max_stack: 0,
+            max_local: 0,
+            code: vec![0; 20],
+            exceptions: vec![],
+            attributes: vec![CodeAttributes::LineNumberTable(LineNumberTableAttribute {
+                lines: vec![
+                    LineNumberEntry { start_pc: 10, line: 12 },
+                    LineNumberEntry { start_pc: 4, line: 11 },
+                    LineNumberEntry { start_pc: 15, line: 13 },
+                ]
+            })]
+        };
+        assert_eq!(unsorted.source_line_for_pc(3), None);
+        assert_eq!(unsorted.source_line_for_pc(9), Some(11));
+        assert_eq!(unsorted.source_line_for_pc(10), Some(12));
+        assert_eq!(unsorted.source_line_for_pc(19), Some(13));
+    }
+
+    #[test]
+    fn modified_utf8_encoding() {
+        assert_eq!(encode_modified_utf8("a\0b"), vec![b'a', 0xC0, 0x80, b'b']);
+        assert_eq!(encode_modified_utf8("\u{10400}"), vec![0xED, 0xA0, 0x81, 0xED, 0xB0, 0x80]);
+        assert_eq!(decode_modified_utf8(&[0xC0, 0x80]).unwrap(), "\0");
+        assert!(decode_modified_utf8(&[0x00]).is_err());
+        assert!(decode_modified_utf8(&[0xF0, 0x90, 0x90, 0x80]).is_err());
+        assert!(decode_modified_utf8(&[0xE2, 0x82]).is_err());
+        assert_eq!(decode_modified_utf8(&[0xED, 0xA0, 0x81]).unwrap(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn modified_utf8_round_trip() {
+        let samples = ["", "\0", "Hello all!", "null\0inside", "\u{7F}\u{80}\u{7FF}\u{800}\u{FFFF}",
+            "za\u{17C}\u{F3}\u{142}\u{107}", "\u{1F600} emoji", "\u{10000}\u{10FFFF}"];
+        for sample in samples {
+            assert_eq!(decode_modified_utf8(&encode_modified_utf8(sample)).unwrap(), sample);
+        }
+        let code_points = (0u32..0x800).chain((0x800..0x10000).step_by(7)).chain((0x10000..0x110000).step_by(251));
+        for c in code_points.filter_map(char::from_u32) {
+            let sample = format!("<{c}>");
+            let encoded = encode_modified_utf8(&sample);
+            assert!(!encoded.contains(&0));
+            assert!(encoded.iter().all(|byte| *byte < 0xF0));
+            assert_eq!(decode_modified_utf8(&encoded).unwrap(), sample);
+        }
+    }
+
+    #[test]
+    fn method_refs_by_owner() {
+        let class = load_class("HelloWorld.class");
+        let owners = class.method_refs_by_owner();
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners["java/io/PrintStream"], vec![("println".to_string(), "(Ljava/lang/String;)V".to_string())]);
+        assert_eq!(owners["java/lang/Object"], vec![("<init>".to_string(), "()V".to_string())]);
+        assert!(!owners.contains_key("java/lang/System"));
+    }
+
+    fn read_resource(file_name: &str) -> Vec<u8> {
+        std::fs::read(PathBuf::from(env!("TEST_RESOURCES_PATH")).join(file_name)).unwrap()
+    }
+
+    fn replace_bytes(data: &mut Vec<u8>, from: &[u8], to: &[u8]) {
+        let position = data.windows(from.len()).position(|window| window == from).unwrap();
+        data.splice(position..position + from.len(), to.iter().copied());
+    }
+
+    #[test]
+    fn custom_attribute_handler() {
+        fn handler(name: &str, length: u32, bytes: &[u8]) -> Option<CustomAttribute> {
+            match name {
+                "CustomFile" if length == 2 => Some(Box::new(u16::from_be_bytes([bytes[0], bytes[1]]))),
+                _ => None
+            }
+        }
+
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"SourceFile", b"CustomFile");
+        let options = ParseOptions {
+            custom_attribute_handler: Some(handler),
+            ..ParseOptions::default()
+        };
+        let class = deserializable_class_with_options(&mut &data[..], &options).unwrap();
+        match &class.clone().attributes[0] {
+            Attribute::Custom { name, data, value } => {
+                assert_eq!((name.as_str(), &data[..]), ("CustomFile", &[0, 14][..]));
+                assert_eq!(value.downcast_ref::<u16>(), Some(&14));
+            }
+            other => panic!("unexpected attribute {other:?}")
+        }
+
+        let class = deserializable_class(&mut &data[..]).unwrap();
+        assert!(matches!(class.attributes[0], Attribute::Unknown(UnknownAttribute { size: 2, .. })));
+
+        replace_bytes(&mut data, b"CustomFile", b"IgnoreFile");
+        let class = deserializable_class_with_options(&mut &data[..], &options).unwrap();
+        assert!(matches!(class.attributes[0], Attribute::Unknown(UnknownAttribute { size: 2, .. })));
+    }
+
+    #[test]
+    fn constant_value_matches_field_type() {
+        let class = load_class("Constants.class");
+        assert_eq!(class.fields.len(), 4);
+        for field in &class.fields {
+            assert!(matches!(field.attributes[0], Attribute::ConstantValue(_)));
+        }
+
+        let mut data = read_resource("Constants.class");
+        replace_bytes(&mut data, b"\x01\x00\x01I", b"\x01\x00\x01J");
+        match deserializable_class(&mut &data[..]).map_err(DeserializationError::into_inner) {
+            Err(DeserializationError::Parsing(message)) => assert!(message.contains("ANSWER")),
+            other => panic!("unexpected result {other:?}")
+        }
+
+        assert_eq!(ConstValueType::from_int(0x1004A, "C"), None);
+        assert_eq!(ConstValueType::from_int(2, "Z"), None);
+        assert_eq!(ConstValueType::from_int(-129, "B"), None);
+        let mut data = read_resource("Constants.class");
+        replace_bytes(&mut data, b"\x03\x00\x00\x00\x4A", b"\x03\x00\x01\x00\x4A");
+        let class = deserializable_class(&mut &data[..]).unwrap();
+        assert_eq!(class.find_field("LETTER").unwrap().attributes,
+            vec![Attribute::ConstantValue(ConstantValueAttribute { value: ConstValueType::Int(0x1004A) })]);
+        assert_eq!(roundtrip_bytes(&data).unwrap(), data);
+    }
+
+    fn permitted_names(class: &Class) -> Vec<&str> {
+        class.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::PermittedSubclasses(permitted) => Some(permitted.classes.iter().map(|class| class.0.as_str()).collect()),
+            _ => None
+        }).unwrap()
+    }
+
+    #[test]
+    fn attribute_name_aliases() {
+        assert_eq!(canonical_attribute_name("PermittedSubtypes"), "PermittedSubclasses");
+        assert_eq!(canonical_attribute_name("PermittedSubclasses"), "PermittedSubclasses");
+        assert_eq!(canonical_attribute_name("SourceFile"), "SourceFile");
+
+        let mut data = read_resource("Shape.class");
+        replace_bytes(&mut data, b"\x00\x13PermittedSubclasses", b"\x00\x11PermittedSubtypes");
+        let class = deserializable_class(&mut &data[..]).unwrap();
+        assert_eq!(permitted_names(&class), vec!["Shape$Circle", "Shape$Square"]);
+    }
+
+    #[test]
+    fn clone_with_renamed_class() {
+        let class = load_class("HelloWorld.class");
+        let renamed = class.clone_with_renamed_class("org/example/Renamed");
+        assert_eq!(renamed.this_class.0.as_str(), "org/example/Renamed");
+        assert_eq!(class.this_class.0.as_str(), "HelloWorld");
+        assert_eq!(renamed.super_class.as_ref().unwrap().0.as_str(), "java/lang/Object");
+        assert!(renamed.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Class(class) if class.0.as_str() == "org/example/Renamed")));
+        assert!(!renamed.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Utf8(name) if name.as_str() == "HelloWorld")));
+        assert_eq!(renamed.const_pool.len(), class.const_pool.len());
+        assert_eq!(renamed.methods.len(), class.methods.len());
+        for (renamed_method, method) in renamed.methods.iter().zip(&class.methods) {
+            assert_eq!(renamed_method.name, method.name);
+            assert_eq!(renamed_method.descriptor, method.descriptor);
+            assert_eq!(method_code(&renamed, &method.name).code, method_code(&class, &method.name).code);
+        }
+
+        let class = load_class("Node.class");
+        let renamed = class.clone_with_renamed_class("org/example/Link");
+        assert!(!renamed.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Utf8(text) if text.contains("LNode<"))));
+        assert!(renamed.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Utf8(text) if text.as_str() == "()Lorg/example/Link<TT;>;")));
+        let types = method_code(&renamed, "last").local_variable_type_table().unwrap();
+        assert!(types.variables.iter().all(|variable| variable.signature.as_str() == "Lorg/example/Link<TT;>;"));
+    }
+
+    #[test]
+    fn method_code_size() {
+        let class = load_class("HelloWorld.class");
+        assert_eq!(class.methods[0].name.as_str(), "<init>");
+        assert_eq!(class.methods[0].code_size(), Some(5));
+        assert_eq!(class.methods[1].name.as_str(), "main");
+        assert_eq!(class.methods[1].code_size(), Some(9));
+        assert_eq!(class.largest_method(), Some(("main", 9)));
+        assert_eq!(load_class("Constants.class").fields[0].code_size(), None);
+    }
+
+    #[test]
+    fn narrow_int_constant_values() {
+        assert!(matches!(ConstValueType::from_int(1, "Z"), Some(ConstValueType::Boolean(true))));
+        assert!(matches!(ConstValueType::from_int(0, "Z"), Some(ConstValueType::Boolean(false))));
+        assert!(matches!(ConstValueType::from_int(74, "C"), Some(ConstValueType::Char(74))));
+        assert!(matches!(ConstValueType::from_int(-3, "B"), Some(ConstValueType::Byte(-3))));
+        assert!(matches!(ConstValueType::from_int(300, "S"), Some(ConstValueType::Short(300))));
+        assert!(matches!(ConstValueType::from_int(7, "I"), Some(ConstValueType::Int(7))));
+        assert!(ConstValueType::from_int(7, "J").is_none());
+
+        let class = load_class("Constants.class");
+        let values: Vec<&ConstValueType> = class.fields.iter().map(|field| match &field.attributes[0] {
+            Attribute::ConstantValue(constant) => &constant.value,
+            other => panic!("unexpected attribute {other:?}")
+        }).collect();
+        assert!(matches!(values[0], ConstValueType::Int(42)));
+        assert!(matches!(values[1], ConstValueType::Float(value) if *value == 0.5));
+        assert!(matches!(values[2], ConstValueType::Char(74)));
+        assert!(matches!(values[3], ConstValueType::String(value) if value.as_str() == "Hello"));
+    }
+
+    #[test]
+    fn const_pool_component_ref() {
+        let class = load_class("HelloWorld.class");
+        let method = class.const_pool[0].as_component_ref().unwrap();
+        assert_eq!(method.class.0.as_str(), "java/lang/Object");
+        assert_eq!(method.name_and_type.name.as_str(), "<init>");
+        assert_eq!(method.name_and_type.descriptor.as_str(), "()V");
+        let field = class.const_pool[1].as_component_ref().unwrap();
+        assert_eq!(field.class.0.as_str(), "java/lang/System");
+        assert_eq!(field.name_and_type.name.as_str(), "out");
+        assert!(class.const_pool[2].as_component_ref().is_none());
+    }
+
+    #[test]
+    fn lambda_heuristics() {
+        let lambda = ComponentInfo {
+            access: MethodAccessSpecifier::Private | MethodAccessSpecifier::Static | MethodAccessSpecifier::Synthetic,
+            name: Utf8Info::from("lambda$main$0"),
+            descriptor: Utf8Info::from("()V"),
+            attributes: vec![]
+        };
+        assert!(lambda.is_lambda_body());
+        let not_synthetic = ComponentInfo {
+            access: MethodAccessSpecifier::Private | MethodAccessSpecifier::Static,
+            ..lambda.clone()
+        };
+        assert!(!not_synthetic.is_lambda_body());
+        let synthetic_attribute = ComponentInfo {
+            attributes: vec![Attribute::Synthetic(SyntheticAttribute {})],
+            ..not_synthetic.clone()
+        };
+        assert!(synthetic_attribute.is_lambda_body());
+        let accessor = ComponentInfo {
+            name: Utf8Info::from("access$000"),
+            ..lambda
+        };
+        assert!(!accessor.is_lambda_body());
+
+        let class = load_class("HelloWorld.class");
+        assert!(!class.is_synthetic_lambda_class());
+        assert!(!class.methods.iter().any(|method| method.is_lambda_body()));
+        let mut proxy = class.clone_with_renamed_class("HelloWorld$$Lambda$1");
+        assert!(!proxy.is_synthetic_lambda_class());
+        proxy.access |= ClassAccess::Synthetic;
+        assert!(proxy.is_synthetic_lambda_class());
+    }
+
+    #[test]
+    fn invalid_reference_indices() {
+        let parse_corrupted = |corrupted: &[u8]| {
+            let mut data = read_resource("HelloWorld.class");
+            replace_bytes(&mut data, b"\x0A\x00\x06\x00\x0F", corrupted);
+            deserializable_class(&mut &data[..]).unwrap_err().into_inner()
+        };
+        assert!(matches!(parse_corrupted(b"\x0A\x00\x63\x00\x0F"), DeserializationError::RefClassIndex(99)));
+        assert!(matches!(parse_corrupted(b"\x0A\x00\x07\x00\x0F"), DeserializationError::RefClassIndex(7)));
+        assert!(matches!(parse_corrupted(b"\x0A\x00\x06\x00\x63"), DeserializationError::RefNameAndTypeIndex(99)));
+    }
+
+    #[test]
+    fn trust_content_over_length() {
+        let trusting = ParseOptions {
+            trust_content_over_length: true,
+            ..ParseOptions::default()
+        };
+        // main's Code attribute: name #9, declared length 37, max_stack 2, max_locals 1
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"\x00\x09\x00\x00\x00\x25\x00\x02\x00\x01", b"\x00\x09\x00\x00\x00\x28\x00\x02\x00\x01");
+        assert!(matches!(deserializable_class(&mut &data[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Parsing(_))));
+        let class = deserializable_class_with_options(&mut &data[..], &trusting).unwrap();
+        assert_eq!(method_code(&class, "main").code.len(), 9);
+        assert!(matches!(&class.attributes[0], Attribute::SourceFile(source) if source.file.as_str() == "HelloWorld.java"));
+
+        // SourceFile attribute at the end of the file, declared length 2 -> 5
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"\x00\x0D\x00\x00\x00\x02\x00\x0E", b"\x00\x0D\x00\x00\x00\x05\x00\x0E");
+        assert!(matches!(deserializable_class(&mut &data[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::CannotRead(_))));
+        let class = deserializable_class_with_options(&mut &data[..], &trusting).unwrap();
+        assert!(matches!(&class.attributes[0], Attribute::SourceFile(source) if source.file.as_str() == "HelloWorld.java"));
+    }
+
+    #[test]
+    fn shared_string_interner() {
+        let options = ParseOptions {
+            interner: Some(Rc::new(StringInterner::new())),
+            ..ParseOptions::default()
+        };
+        let hello = deserializable_class_with_options(&mut &read_resource("HelloWorld.class")[..], &options).unwrap();
+        let interned = options.interner.as_ref().unwrap().len();
+        let constants = deserializable_class_with_options(&mut &read_resource("Constants.class")[..], &options).unwrap();
+        assert!(options.interner.as_ref().unwrap().len() > interned);
+        assert!(Utf8Info::ptr_eq(&hello.super_class.as_ref().unwrap().0, &constants.super_class.as_ref().unwrap().0));
+        assert!(Utf8Info::ptr_eq(&hello.methods[0].name, &constants.methods[0].name));
+
+        let separate = load_class("Constants.class");
+        assert!(!Utf8Info::ptr_eq(&hello.super_class.as_ref().unwrap().0, &separate.super_class.as_ref().unwrap().0));
+    }
+
+    #[test]
+    fn wide_constants_take_two_slots() {
+        let class = load_class("WideConstants.class");
+        assert_eq!(class.const_pool.len(), 28);
+        assert!(matches!(class.const_pool[6], ConstPoolType::Long(2)));
+        assert!(matches!(class.const_pool[7], ConstPoolType::Placeholder));
+        assert!(matches!(&class.const_pool[8], ConstPoolType::Class(info) if info.0.as_str() == "WideConstants"));
+        assert!(matches!(class.const_pool[17], ConstPoolType::Double(_)));
+        assert!(matches!(class.const_pool[18], ConstPoolType::Placeholder));
+        assert_eq!(class.this_class.0.as_str(), "WideConstants");
+        let fields: Vec<(&str, &str)> = class.fields.iter()
+            .map(|field| (field.name.as_str(), field.descriptor.as_str()))
+            .collect();
+        assert_eq!(fields, vec![("BIG", "J"), ("RATE", "D"), ("AFTER", "I")]);
+        assert!(matches!(&class.fields[0].attributes[0],
+            Attribute::ConstantValue(constant) if matches!(constant.value, ConstValueType::Long(1234567890123))));
+        assert!(matches!(&class.fields[1].attributes[0],
+            Attribute::ConstantValue(constant) if matches!(constant.value, ConstValueType::Double(value) if value == 2.5)));
+        assert!(matches!(&class.fields[2].attributes[0],
+            Attribute::ConstantValue(constant) if matches!(constant.value, ConstValueType::Int(7))));
+        assert_eq!(class.methods[1].name.as_str(), "twice");
+        assert_eq!(class.methods[1].descriptor.as_str(), "(J)J");
+    }
+
+    #[test]
+    fn empty_const_pool_count() {
+        let header = b"\xCA\xFE\xBA\xBE\x00\x00\x00\x37\x00\x00";
+        assert!(matches!(deserializable_class(&mut &header[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Parsing(_))));
+    }
+
+    #[test]
+    fn utf8_short_reads() {
+        struct OneByteReader<'a>(&'a [u8]);
+
+        impl Read for OneByteReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match (self.0.split_first(), buf.first_mut()) {
+                    (Some((byte, rest)), Some(target)) => {
+                        *target = *byte;
+                        self.0 = rest;
+                        Ok(1)
+                    }
+                    _ => Ok(0)
+                }
+            }
+        }
+
+        let data = read_resource("HelloWorld.class");
+        let class = deserializable_class(OneByteReader(&data)).unwrap();
+        assert_eq!(class.this_class.0.as_str(), "HelloWorld");
+        assert!(matches!(&class.attributes[0], Attribute::SourceFile(source) if source.file.as_str() == "HelloWorld.java"));
+
+        let truncated = &data[..data.windows(10).position(|window| window == b"HelloWorld").unwrap() + 4];
+        assert!(matches!(deserializable_class(OneByteReader(truncated)).map_err(DeserializationError::into_inner), Err(DeserializationError::CannotRead(_))));
+    }
+
+    #[test]
+    fn modified_utf8_constants() {
+        let class = load_class("ModifiedUtf8.class");
+        let strings: Vec<&str> = class.fields.iter().skip(1).map(|field| match &field.attributes[0] {
+            Attribute::ConstantValue(constant) => match &constant.value {
+                ConstValueType::String(value) => value.as_str(),
+                other => panic!("unexpected value {other:?}")
+            },
+            other => panic!("unexpected attribute {other:?}")
+        }).collect();
+        assert_eq!(strings, vec!["smile \u{1F600}", "a\0b", "\u{FFFD}"]);
+        // The lone surrogate keeps its bytes, so the class is written back unchanged.
+        assert!(class.const_pool.iter().any(|entry| matches!(entry, ConstPoolType::Utf8(value) if value.raw() == Some(&b"\xED\xA0\x80"[..]))));
+        let data = read_resource("ModifiedUtf8.class");
+        assert_eq!(roundtrip_bytes(&data).unwrap(), data);
+
+        let mut data = read_resource("ModifiedUtf8.class");
+        replace_bytes(&mut data, b"\x00\x0Azero_field", b"\x00\x0Bzero\xC0\x80field");
+        let class = deserializable_class(&mut &data[..]).unwrap();
+        assert_eq!(class.fields[0].name.as_str(), "zero\0field");
+    }
+
+    fn class_with_pool(entries: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"\xCA\xFE\xBA\xBE\x00\x00\x00\x37".to_vec();
+        data.extend_from_slice(&(entries.len() as u16 + 1).to_be_bytes());
+        entries.iter().for_each(|entry| data.extend_from_slice(entry));
+        // public class #2 without superclass, interfaces, fields, methods or attributes
+        data.extend_from_slice(b"\x00\x21\x00\x02\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        data
+    }
+
+    #[test]
+    fn method_handle_constants() {
+        let pool: [&[u8]; 9] = [b"\x01\x00\x01A", b"\x07\x00\x01", b"\x01\x00\x01m", b"\x01\x00\x03()V",
+            b"\x0C\x00\x03\x00\x04", b"\x0A\x00\x02\x00\x05", b"\x09\x00\x02\x00\x05",
+            b"\x0F\x06\x00\x06", b"\x0F\x02\x00\x07"];
+        let class = deserializable_class(&mut &class_with_pool(&pool)[..]).unwrap();
+        match (&class.const_pool[7], &class.const_pool[8]) {
+            (ConstPoolType::MethodHandle(invoke_static), ConstPoolType::MethodHandle(get_static)) => {
+                assert_eq!(invoke_static.reference_kind, 6);
+                assert_eq!(invoke_static.reference.class.0.as_str(), "A");
+                assert_eq!(invoke_static.reference.name_and_type.name.as_str(), "m");
+                assert_eq!(get_static.reference_kind, 2);
+                assert_eq!(get_static.reference.name_and_type.descriptor.as_str(), "()V");
+            }
+            other => panic!("unexpected entries {other:?}")
+        }
+
+        let mut wrong_kind = pool;
+        wrong_kind[8] = b"\x0F\x05\x00\x07";
+        assert!(matches!(deserializable_class(&mut &class_with_pool(&wrong_kind)[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Link)));
+        wrong_kind[8] = b"\x0F\x0A\x00\x06";
+        assert!(matches!(deserializable_class(&mut &class_with_pool(&wrong_kind)[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Parsing(_))));
+    }
+
+    #[test]
+    fn method_type_constants() {
+        let pool: [&[u8]; 4] = [b"\x01\x00\x01A", b"\x07\x00\x01", b"\x01\x00\x15(Ljava/lang/String;)I", b"\x10\x00\x03"];
+        let class = deserializable_class(&mut &class_with_pool(&pool)[..]).unwrap();
+        assert!(matches!(&class.const_pool[3], ConstPoolType::MethodType(method_type) if method_type.0.as_str() == "(Ljava/lang/String;)I"));
+
+        let mut wrong_index = pool;
+        wrong_index[3] = b"\x10\x00\x02";
+        assert!(matches!(deserializable_class(&mut &class_with_pool(&wrong_index)[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Link)));
+    }
+
+    #[test]
+    fn invoke_dynamic_constants() {
+        let class = load_class("Lambda.class");
+        let call_sites: Vec<(u16, &str, &str)> = class.const_pool.iter().filter_map(|entry| match entry {
+            ConstPoolType::InvokeDynamic(dynamic) => Some((dynamic.bootstrap_method_attr_index,
+                                                           dynamic.name_and_type.name.as_str(),
+                                                           dynamic.name_and_type.descriptor.as_str())),
+            _ => None
+        }).collect();
+        assert_eq!(call_sites, vec![(0, "run", "([Ljava/lang/String;)Ljava/lang/Runnable;"),
+                                    (1, "get", "()Ljava/util/function/Supplier;"),
+                                    (2, "makeConcatWithConstants", "(I)Ljava/lang/String;")]);
+        assert!(matches!(&class.const_pool[58], ConstPoolType::MethodType(method_type) if method_type.0.as_str() == "()V"));
+        match &class.const_pool[59] {
+            ConstPoolType::MethodHandle(handle) => {
+                assert_eq!(handle.reference_kind, 6);
+                assert_eq!(handle.reference.name_and_type.name.as_str(), "lambda$main$0");
+            }
+            other => panic!("unexpected entry {other:?}")
+        }
+        let lambdas: Vec<&str> = class.methods.iter()
+            .filter(|method| method.is_lambda_body())
+            .map(|method| method.name.as_str())
+            .collect();
+        assert_eq!(lambdas, vec!["lambda$main$0"]);
+        assert!(!class.is_synthetic_lambda_class());
+
+        let pool: [&[u8]; 6] = [b"\x01\x00\x01A", b"\x07\x00\x01", b"\x01\x00\x01x", b"\x01\x00\x01I",
+            b"\x0C\x00\x03\x00\x04", b"\x11\x00\x03\x00\x05"];
+        let class = deserializable_class(&mut &class_with_pool(&pool)[..]).unwrap();
+        match &class.const_pool[5] {
+            ConstPoolType::Dynamic(dynamic) => {
+                assert_eq!(dynamic.bootstrap_method_attr_index, 3);
+                assert_eq!(dynamic.name_and_type.name.as_str(), "x");
+                assert_eq!(dynamic.name_and_type.descriptor.as_str(), "I");
+            }
+            other => panic!("unexpected entry {other:?}")
+        }
+        let mut wrong_index = pool;
+        wrong_index[5] = b"\x11\x00\x03\x00\x02";
+        assert!(matches!(deserializable_class(&mut &class_with_pool(&wrong_index)[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::RefNameAndTypeIndex(2))));
+    }
+
+    #[test]
+    fn module_info_constants() {
+        let class = load_class("module-info.class");
+        assert_eq!(class.access, ClassAccess::Module);
+        assert_eq!(class.this_class.0.as_str(), "module-info");
+        assert!(class.super_class.is_none());
+        let modules: Vec<&str> = class.const_pool.iter().filter_map(|entry| match entry {
+            ConstPoolType::Module(module) => Some(module.0.as_str()),
+            _ => None
+        }).collect();
+        assert_eq!(modules, vec!["org.example.hello", "java.base"]);
+        assert!(matches!(&class.const_pool[9], ConstPoolType::Package(package) if package.0.as_str() == "org/example/hello"));
+    }
+
+    #[test]
+    fn signature_attribute() {
+        fn signature(attributes: &[Attribute]) -> Option<&str> {
+            attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Signature(signature) => Some(signature.signature.as_str()),
+                _ => None
+            })
+        }
+
+        let class = load_class("Generics.class");
+        assert_eq!(signature(&class.attributes), Some("<E::Ljava/lang/Comparable<TE;>;>Ljava/lang/Object;"));
+        assert_eq!(signature(&class.fields[0].attributes), Some("Ljava/util/List<TE;>;"));
+        let foo = class.methods.iter().find(|method| method.name.as_str() == "foo").unwrap();
+        assert_eq!(foo.descriptor.as_str(), "(Ljava/lang/Object;)Ljava/util/List;");
+        assert_eq!(signature(&foo.attributes), Some("<T:Ljava/lang/Object;>(TT;)Ljava/util/List<TT;>;"));
+        let size = class.methods.iter().find(|method| method.name.as_str() == "size").unwrap();
+        assert_eq!(signature(&size.attributes), None);
+
+        let renamed = load_class("Node.class").clone_with_renamed_class("org/example/Link");
+        assert_eq!(signature(&renamed.fields[0].attributes), Some("Lorg/example/Link<TT;>;"));
+        assert_eq!(signature(&renamed.methods[1].attributes), Some("()Lorg/example/Link<TT;>;"));
+    }
+
+    #[test]
+    fn runtime_annotations() {
+        let class = load_class("Annotated.class");
+        assert!(class.attributes.iter().any(|attribute| matches!(attribute, Attribute::Deprecated(_))));
+        let visible = class.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::RuntimeVisibleAnnotations(visible) => Some(&visible.annotations),
+            _ => None
+        }).unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].type_descriptor.as_str(), "Ljava/lang/Deprecated;");
+        assert_eq!(visible[0].elements.len(), 1);
+        assert_eq!(visible[0].elements[0].0.as_str(), "since");
+        assert!(matches!(&visible[0].elements[0].1, ElementValue::Const(ConstValueType::String(since)) if since.as_str() == "1"));
+
+        let invisible = class.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::RuntimeInvisibleAnnotations(invisible) => Some(&invisible.annotations),
+            _ => None
+        }).unwrap();
+        assert_eq!(invisible[0].type_descriptor.as_str(), "LInfo;");
+        let elements: Vec<&str> = invisible[0].elements.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(elements, vec!["level", "ratio", "tags", "kind", "type", "nested", "flag", "letter"]);
+        let values: Vec<&ElementValue> = invisible[0].elements.iter().map(|(_, value)| value).collect();
+        assert!(matches!(values[0], ElementValue::Const(ConstValueType::Int(3))));
+        assert!(matches!(values[1], ElementValue::Const(ConstValueType::Double(ratio)) if *ratio == 1.5));
+        match values[2] {
+            ElementValue::Array(tags) => {
+                assert_eq!(tags.len(), 2);
+                assert!(matches!(&tags[1], ElementValue::Const(ConstValueType::String(tag)) if tag.as_str() == "b"));
+            }
+            other => panic!("unexpected value {other:?}")
+        }
+        assert!(matches!(values[3], ElementValue::Enum { type_name, const_name }
+            if type_name.as_str() == "Ljava/lang/annotation/ElementType;" && const_name.as_str() == "TYPE"));
+        assert!(matches!(values[4], ElementValue::Class(class) if class.as_str() == "Ljava/lang/String;"));
+        match values[5] {
+            ElementValue::Annotation(nested) => {
+                assert_eq!(nested.type_descriptor.as_str(), "Ljava/lang/annotation/Retention;");
+                assert!(matches!(&nested.elements[0].1, ElementValue::Enum { const_name, .. } if const_name.as_str() == "CLASS"));
+            }
+            other => panic!("unexpected value {other:?}")
+        }
+        assert!(matches!(values[6], ElementValue::Const(ConstValueType::Boolean(true))));
+        assert!(matches!(values[7], ElementValue::Const(ConstValueType::Char(120))));
+    }
+
+    #[test]
+    fn permitted_subclasses() {
+        let class = load_class("Shape.class");
+        assert_eq!(class.version.major, 61);
+        assert_eq!(class.access, ClassAccess::Public | ClassAccess::Interface | ClassAccess::Abstract);
+        assert_eq!(permitted_names(&class), vec!["Shape$Circle", "Shape$Square"]);
+    }
+
+    #[test]
+    fn record_components() {
+        let class = load_class("Point.class");
+        assert_eq!(class.super_class.as_ref().unwrap().0.as_str(), "java/lang/Record");
+        let record = class.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::Record(record) => Some(record),
+            _ => None
+        }).unwrap();
+        let components: Vec<(&str, &str)> = record.components.iter()
+            .map(|component| (component.name.as_str(), component.descriptor.as_str()))
+            .collect();
+        assert_eq!(components, vec![("x", "I"), ("y", "I")]);
+        assert!(record.components.iter().all(|component| component.attributes.is_empty()));
+    }
+
+    #[test]
+    fn annotation_default() {
+        let class = load_class("Foo.class");
+        assert!(class.access.contains(ClassAccess::Annotation));
+        let defaults: Vec<Option<&ElementValue>> = class.methods.iter().map(|method| method.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::AnnotationDefault(default) => Some(&default.value),
+            _ => None
+        })).collect();
+        assert_eq!(class.methods[0].name.as_str(), "bar");
+        assert!(matches!(defaults[0], Some(ElementValue::Const(ConstValueType::Int(7)))));
+        assert!(defaults[1].is_none());
+    }
+
+    fn reserialize(class: &Class) -> Class {
+        let mut data = Vec::new();
+        serialize_class(class, &mut data).unwrap();
+        deserializable_class(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        for name in ["HelloWorld.class", "Constants.class", "WideConstants.class", "ModifiedUtf8.class", "Generics.class",
+            "Annotated.class", "Foo.class"] {
+            let class = load_class(name);
+            assert_eq!(reserialize(&class), class, "{name}");
+        }
+
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"SourceFile", b"CustomFile");
+        let options = ParseOptions {
+            custom_attribute_handler: Some(|_, _, _| Some(Box::new(()))),
+            ..ParseOptions::default()
+        };
+        let class = deserializable_class_with_options(&mut &data[..], &options).unwrap();
+        let mut written = Vec::new();
+        serialize_class(&class, &mut written).unwrap();
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn serialize_modified_class() {
+        let class = load_class("HelloWorld.class");
+        let renamed = reserialize(&class.clone_with_renamed_class("org/example/Renamed"));
+        assert_eq!(renamed.this_class.0.as_str(), "org/example/Renamed");
+        assert_eq!(renamed.const_pool.len(), class.const_pool.len());
+        assert_eq!(method_code(&renamed, "main").code, method_code(&class, "main").code);
+
+        let mut extended = load_class("HelloWorld.class");
+        extended.interfaces.push(ClassInfo(Utf8Info::from("java/io/Serializable")));
+        extended.fields.push(ComponentInfo {
+            access: AccessSpecifier::Static | AccessSpecifier::Final,
+            name: Utf8Info::from("LIMIT"),
+            descriptor: Utf8Info::from("J"),
+            attributes: vec![Attribute::ConstantValue(ConstantValueAttribute { value: ConstValueType::Long(1 << 40) })]
+        });
+        let reparsed = reserialize(&extended);
+        assert_eq!(reparsed.const_pool.len(), class.const_pool.len() + 7);
+        assert_eq!(reparsed.interfaces[0].0.as_str(), "java/io/Serializable");
+        assert_eq!(reparsed.fields[0].name.as_str(), "LIMIT");
+        assert!(matches!(&reparsed.fields[0].attributes[0],
+            Attribute::ConstantValue(constant) if matches!(constant.value, ConstValueType::Long(value) if value == 1 << 40)));
+    }
+
+    #[test]
+    fn byte_identical_round_trip() {
+        for name in ["HelloWorld.class", "Constants.class", "WideConstants.class", "ModifiedUtf8.class", "Lambda.class",
+            "module-info.class", "Generics.class", "Annotated.class", "Shape.class", "Point.class", "Foo.class", "Bytecode.class", "MethodFlags.class",
+            "Color.class", "TryCatch.class", "StackFrames.class", "ParameterAnnotated.class",
+            "TypeAnnotated.class", "GenericLocals.class"] {
+            let data = read_resource(name);
+            assert_eq!(roundtrip_bytes(&data).unwrap(), data, "{name}");
+        }
+        assert!(matches!(roundtrip_bytes(b"\xCA\xFE"), Err(SerializationError::Input(_))));
+    }
+
+    #[test]
+    fn decode_bytecode() {
+        let class = load_class("Bytecode.class");
+        assert_eq!(decode(&method_code(&class, "arithmetic").code).unwrap(), vec![(0, Instruction::Iload0), (1, Instruction::Iload1),
+            (2, Instruction::Imul), (3, Instruction::Iconst3), (4, Instruction::Iadd), (5, Instruction::Ireturn)]);
+
+        let dense = decode(&method_code(&class, "dense").code).unwrap();
+        assert!(matches!(&dense[1], (1, Instruction::Tableswitch { default: 36, low: 1, high: 3, offsets }) if offsets == &vec![27, 30, 33]));
+        let sparse = decode(&method_code(&class, "sparse").code).unwrap();
+        assert!(matches!(&sparse[1], (1, Instruction::Lookupswitch { pairs, .. })
+            if pairs.iter().map(|pair| pair.0).collect::<Vec<i32>>() == vec![1, 1000, 100000]));
+        let looped = decode(&method_code(&class, "loop").code).unwrap();
+        assert!(looped.contains(&(9, Instruction::Iinc { index: 1, constant: 1000 })));
+        assert!(looped.contains(&(15, Instruction::Iinc { index: 2, constant: 1 })));
+
+        for method in &class.methods {
+            let code = &method_code(&class, &method.name).code;
+            let mut next = 0;
+            for item in instructions(code) {
+                let (pc, _, length) = item.unwrap();
+                assert_eq!(pc, next);
+                next = pc + length;
+            }
+            assert_eq!(next as usize, code.len());
+        }
+
+        assert!(matches!(decode(&[0xCA]), Err(DeserializationError::Parsing(_))));
+        assert!(matches!(decode(&[0xC4, 0x10, 0x00]), Err(DeserializationError::Parsing(_))));
+        assert!(matches!(decode(&[0x11, 0x00]), Err(DeserializationError::Parsing(_))));
+        assert!(matches!(decode(&[0xAA, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 1]), Err(DeserializationError::Parsing(_))));
+    }
+
+    #[test]
+    fn method_callees() {
+        let class = load_class("HelloWorld.class");
+        let main = class.methods.iter().find(|method| method.name.as_str() == "main").unwrap();
+        assert!(main.callees(&class).unwrap().contains(&Callee {
+            owner: "java.io.PrintStream".to_string(),
+            name: "println".to_string(),
+            descriptor: "(Ljava/lang/String;)V".to_string(),
+            kind: InvokeKind::Virtual
+        }));
+    }
+
+    #[test]
+    fn instruction_stack_effect() {
+        let class = load_class("HelloWorld.class");
+        assert_eq!(stack_effect(&Instruction::Iadd, &class).unwrap(), -1);
+        assert_eq!(stack_effect(&Instruction::Ldc2W(1), &class).unwrap(), 2);
+        let println = decode(&method_code(&class, "main").code).unwrap().into_iter()
+            .find_map(|(_, instruction)| match instruction {
+                Instruction::Invokevirtual(index) => Some(index),
+                _ => None
+            })
+            .unwrap();
+        assert_eq!(stack_effect(&Instruction::Invokevirtual(println), &class).unwrap(), -2);
+        assert!(matches!(stack_effect(&Instruction::Getstatic(0), &class), Err(DeserializationError::Link)));
+
+        let class = load_class("Bytecode.class");
+        let code = &method_code(&class, "mixed").code;
+        let depth: i32 = decode(code).unwrap().iter().map(|(_, instruction)| stack_effect(instruction, &class).unwrap()).sum();
+        assert_eq!(depth, 0);
+    }
+
+    #[test]
+    fn disassemble_hello_world() {
+        let expected = String::from_utf8(read_resource("HelloWorld.javap")).unwrap();
+        assert_eq!(disassemble(&load_class("HelloWorld.class")), expected);
+    }
+
+    #[test]
+    fn disassemble_verbose_method_flags() {
+        let expected = String::from_utf8(read_resource("MethodFlags.javap")).unwrap();
+        assert_eq!(disassemble_verbose(&load_class("MethodFlags.class")), expected);
+        let listing = disassemble_verbose(&load_class("Constants.class"));
+        assert!(listing.contains("   #12 = Integer 42\n"));
+        assert!(listing.contains("  public static final java.lang.String GREETING;\n"));
+    }
+
+    #[test]
+    fn field_descriptors() {
+        for (descriptor, expected) in [("B", FieldType::Byte), ("C", FieldType::Char), ("D", FieldType::Double),
+            ("F", FieldType::Float), ("I", FieldType::Int), ("J", FieldType::Long), ("S", FieldType::Short),
+            ("Z", FieldType::Boolean)] {
+            assert_eq!(parse_field_descriptor(descriptor).unwrap(), expected);
+        }
+        assert_eq!(parse_field_descriptor("Ljava/lang/String;").unwrap(), FieldType::Object("java/lang/String".to_string()));
+        assert_eq!(parse_field_descriptor("[[Ljava/lang/Object;").unwrap(),
+            FieldType::Array(Box::new(FieldType::Array(Box::new(FieldType::Object("java/lang/Object".to_string()))))));
+        assert_eq!(parse_field_descriptor("[I").unwrap(), FieldType::Array(Box::new(FieldType::Int)));
+
+        assert_eq!(parse_field_descriptor("II"), Err(DescriptorError::Trailing("II".to_string())));
+        assert_eq!(parse_field_descriptor("Ljava/lang/String;;"), Err(DescriptorError::Trailing("Ljava/lang/String;;".to_string())));
+        assert_eq!(parse_field_descriptor("[["), Err(DescriptorError::Incomplete("[[".to_string())));
+        assert_eq!(parse_field_descriptor("Ljava/lang/String"), Err(DescriptorError::Incomplete("Ljava/lang/String".to_string())));
+        assert_eq!(parse_field_descriptor("L;"), Err(DescriptorError::UnexpectedChar("L;".to_string(), ';', 1)));
+        assert_eq!(parse_field_descriptor("V"), Err(DescriptorError::UnexpectedChar("V".to_string(), 'V', 0)));
+    }
+
+    #[test]
+    fn method_access_flags() {
+        let mut class = load_class("MethodFlags.class");
+        let access = |class: &Class, name: &str| class.methods.iter().find(|method| method.name.as_str() == name).unwrap().access;
+        assert_eq!(access(&class, "run"), MethodAccessSpecifier::Abstract);
+        assert_eq!(access(&class, "lock"), MethodAccessSpecifier::Synchronized);
+        assert_eq!(access(&class, "call"), MethodAccessSpecifier::Native);
+        assert_eq!(access(&class, "sum"), MethodAccessSpecifier::Static | MethodAccessSpecifier::Varargs);
+        assert_eq!(access(&class, "divide"), MethodAccessSpecifier::Strict);
+
+        class.methods.iter_mut().find(|method| method.name.as_str() == "run").unwrap().access |= MethodAccessSpecifier::Synchronized;
+        let reparsed = reserialize(&class);
+        assert_eq!(access(&reparsed, "run"), MethodAccessSpecifier::Abstract | MethodAccessSpecifier::Synchronized);
+    }
+
+    #[test]
+    fn enum_and_annotation_access_flags() {
+        let class = load_class("Color.class");
+        assert_eq!(class.access, ClassAccess::Public | ClassAccess::Final | ClassAccess::Super | ClassAccess::Enum);
+        let red = class.fields.iter().find(|field| field.name.as_str() == "RED").unwrap();
+        assert_eq!(red.access, AccessSpecifier::Public | AccessSpecifier::Static | AccessSpecifier::Final | AccessSpecifier::Enum);
+        let values = class.fields.iter().find(|field| field.name.as_str() == "$VALUES").unwrap();
+        assert!(values.access.contains(AccessSpecifier::Synthetic));
+
+        let annotation = load_class("Foo.class");
+        assert_eq!(annotation.access,
+            ClassAccess::Public | ClassAccess::Interface | ClassAccess::Abstract | ClassAccess::Annotation);
+    }
+
+    #[test]
+    fn java_versions() {
+        for (major, expected) in [(44, None), (45, Some(1)), (46, Some(2)), (50, Some(6)), (52, Some(8)), (55, Some(11)),
+            (61, Some(17)), (65, Some(21)), (69, Some(25))] {
+            assert_eq!(ClassVersion { minor: 0, major }.java_version(), expected, "{major}");
+        }
+        let class = load_class("HelloWorld.class");
+        assert_eq!(class.version.java_version(), Some(11));
+        assert!(!class.version.is_preview());
+        assert!(ClassVersion { minor: 0xFFFF, major: 65 }.is_preview());
+    }
+
+    #[test]
+    fn parse_from_bytes() {
+        let bytes: &[u8] = include_bytes!("../../resources/HelloWorld.class");
+        let class = from_bytes(bytes).unwrap();
+        assert_eq!(class.this_class.0.as_str(), "HelloWorld");
+        assert_eq!(class.methods.len(), 2);
+        assert_eq!(Class::parse(bytes).unwrap().const_pool.len(), class.const_pool.len());
+        assert!(matches!(Class::parse(&bytes[..bytes.len() - 1]).map_err(DeserializationError::into_inner), Err(DeserializationError::CannotRead(_))));
+    }
+
+    #[test]
+    fn error_byte_offsets() {
+        for position in [10, 15] {
+            let mut data = read_resource("HelloWorld.class");
+            data[position] = 0x02;
+            let error = deserializable_class(&mut &data[..]).unwrap_err();
+            assert_eq!(error.offset(), Some(position as u64));
+            assert!(error.to_string().starts_with(&format!("At byte offset {position}:")));
+            assert!(matches!(error.into_inner(), DeserializationError::Parsing(_)));
+        }
+        let data = read_resource("HelloWorld.class");
+        let error = from_bytes(&data[..20]).unwrap_err();
+        assert_eq!(error.offset(), Some(20));
+        assert!(matches!(error.into_inner(), DeserializationError::CannotRead(_)));
+    }
+
+    #[test]
+    fn serde_json_snapshot() {
+        let class = load_class("HelloWorld.class");
+        let json = serde_json::to_string(&class).unwrap();
+        assert!(json.contains(r#""this_class":"HelloWorld""#));
+        let restored: Class = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(restored, class);
+    }
+
+    #[test]
+    fn typed_const_pool_accessors() {
+        let class = load_class("HelloWorld.class");
+        assert_eq!(class.utf8_at(7), Some("<init>"));
+        assert_eq!(class.class_name_at(5), Some("HelloWorld"));
+        assert_eq!(class.class_name_at(6), Some("java/lang/Object"));
+        let name_and_type = class.name_and_type_at(20).unwrap();
+        assert_eq!((name_and_type.name.as_str(), name_and_type.descriptor.as_str()), ("println", "(Ljava/lang/String;)V"));
+        assert!(matches!(class.const_pool_entry(1), Some(ConstPoolType::MethodRef(_))));
+
+        assert_eq!(class.utf8_at(0), None);
+        assert_eq!(class.utf8_at(29), None);
+        assert_eq!(class.utf8_at(5), None);
+        assert_eq!(class.class_name_at(7), None);
+        assert!(class.name_and_type_at(1).is_none());
+
+        let class = load_class("WideConstants.class");
+        let wide = class.const_pool.iter().position(|entry| matches!(entry, ConstPoolType::Long(_) | ConstPoolType::Double(_))).unwrap();
+        assert!(class.const_pool_entry(wide as u16 + 1).is_some());
+        assert!(class.const_pool_entry(wide as u16 + 2).is_none());
+    }
+
+    #[test]
+    fn member_lookups() {
+        let class = load_class("HelloWorld.class");
+        let main = class.find_method("main", "([Ljava/lang/String;)V").unwrap();
+        assert_eq!(main.code_size(), Some(9));
+        assert!(class.find_method("main", "()V").is_none());
+        assert!(class.find_method("missing", "([Ljava/lang/String;)V").is_none());
+
+        let class = load_class("Bytecode.class");
+        let descriptors: Vec<&str> = class.methods_named("arithmetic").map(|method| method.descriptor.as_str()).collect();
+        assert_eq!(descriptors, vec!["(II)I", "(JJ)J"]);
+        assert_eq!(class.methods_named("missing").count(), 0);
+
+        let class = load_class("Constants.class");
+        assert_eq!(class.find_field("GREETING").unwrap().descriptor.as_str(), "Ljava/lang/String;");
+        assert!(class.find_field("main").is_none());
+    }
+
+    #[test]
+    fn source_file_and_deprecation() {
+        let class = load_class("HelloWorld.class");
+        assert_eq!(class.source_file(), Some("HelloWorld.java"));
+        assert!(!class.is_deprecated());
+        assert!(!class.find_method("main", "([Ljava/lang/String;)V").unwrap().is_deprecated());
+
+        let class = load_class("Annotated.class");
+        assert!(class.is_deprecated());
+        assert!(class.find_method("old", "()V").unwrap().is_deprecated());
+        assert!(!class.find_method("<init>", "()V").unwrap().is_deprecated());
+        assert_eq!(class.clone_with_renamed_class("Other").source_file(), Some("Annotated.java"));
+    }
+
+    #[test]
+    fn parsed_model_equality() {
+        for name in ["HelloWorld.class", "Constants.class", "WideConstants.class", "Annotated.class", "Point.class"] {
+            assert_eq!(load_class(name), load_class(name), "{name}");
+        }
+        let class = load_class("HelloWorld.class");
+        assert_ne!(class.clone_with_renamed_class("Other"), class);
+        assert_eq!(class.const_pool[4], ConstPoolType::Class(ClassInfo(Utf8Info::from("HelloWorld"))));
+        assert_ne!(ConstPoolType::Double(f64::NAN), ConstPoolType::Double(f64::NAN));
+    }
+
+    #[test]
+    fn exception_handler_lookup() {
+        let class = load_class("TryCatch.class");
+        let code = method_code(&class, "parse");
+        let handler = code.handler_for(3).unwrap();
+        assert_eq!(handler.handler_pc, 13);
+        assert_eq!(handler.catch_type.as_ref().unwrap().0.as_str(), "java/lang/NumberFormatException");
+        let finally = code.handler_for(14).unwrap();
+        assert_eq!(finally.handler_pc, 22);
+        assert!(finally.catch_type.is_none());
+        assert!(code.handler_for(0).is_none());
+        assert!(code.handler_for(7).is_none());
+        assert!(code.handler_for(28).is_none());
+    }
+
+    #[test]
+    fn line_and_variable_lookup() {
+        let class = load_class("TryCatch.class");
+        let code = method_code(&class, "parse");
+        let lines = code.line_number_table().unwrap();
+        assert_eq!(lines.line_for(0), Some(3));
+        assert_eq!(lines.line_for(3), Some(5));
+        assert_eq!(lines.line_for(14), Some(7));
+        assert_eq!(lines.line_for(29), Some(11));
+
+        let variables = code.local_variable_table().unwrap();
+        assert_eq!(variables.variable_at(14, 2).unwrap().name.as_str(), "e");
+        assert_eq!(variables.variable_at(15, 2).unwrap().descriptor.as_str(), "Ljava/lang/NumberFormatException;");
+        assert!(variables.variable_at(13, 2).is_none());
+        assert!(variables.variable_at(16, 2).is_none());
+        assert_eq!(variables.variable_at(29, 1).unwrap().name.as_str(), "result");
+        assert!(variables.variable_at(1, 1).is_none());
+        assert_eq!(variables.variable_at(0, 0).unwrap().name.as_str(), "value");
+    }
+
+    #[test]
+    fn method_descriptors() {
+        assert_eq!(parse_method_descriptor("()V").unwrap(), MethodDescriptor { parameters: vec![], return_type: None });
+        assert_eq!(parse_method_descriptor("(IJ[Ljava/lang/String;)D").unwrap(), MethodDescriptor {
+            parameters: vec![FieldType::Int, FieldType::Long, FieldType::Array(Box::new(FieldType::Object("java/lang/String".to_string())))],
+            return_type: Some(FieldType::Double)
+        });
+        assert_eq!(parse_method_descriptor("I"), Err(DescriptorError::UnexpectedChar("I".to_string(), 'I', 0)));
+        assert_eq!(parse_method_descriptor("(I"), Err(DescriptorError::Incomplete("(I".to_string())));
+        assert_eq!(parse_method_descriptor("(V)V"), Err(DescriptorError::UnexpectedChar("(V)V".to_string(), 'V', 1)));
+        assert_eq!(parse_method_descriptor("()VV"), Err(DescriptorError::Trailing("()VV".to_string())));
+        assert_eq!(parse_method_descriptor("()"), Err(DescriptorError::Incomplete("()".to_string())));
+    }
+
+    #[test]
+    fn validate_references() {
+        for name in ["HelloWorld.class", "Bytecode.class", "TryCatch.class", "Lambda.class", "MethodFlags.class"] {
+            assert_eq!(validate(&load_class(name)), Ok(()), "{name}");
+        }
+
+        // Methodref #1 descriptor ()V -> (XV, main max_locals 1 -> 0
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"\x01\x00\x03()V", b"\x01\x00\x03(XV");
+        replace_bytes(&mut data, b"\x00\x02\x00\x01\x00\x00\x00\x09", b"\x00\x02\x00\x00\x00\x00\x00\x09");
+        let errors = validate(&deserializable_class(&mut &data[..]).unwrap()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], ValidationError::InvalidDescriptor { index: 1, error: DescriptorError::UnexpectedChar(_, 'X', 1) }));
+        assert!(matches!(&errors[1], ValidationError::MaxLocalsTooSmall { method, max_local: 0, required: 1 }
+            if method == "main([Ljava/lang/String;)V"));
+
+        let mut class = load_class("TryCatch.class");
+        class.this_class = ClassInfo(Utf8Info::from("Missing"));
+        class.methods.iter_mut().find(|method| method.name.as_str() == "parse").unwrap()
+            .attributes.iter_mut().for_each(|attribute| if let Attribute::Code(code) = attribute {
+                code.exceptions[1].handler_pc = 100;
+            });
+        assert_eq!(validate(&class), Err(vec![ValidationError::MissingClassEntry("Missing".to_string()),
+            ValidationError::HandlerOutOfRange { method: "parse(Ljava/lang/String;)I".to_string(), entry: 1 }]));
+    }
+
+    #[test]
+    fn recompute_max_stack_and_locals() {
+        let class = load_class("HelloWorld.class");
+        let main = method_code(&class, "main");
+        let descriptor = parse_method_descriptor("([Ljava/lang/String;)V").unwrap();
+        assert_eq!(main.compute_max_stack(&class).unwrap(), 2);
+        assert_eq!(main.compute_max_locals(&descriptor, true).unwrap(), 1);
+
+        for name in ["HelloWorld.class", "Bytecode.class", "TryCatch.class", "Lambda.class", "Generics.class", "Point.class"] {
+            let class = load_class(name);
+            for method in &class.methods {
+                if let Some(code) = method.code() {
+                    let descriptor = parse_method_descriptor(&method.descriptor).unwrap();
+                    let is_static = method.access.contains(MethodAccessSpecifier::Static);
+                    assert_eq!(code.compute_max_stack(&class).unwrap(), code.max_stack, "{name} {}", method.name);
+                    assert_eq!(code.compute_max_locals(&descriptor, is_static).unwrap(), code.max_local, "{name} {}", method.name);
+                }
+            }
+        }
+
+        let underflow = CodeAttribute { max_stack: 0, max_local: 0, code: vec![0x57, 0xB1], exceptions: vec![], attributes: vec![] };
+        assert!(matches!(underflow.compute_max_stack(&class), Err(DeserializationError::Parsing(_))));
+        let falls_off = CodeAttribute { code: vec![0x00], ..underflow };
+        assert!(matches!(falls_off.compute_max_stack(&class), Err(DeserializationError::Parsing(_))));
+    }
+
+    #[test]
+    fn assemble_decoded_bytecode() {
+        for name in ["HelloWorld.class", "Bytecode.class", "TryCatch.class", "Lambda.class", "Generics.class", "Point.class"] {
+            let class = load_class(name);
+            for method in &class.methods {
+                if let Some(code) = method.code() {
+                    let instructions = decode(&code.code).unwrap().into_iter().map(|(_, instruction)| instruction).collect::<Vec<_>>();
+                    assert_eq!(assemble(&instructions).unwrap(), code.code, "{name} {}", method.name);
+                }
+            }
+        }
+        assert_eq!(assemble(&[Instruction::Iload(300), Instruction::Iinc { index: 1, constant: 200 }]).unwrap(),
+            vec![0xC4, 0x15, 0x01, 0x2C, 0xC4, 0x84, 0x00, 0x01, 0x00, 0xC8]);
+        let invalid = Instruction::Tableswitch { default: 0, low: 0, high: 2, offsets: vec![0] };
+        assert!(matches!(assemble(&[Instruction::Nop, invalid]), Err(SerializationError::Unsupported(_))));
+    }
+
+    #[test]
+    fn build_const_pool() {
+        let mut pool = ConstPoolBuilder::new();
+        assert_eq!(pool.method_ref("java/lang/Object", "<init>", "()V").unwrap(), 6);
+        assert_eq!(pool.class("java/lang/Object").unwrap(), 2);
+        assert_eq!(pool.long(1).unwrap(), 7);
+        assert_eq!(pool.utf8("next").unwrap(), 9);
+        assert_eq!(pool.name_and_type("<init>", "()V").unwrap(), 5);
+        let pool = pool.build();
+        assert_eq!(pool.len(), 9);
+        assert_eq!(pool[7], ConstPoolType::Placeholder);
+    }
+
+    #[test]
+    fn build_empty_class() {
+        let class = ClassBuilder::new("Empty").build().unwrap();
+        let parsed = reserialize(&class);
+        assert_eq!(parsed, class);
+        assert_eq!(parsed.this_class.0.as_str(), "Empty");
+        assert_eq!(parsed.super_class.unwrap().0.as_str(), "java/lang/Object");
+        assert_eq!(parsed.access, ClassAccess::Public | ClassAccess::Super);
+        assert_eq!(parsed.version.major, 55);
+        assert!(parsed.methods.is_empty());
+    }
+
+    #[test]
+    fn build_class_with_methods() {
+        let method = MethodBuilder::new("foo", "()V").instr(Instruction::Return).build().unwrap();
+        assert_eq!(method.code().unwrap().code, vec![0xB1]);
+        assert_eq!(method.code().unwrap().max_local, 1);
+
+        let mut builder = ClassBuilder::new("Hello");
+        let out = builder.pool().field_ref("java/lang/System", "out", "Ljava/io/PrintStream;").unwrap();
+        let greeting = builder.pool().string("Hello").unwrap();
+        let println = builder.pool().method_ref("java/io/PrintStream", "println", "(Ljava/lang/String;)V").unwrap();
+        let main = MethodBuilder::new("main", "([Ljava/lang/String;)V")
+            .access(MethodAccessSpecifier::Public | MethodAccessSpecifier::Static)
+            .instr(Instruction::Getstatic(out))
+            .instr(Instruction::Ldc(greeting as u8))
+            .instr(Instruction::Invokevirtual(println))
+            .instr(Instruction::Return);
+        let class = builder.method(method).method_builder(main).build().unwrap();
+        let parsed = reserialize(&class);
+        assert_eq!(parsed, class);
+        assert_eq!(parsed.methods.len(), 2);
+        let main = method_code(&parsed, "main");
+        assert_eq!((main.max_stack, main.max_local), (2, 1));
+        assert_eq!(main.code, vec![0xB2, 0x00, out as u8, 0x12, greeting as u8, 0xB6, 0x00, println as u8, 0xB1]);
+    }
+
+    #[test]
+    fn display_const_pool_entries() {
+        let class = load_class("HelloWorld.class");
+        let rendered = class.const_pool.iter().map(ToString::to_string).collect::<Vec<String>>();
+        assert!(rendered.contains(&"Class java/lang/Object".to_string()));
+        assert!(rendered.contains(&"Utf8 \"main\"".to_string()));
+        assert!(rendered.contains(&"Methodref java/io/PrintStream.println:(Ljava/lang/String;)V".to_string()));
+        assert!(rendered.contains(&"Fieldref java/lang/System.out:Ljava/io/PrintStream;".to_string()));
+        assert!(rendered.contains(&"NameAndType <init>:()V".to_string()));
+        assert_eq!(ConstPoolType::Long(7).to_string(), "Long 7l");
+        assert_eq!(ConstPoolType::Placeholder.to_string(), "<unusable>");
+    }
+
+    #[test]
+    fn render_java_types() {
+        assert_eq!(to_java_type(&FieldType::Int), "int");
+        assert_eq!(to_java_type(&parse_field_descriptor("[I").unwrap()), "int[]");
+        assert_eq!(to_java_type(&parse_field_descriptor("Ljava/lang/String;").unwrap()), "java.lang.String");
+        assert_eq!(to_java_type(&parse_field_descriptor("[[Ljava/util/Map$Entry;").unwrap()), "java.util.Map$Entry[][]");
+    }
+
+    #[test]
+    fn parse_from_path() {
+        let class = parse_file(PathBuf::from(env!("TEST_RESOURCES_PATH")).join("HelloWorld.class")).unwrap();
+        assert_eq!(class, load_class("HelloWorld.class"));
+        assert!(matches!(parse_file("missing/Nothing.class"), Err(DeserializationError::CannotRead(_))));
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn parse_jar_entries() {
+        use class_parser::deserialization::{parse_jar, parse_jar_with_progress};
+        let path = PathBuf::from(env!("TEST_RESOURCES_PATH")).join("Tiny.jar");
+        let classes = parse_jar(&path).unwrap();
+        assert_eq!(classes.len(), 2);
+        assert_eq!(classes[0], ("HelloWorld.class".to_string(), load_class("HelloWorld.class")));
+        assert_eq!(classes[1], ("Point.class".to_string(), load_class("Point.class")));
+
+        let mut reported = vec![];
+        parse_jar_with_progress(&path, |index, total, name| reported.push((index, total, name.to_string()))).unwrap();
+        assert_eq!(reported, vec![(0, 2, "HelloWorld.class".to_string()), (1, 2, "Point.class".to_string())]);
+    }
+
+    #[test]
+    fn resolve_class_hierarchy() {
+        let interface = ClassBuilder::new("I").access(ClassAccess::Public | ClassAccess::Interface | ClassAccess::Abstract);
+        let classes = [interface, ClassBuilder::new("B"), ClassBuilder::new("C").super_class(Some("B")).interface("I")]
+            .into_iter()
+            .map(|builder| builder.build().unwrap())
+            .map(|class| (class.this_class.0.to_string(), class))
+            .collect::<HashMap<String, Class>>();
+        let hierarchy = Hierarchy::new(&classes);
+        assert_eq!(hierarchy.supertypes_of("C"), vec!["B", "I", "java/lang/Object"]);
+        assert_eq!(hierarchy.supertypes_of("Unknown"), Vec::<String>::new());
+        assert!(hierarchy.is_assignable("C", "B"));
+        assert!(hierarchy.is_assignable("C", "I"));
+        assert!(hierarchy.is_assignable("C", "C"));
+        assert!(hierarchy.is_assignable("I", "java/lang/Object"));
+        assert!(hierarchy.is_assignable("Unknown", "java/lang/Object"));
+        assert!(!hierarchy.is_assignable("B", "C"));
+        assert!(!hierarchy.is_assignable("B", "I"));
+        assert!(!hierarchy.is_assignable("Unknown", "B"));
+    }
+
+    #[test]
+    fn lenient_parsing_recovers_attributes() {
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, &[0x00, 0x00, 0x00, 0x09, 0xB2], &[0x00, 0x00, 0xFF, 0xFF, 0xB2]);
+        assert!(deserializable_class(&data[..]).is_err());
+
+        let (class, errors) = deserializable_class_lenient(&data[..]);
+        let class = class.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], DeserializationError::CannotRead(_)));
+        let main = class.methods.iter().find(|method| method.name.as_str() == "main").unwrap();
+        assert!(main.code().is_none());
+        assert!(matches!(&main.attributes[0], Attribute::Unknown(unknown) if unknown.name.as_str() == "Code"));
+        assert_eq!(class.methods[0].code(), load_class("HelloWorld.class").methods[0].code());
+
+        let (class, errors) = deserializable_class_lenient(&data[..20]);
+        assert!(class.is_none());
+        assert!(matches!(errors[..], [DeserializationError::AtOffset { .. }]));
+    }
+
+    #[test]
+    fn parse_limits() {
+        let mut data = read_resource("HelloWorld.class");
+        let class = load_class("HelloWorld.class");
+        let name = class.const_pool.iter()
+            .position(|entry| matches!(entry, ConstPoolType::Utf8(value) if value.as_str() == "main"))
+            .unwrap() as u16 + 1;
+        // The class ends with its SourceFile attribute, which becomes an unknown attribute claiming almost 4GB.
+        let length = data.len();
+        data.splice(length - 8..length - 2, name.to_be_bytes().into_iter().chain(0xFFFF_FFF0u32.to_be_bytes()));
+        let error = deserializable_class(&data[..]).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::CannotRead(_)));
+
+        let limits = ParseLimits { max_attribute_len: 1 << 20, ..ParseLimits::default() };
+        let error = deserializable_class_with_limits(&data[..], limits).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::LimitExceeded { what: "attribute length", declared: 0xFFFF_FFF0, limit: 0x10_0000 }));
+
+        let data = read_resource("HelloWorld.class");
+        let limits = ParseLimits { max_code_len: 8, ..ParseLimits::default() };
+        let error = deserializable_class_with_limits(&data[..], limits).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::LimitExceeded { what: "code length", declared: 9, limit: 8 }));
+        let limits = ParseLimits { max_pool_entries: 10, ..ParseLimits::default() };
+        let error = deserializable_class_with_limits(&data[..], limits).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::LimitExceeded { what: "constant pool entry count", .. }));
+        assert_eq!(deserializable_class_with_limits(&data[..], ParseLimits::default()).unwrap(), class);
+    }
+
+    #[test]
+    fn non_canonical_modified_utf8() {
+        // An overlong encoding of the 'a' in "main" decodes as usual and is written back as it was read.
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"\x00\x04main", b"\x00\x05m\xC1\xA1in");
+        let class = deserializable_class(&mut &data[..]).unwrap();
+        let main = class.methods.iter().find(|method| method.name.as_str() == "main").unwrap();
+        assert_eq!(main.name.raw(), Some(&b"m\xC1\xA1in"[..]));
+        assert_eq!(roundtrip_bytes(&data).unwrap(), data);
+
+        assert!(load_class("HelloWorld.class").const_pool.iter().all(|entry| !matches!(entry, ConstPoolType::Utf8(value) if value.raw().is_some())));
+        assert_ne!(main.name, Utf8Info::from("main"));
+        assert_eq!(Utf8Info::from(main.name.as_str()).raw(), None);
+    }
+
+    #[test]
+    fn validate_raw_const_pool() {
+        for name in ["HelloWorld.class", "Lambda.class", "WideConstants.class", "module-info.class"] {
+            assert_eq!(validate_const_pool(&read_resource(name)), Ok(()), "{name}");
+        }
+
+        let pool: [&[u8]; 6] = [b"\x01\x00\x01A", b"\x07\x00\x01", b"\x07\x00\x02", b"\x0C\x00\x02\x00\x01",
+            b"\x0A\x00\x01\x00\x04", b"\x0F\x09\x00\x05"];
+        let data = class_with_pool(&pool);
+        assert!(matches!(deserializable_class(&data[..]).map_err(DeserializationError::into_inner), Err(DeserializationError::Link)));
+        assert_eq!(validate_const_pool(&data), Err(vec![
+            ValidationError::WrongReference { index: 3, target: 2, expected: "Utf8" },
+            ValidationError::WrongReference { index: 4, target: 2, expected: "Utf8" },
+            ValidationError::WrongReference { index: 5, target: 1, expected: "Class" },
+            ValidationError::WrongReference { index: 6, target: 5, expected: "InterfaceMethodref" }]));
+
+        let pool: [&[u8]; 6] = [b"\x01\x00\x01A", b"\x07\x00\x01", b"\x01\x00\x01f", b"\x01\x00\x01X",
+            b"\x0C\x00\x03\x00\x04", b"\x09\x00\x02\x00\x05"];
+        assert!(matches!(&validate_const_pool(&class_with_pool(&pool)).unwrap_err()[..],
+            [ValidationError::InvalidDescriptor { index: 6, error: DescriptorError::UnexpectedChar(_, 'X', 0) }]));
+        assert!(matches!(&validate_const_pool(b"\xCA\xFE").unwrap_err()[..], [ValidationError::Unreadable(_)]));
+    }
+
+    #[test]
+    fn stack_map_frames() {
+        let class = load_class("Bytecode.class");
+        let frames = method_code(&class, "loop").stack_map_table().unwrap();
+        assert_eq!(frames.frames, vec![
+            StackMapFrame::Append { offset_delta: 4, locals: vec![VerificationType::Integer, VerificationType::Integer] },
+            StackMapFrame::Chop { offset_delta: 16, count: 1 }]);
+        assert_eq!(frames.offsets(), vec![4, 21]);
+        assert!(method_code(&class, "arithmetic").stack_map_table().is_none());
+
+        let class = load_class("StackFrames.class");
+        let string = |name: &str| VerificationType::Object(ClassInfo(Utf8Info::from(name)));
+        assert_eq!(method_code(&class, "uninitialized").stack_map_table().unwrap().frames, vec![
+            StackMapFrame::Full { offset_delta: 13, locals: vec![VerificationType::Integer],
+                stack: vec![VerificationType::Uninitialized(0), VerificationType::Uninitialized(0)] },
+            StackMapFrame::Full { offset_delta: 1, locals: vec![VerificationType::Integer],
+                stack: vec![VerificationType::Uninitialized(0), VerificationType::Uninitialized(0), string("java/lang/String")] }]);
+        assert_eq!(method_code(&class, "full").stack_map_table().unwrap().frames, vec![StackMapFrame::Same { offset_delta: 25 }]);
+        assert_eq!(method_code(&class, "caught").stack_map_table().unwrap().frames,
+            vec![StackMapFrame::SameLocals1StackItem { offset_delta: 5, stack: string("java/lang/RuntimeException") }]);
+
+        // A delta that does not fit the short forms is written with the extended tags and read back as such.
+        let class = with_loop_frames(vec![StackMapFrame::Same { offset_delta: 300 },
+            StackMapFrame::SameLocals1StackItem { offset_delta: 70, stack: VerificationType::Null }]);
+        let mut data = Vec::new();
+        serialize_class(&class, &mut data).unwrap();
+        assert!(data.windows(7).any(|window| window == [0x00, 0x02, 0xFB, 0x01, 0x2C, 0xF7, 0x00]));
+        assert_eq!(reserialize(&class), with_loop_frames(vec![StackMapFrame::SameExtended { offset_delta: 300 },
+            StackMapFrame::SameLocals1StackItemExtended { offset_delta: 70, stack: VerificationType::Null }]));
+    }
+
+    fn with_loop_frames(frames: Vec<StackMapFrame>) -> Class {
+        let mut class = load_class("Bytecode.class");
+        class.methods.iter_mut().find(|method| method.name.as_str() == "loop").unwrap()
+            .attributes.iter_mut().for_each(|attribute| if let Attribute::Code(code) = attribute {
+                code.attributes = vec![CodeAttributes::StackMapTable(class::attributes::StackMapTableAttribute { frames: frames.clone() })];
+            });
+        class
+    }
+
+    #[test]
+    fn extended_stack_map_frames() {
+        let class = with_loop_frames(vec![StackMapFrame::SameExtended { offset_delta: 4 },
+            StackMapFrame::SameLocals1StackItemExtended { offset_delta: 15, stack: VerificationType::Integer }]);
+        let mut data = Vec::new();
+        serialize_class(&class, &mut data).unwrap();
+        assert!(data.windows(9).any(|window| window == [0x00, 0x02, 0xFB, 0x00, 0x04, 0xF7, 0x00, 0x0F, 0x01]));
+        assert_eq!(reserialize(&class), class);
+        assert_eq!(roundtrip_bytes(&data).unwrap(), data);
+    }
+
+    #[test]
+    fn bootstrap_methods() {
+        let class = load_class("Lambda.class");
+        let bootstrap = class.bootstrap_method(0).unwrap();
+        assert_eq!(bootstrap.method.reference_kind, 6);
+        assert_eq!(bootstrap.method.reference.class.0.as_str(), "java/lang/invoke/LambdaMetafactory");
+        assert_eq!(bootstrap.method.reference.name_and_type.name.as_str(), "metafactory");
+        assert_eq!(bootstrap.arguments.len(), 3);
+        assert!(matches!(&bootstrap.arguments[0], ConstPoolType::MethodType(method_type) if method_type.0.as_str() == "()V"));
+        assert!(matches!(&bootstrap.arguments[1], ConstPoolType::MethodHandle(handle)
+            if handle.reference.name_and_type.name.as_str() == "lambda$main$0"));
+
+        let concat = class.bootstrap_method(2).unwrap();
+        assert_eq!(concat.method.reference.name_and_type.name.as_str(), "makeConcatWithConstants");
+        assert!(matches!(&concat.arguments[..], [ConstPoolType::String(recipe)] if recipe.as_str() == "Hello \u{1}"));
+        assert!(class.bootstrap_method(3).is_none());
+        assert!(load_class("HelloWorld.class").bootstrap_method(0).is_none());
+    }
+
+    #[test]
+    fn generic_signatures() {
+        let class = load_class("Generics.class");
+        let class_signature = parse_class_signature(class.signature().unwrap()).unwrap();
+        assert_eq!(class_signature.type_parameters.len(), 1);
+        let parameter = &class_signature.type_parameters[0];
+        assert_eq!(parameter.name, "E");
+        assert_eq!(parameter.class_bound, None);
+        match &parameter.interface_bounds[..] {
+            [ReferenceType::Class(bound)] => {
+                assert_eq!(bound.internal_name(), "java/lang/Comparable");
+                assert_eq!(bound.segments[0].arguments, vec![TypeArgument::Exact(ReferenceType::TypeVariable("E".to_string()))]);
+            }
+            other => panic!("unexpected bounds {other:?}")
+        }
+        assert_eq!(class_signature.super_class.internal_name(), "java/lang/Object");
+        assert!(class_signature.interfaces.is_empty());
+
+        let field = parse_field_signature(class.fields[0].signature().unwrap()).unwrap();
+        assert!(matches!(field, ReferenceType::Class(list) if list.internal_name() == "java/util/List"));
+        let foo = class.methods.iter().find(|method| method.name.as_str() == "foo").unwrap();
+        let method = parse_method_signature(foo.signature().unwrap()).unwrap();
+        assert_eq!(method.type_parameters[0].name, "T");
+        assert_eq!(method.parameters, vec![JavaType::Reference(ReferenceType::TypeVariable("T".to_string()))]);
+        assert!(method.throws.is_empty());
+
+        let inner = parse_field_signature("La/Outer<TT;>.Inner<-Ljava/lang/Number;*+[I>;").unwrap();
+        match inner {
+            ReferenceType::Class(inner) => {
+                assert_eq!(inner.internal_name(), "a/Outer$Inner");
+                assert_eq!(inner.segments[1].arguments.len(), 3);
+                assert!(matches!(&inner.segments[1].arguments[1], TypeArgument::Wildcard));
+                assert!(matches!(&inner.segments[1].arguments[2],
+                    TypeArgument::Extends(ReferenceType::Array(component)) if **component == JavaType::Base(FieldType::Int)));
+            }
+            other => panic!("unexpected type {other:?}")
+        }
+        let throwing = parse_method_signature("<X:Ljava/lang/Exception;>(J)V^TX;^Ljava/io/IOException;").unwrap();
+        assert_eq!(throwing.parameters, vec![JavaType::Base(FieldType::Long)]);
+        assert_eq!(throwing.return_type, None);
+        assert_eq!(throwing.throws.len(), 2);
+
+        assert_eq!(parse_class_signature("<>Ljava/lang/Object;"),
+                   Err(DescriptorError::UnexpectedChar("<>Ljava/lang/Object;".to_string(), '>', 1)));
+        assert_eq!(parse_field_signature("Ljava/util/List<TE;>;x"), Err(DescriptorError::Trailing("Ljava/util/List<TE;>;x".to_string())));
+        assert_eq!(parse_field_signature("Ljava/util/List<TE;"), Err(DescriptorError::Incomplete("Ljava/util/List<TE;".to_string())));
+        assert!(parse_method_signature("()V^[I").is_err());
+    }
+
+    #[test]
+    fn parameter_annotations() {
+        let class = load_class("ParameterAnnotated.class");
+        let handle = class.methods.iter().find(|method| method.name.as_str() == "handle").unwrap();
+        let names: Vec<Vec<&str>> = handle.parameter_annotations().iter()
+            .map(|annotations| annotations.iter().map(|annotation| annotation.type_descriptor.as_str()).collect())
+            .collect();
+        assert_eq!(names, vec![vec!["Ljava/lang/Deprecated;"], vec![],
+                               vec!["Ljava/lang/Deprecated;", "LParameterAnnotated$Marker;"]]);
+        assert!(handle.attributes.iter().any(|attribute| matches!(attribute, Attribute::RuntimeInvisibleParameterAnnotations(invisible)
+            if invisible.parameters.len() == 3)));
+        let plain = class.methods.iter().find(|method| method.name.as_str() == "plain").unwrap();
+        assert!(plain.parameter_annotations().is_empty());
+    }
+
+    #[test]
+    fn type_annotations() {
+        fn targets(annotations: &[TypeAnnotation]) -> Vec<(u8, &TargetInfo, &str)> {
+            annotations.iter()
+                .map(|annotation| (annotation.target_type, &annotation.target_info, annotation.annotation.type_descriptor.as_str()))
+                .collect()
+        }
+
+        let class = load_class("TypeAnnotated.class");
+        for attribute in &class.attributes {
+            match attribute {
+                Attribute::RuntimeVisibleTypeAnnotations(visible) =>
+                    assert_eq!(targets(&visible.annotations), vec![(0x00, &TargetInfo::TypeParameter { index: 0 }, "LVisible;")]),
+                Attribute::RuntimeInvisibleTypeAnnotations(invisible) =>
+                    assert_eq!(targets(&invisible.annotations), vec![(0x10, &TargetInfo::Supertype { index: 0 }, "LHidden;")]),
+                _ => {}
+            }
+        }
+        let field = class.fields[0].attributes.iter().find_map(|attribute| match attribute {
+            Attribute::RuntimeVisibleTypeAnnotations(visible) => Some(&visible.annotations[0]),
+            _ => None
+        }).unwrap();
+        assert_eq!(field.target_info, TargetInfo::Empty);
+        assert_eq!(field.target_path, vec![TypePathEntry { kind: 3, type_argument_index: 0 }]);
+
+        let compare = class.methods.iter().find(|method| method.name.as_str() == "compareTo" && method.signature().is_some()).unwrap();
+        let code = compare.code().unwrap();
+        let mut found = vec![];
+        for attribute in &code.attributes {
+            match attribute {
+                CodeAttributes::RuntimeVisibleTypeAnnotations(visible) => found.extend(targets(&visible.annotations)),
+                CodeAttributes::RuntimeInvisibleTypeAnnotations(invisible) => found.extend(targets(&invisible.annotations)),
+                _ => {}
+            }
+        }
+        let local = TargetInfo::LocalVariable(vec![LocalVariableTarget { start_pc: 2, length: 11, index: 2 }]);
+        assert_eq!(found, vec![(0x47, &TargetInfo::TypeArgument { offset: 3, type_argument_index: 0 }, "LVisible;"),
+                               (0x42, &TargetInfo::Catch { exception_table_index: 0 }, "LVisible;"),
+                               (0x40, &local, "LHidden;")]);
+    }
+
+    #[test]
+    fn nest_attributes() {
+        let shape = load_class("Shape.class");
+        let members = shape.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::NestMembers(members) => Some(members.classes.iter().map(|class| class.0.as_str()).collect::<Vec<&str>>()),
+            _ => None
+        }).unwrap();
+        assert_eq!(members, vec!["Shape$Square", "Shape$Circle"]);
+        assert_eq!(shape.nest_host(), "Shape");
+        let circle = load_class("Shape$Circle.class");
+        assert!(circle.attributes.iter().any(|attribute| matches!(attribute, Attribute::NestHost(host) if host.host_class.0.as_str() == "Shape")));
+        assert_eq!(circle.nest_host(), "Shape");
+        assert_eq!(roundtrip_bytes(&read_resource("Shape$Circle.class")).unwrap(), read_resource("Shape$Circle.class"));
+    }
+
+    #[test]
+    fn module_attributes() {
+        let mut class = load_class("module-info.class");
+        let module = class.module().unwrap();
+        assert_eq!(module.name.0.as_str(), "org.example.hello");
+        assert!(module.access.is_empty());
+        assert_eq!(module.version, None);
+        assert_eq!(module.requires.len(), 1);
+        assert_eq!(module.requires[0].module.0.as_str(), "java.base");
+        assert_eq!(module.requires[0].access, RequiresAccess::Mandated);
+        let exports: Vec<(&str, usize)> = module.exports.iter().map(|exports| (exports.package.0.as_str(), exports.to.len())).collect();
+        assert_eq!(exports, vec![("org/example/hello", 0)]);
+        assert!(module.opens.is_empty() && module.uses.is_empty() && module.provides.is_empty());
+        assert!(load_class("HelloWorld.class").module().is_none());
+
+        class.attributes.push(Attribute::ModulePackages(ModulePackagesAttribute {
+            packages: vec![PackageInfo(Utf8Info::from("org/example/hello"))]
+        }));
+        class.attributes.push(Attribute::ModuleMainClass(ModuleMainClassAttribute {
+            main_class: ClassInfo(Utf8Info::from("org/example/hello/Main"))
+        }));
+        assert_eq!(reserialize(&class).attributes, class.attributes);
+    }
+
+    #[test]
+    fn local_variable_type_table() {
+        let class = load_class("GenericLocals.class");
+        let code = method_code(&class, "repeat");
+        let table = code.local_variable_type_table().unwrap();
+        let variables: Vec<(&str, &str, u16)> = table.variables.iter()
+            .map(|variable| (variable.name.as_str(), variable.signature.as_str(), variable.index))
+            .collect();
+        assert_eq!(variables, vec![("value", "TT;", 0), ("result", "Ljava/util/List<TT;>;", 2)]);
+        assert_eq!(table.variable_at(10, 2).map(|variable| variable.name.as_str()), Some("result"));
+        assert!(table.variable_at(4, 2).is_none());
+        assert_eq!(code.local_variable_table().unwrap().variable_at(4, 1).map(|variable| variable.name.as_str()), Some("count"));
+        assert!(method_code(&load_class("TryCatch.class"), "parse").local_variable_type_table().is_none());
+    }
+
+    #[test]
+    fn attribute_registry() {
+        let mut registry = AttributeRegistry::new();
+        registry.register("CustomFile", |cursor, pool| {
+            let mut index = [0; 2];
+            cursor.read_exact(&mut index)?;
+            match &pool[u16::from_be_bytes(index) as usize - 1] {
+                ConstPoolType::Utf8(file) => Ok(Box::new(file.to_string()) as CustomAttribute),
+                _ => Err(DeserializationError::Link)
+            }
+        });
+        registry.register("Broken", |_, _| Err(DeserializationError::Parsing("broken".to_string())));
+        let options = ParseOptions {
+            attribute_registry: Some(Rc::new(registry)),
+            ..ParseOptions::default()
+        };
+
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"SourceFile", b"CustomFile");
+        let class = deserializable_class_with_options(&mut &data[..], &options).unwrap();
+        match &class.attributes[0] {
+            Attribute::Custom { value, .. } => assert_eq!(value.downcast_ref::<String>().map(String::as_str), Some("HelloWorld.java")),
+            other => panic!("unexpected attribute {other:?}")
+        }
+
+        let mut data = read_resource("HelloWorld.class");
+        replace_bytes(&mut data, b"\x00\x0ASourceFile", b"\x00\x06Broken");
+        let error = deserializable_class_with_options(&mut &data[..], &options).unwrap_err();
+        assert!(matches!(error.into_inner(), DeserializationError::Parsing(message) if message == "broken"));
+    }
+
+    #[test]
+    fn lazy_attributes() {
+        let options = ParseOptions {
+            lazy_attributes: true,
+            ..ParseOptions::default()
+        };
+        let data = read_resource("Annotated.class");
+        let lazy = deserializable_class_with_options(&mut &data[..], &options).unwrap();
+        let eager = deserializable_class(&mut &data[..]).unwrap();
+        assert!(lazy.methods.iter().all(|method| method.code().is_none()));
+        assert!(lazy.source_file().is_some());
+        for (lazy_method, eager_method) in lazy.methods.iter().zip(&eager.methods) {
+            assert_eq!(lazy_method.name, eager_method.name);
+            assert_eq!(lazy_method.load_code(&lazy).unwrap().as_ref(), eager_method.code());
+            assert_eq!(lazy_method.decoded_attributes(&lazy).unwrap(), eager_method.attributes);
+        }
+        let annotations = lazy.attributes.iter().find_map(|attribute| match attribute {
+            Attribute::Unknown(unknown) if unknown.name.as_str() == "RuntimeVisibleAnnotations" => Some(unknown),
+            _ => None
+        }).unwrap();
+        assert!(matches!(decode_attribute(annotations, &lazy.const_pool).unwrap(), Attribute::RuntimeVisibleAnnotations(_)));
+        let mut written = Vec::new();
+        serialize_class(&lazy, &mut written).unwrap();
+        assert_eq!(written, data);
+
+        let mut padded = annotations.clone();
+        padded.data.push(0);
+        assert!(decode_attribute(&padded, &lazy.const_pool).is_err());
+    }
+
+    #[test]
+    fn attribute_placement() {
+        for name in ["Point.class", "module-info.class", "Annotated.class", "ParameterAnnotated.class", "TypeAnnotated.class",
+            "Lambda.class", "Shape.class"] {
+            assert_eq!(validate(&load_class(name)), Ok(()), "{name}");
+        }
+
+        let mut class = load_class("HelloWorld.class");
+        let code = class.methods[1].attributes.remove(0);
+        class.fields.push(ComponentInfo {
+            access: AccessSpecifier::Private.into(),
+            name: Utf8Info::from("counter"),
+            descriptor: Utf8Info::from("I"),
+            attributes: vec![code]
+        });
+        class.methods[0].attributes.push(Attribute::ConstantValue(ConstantValueAttribute { value: ConstValueType::Int(1) }));
+        class.attributes.push(Attribute::Synthetic(SyntheticAttribute {}));
+        class.attributes.push(Attribute::Unknown(UnknownAttribute {
+            name: Utf8Info::from("Exceptions"),
+            size: 0,
+            data: vec![]
+        }));
+        assert_eq!(validate(&class), Err(vec![
+            ValidationError::MisplacedAttribute { attribute: "Exceptions".to_string(), owner: "class `HelloWorld`".to_string() },
+            ValidationError::MisplacedAttribute { attribute: "Code".to_string(), owner: "field `counter`".to_string() },
+            ValidationError::MisplacedAttribute { attribute: "ConstantValue".to_string(), owner: "method `<init>()V`".to_string() }
+        ]));
+    }
+
+    #[test]
+    fn duplicate_attributes() {
+        let strict = ParseOptions {
+            reject_duplicate_attributes: true,
+            ..ParseOptions::default()
+        };
+        for name in ["HelloWorld.class", "TryCatch.class", "Annotated.class", "GenericLocals.class", "Point.class"] {
+            assert!(deserializable_class_with_options(&mut &read_resource(name)[..], &strict).is_ok(), "{name}");
+        }
+
+        let mut class = load_class("HelloWorld.class");
+        class.attributes.push(class.attributes[0].clone());
+        let mut data = Vec::new();
+        serialize_class(&class, &mut data).unwrap();
+        assert!(deserializable_class(&mut &data[..]).is_ok());
+        let error = deserializable_class_with_options(&mut &data[..], &strict).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::DuplicateAttribute { attribute, owner }
+            if attribute == "SourceFile" && owner == "class `HelloWorld`"));
+
+        let mut class = load_class("HelloWorld.class");
+        let code = class.methods[1].attributes[0].clone();
+        class.methods[1].attributes.push(code);
+        let mut data = Vec::new();
+        serialize_class(&class, &mut data).unwrap();
+        let lazy = ParseOptions {
+            lazy_attributes: true,
+            ..strict.clone()
+        };
+        for options in [&strict, &lazy] {
+            let error = deserializable_class_with_options(&mut &data[..], options).unwrap_err().into_inner();
+            assert_eq!(error.to_string(), "Duplicate `Code` attribute on method `main([Ljava/lang/String;)V`.");
+        }
+
+        let mut class = load_class("TryCatch.class");
+        let code = class.methods.iter_mut().find_map(|method| match method.attributes.first_mut() {
+            Some(Attribute::Code(code)) if code.stack_map_table().is_some() => Some(code),
+            _ => None
+        }).unwrap();
+        let table = code.attributes.iter().find(|attribute| matches!(attribute, CodeAttributes::StackMapTable(_))).unwrap().clone();
+        code.attributes.push(table);
+        let mut tables = Vec::new();
+        serialize_class(&class, &mut tables).unwrap();
+        assert!(deserializable_class(&mut &tables[..]).is_ok());
+        let error = deserializable_class_with_options(&mut &tables[..], &strict).unwrap_err().into_inner();
+        assert!(matches!(error, DeserializationError::DuplicateAttribute { attribute, owner }
+            if attribute == "StackMapTable" && owner.starts_with("Code of method `")));
+
+        struct Methods;
+        impl MethodVisitor for Methods {}
+        struct Everything(Methods);
+        impl ClassVisitor for Everything {
+            fn visit_method(&mut self, _access: BitFlags<MethodAccessSpecifier>, _name: &Utf8Info,
+                            _descriptor: &Utf8Info) -> Option<&mut dyn MethodVisitor> {
+                Some(&mut self.0)
+            }
+        }
+        assert!(visit_class(&mut &data[..], &mut Everything(Methods), &ParseOptions::default()).is_ok());
+        let error = visit_class(&mut &data[..], &mut Everything(Methods), &strict).unwrap_err().into_inner();
+        assert_eq!(error.to_string(), "Duplicate `Code` attribute on method `main([Ljava/lang/String;)V`.");
+    }
+
+    #[test]
+    fn descriptor_display() {
+        for descriptor in ["B", "C", "D", "F", "I", "J", "S", "Z", "Ljava/lang/String;", "[[I", "[Ljava/util/List;"] {
+            assert_eq!(parse_field_descriptor(descriptor).unwrap().to_string(), descriptor);
+        }
+        for descriptor in ["()V", "(Ljava/lang/String;I)V", "([Ljava/lang/String;)V", "(JD[[Z)Ljava/lang/Object;"] {
+            assert_eq!(parse_method_descriptor(descriptor).unwrap().to_string(), descriptor);
+        }
+        let descriptor = MethodDescriptor {
+            parameters: vec![FieldType::Array(Box::new(FieldType::Object("java/lang/String".to_string())))],
+            return_type: None
+        };
+        assert_eq!(descriptor.to_string(), "([Ljava/lang/String;)V");
+        for method in load_class("TryCatch.class").methods {
+            assert_eq!(parse_method_descriptor(&method.descriptor).unwrap().to_string(), method.descriptor.as_str());
+        }
+    }
+
+    #[test]
+    fn descriptor_slots() {
+        for (descriptor, count, slots, kind) in [("()V", 0, 0, ReturnKind::Void), ("(Ljava/lang/String;I)V", 2, 2, ReturnKind::Void),
+            ("(JD)J", 2, 4, ReturnKind::Long), ("([J[D)Z", 2, 2, ReturnKind::Int), ("(BCSZ)C", 4, 4, ReturnKind::Int),
+            ("(F)F", 1, 1, ReturnKind::Float), ("(IJ)D", 2, 3, ReturnKind::Double), ("()[I", 0, 0, ReturnKind::Reference),
+            ("(J)Ljava/lang/Object;", 1, 2, ReturnKind::Reference)] {
+            let parsed = parse_method_descriptor(descriptor).unwrap();
+            assert_eq!(parsed.parameter_count(), count, "{descriptor}");
+            assert_eq!(parsed.argument_slot_size(), slots, "{descriptor}");
+            assert_eq!(parsed.return_kind(), kind, "{descriptor}");
+        }
+        assert_eq!(FieldType::Long.slot_size(), 2);
+        assert_eq!(FieldType::Array(Box::new(FieldType::Double)).slot_size(), 1);
+    }
+
+    #[test]
+    fn recompute_stack_map() {
+        let recompute = |class: &Class, name: &str| {
+            let method = class.methods.iter().find(|method| method.name.as_str() == name).unwrap();
+            method.code().unwrap().recompute_stack_map(class, method).unwrap().frames
+        };
+        for (name, methods) in [("StackFrames.class", vec!["<init>", "uninitialized", "full", "caught"]),
+            ("TryCatch.class", vec!["parse"]), ("Bytecode.class", vec!["dense", "sparse", "mixed", "loop"])] {
+            let class = load_class(name);
+            for method in methods {
+                let expected = method_code(&class, method).stack_map_table().map_or(vec![], |table| table.frames.clone());
+                assert_eq!(recompute(&class, method), expected, "{name} {method}");
+            }
+        }
+
+        // javac lists a local with its declared type, here List rather than the ArrayList stored into it.
+        let class = load_class("GenericLocals.class");
+        let expected = method_code(&class, "repeat").stack_map_table().unwrap().clone();
+        let frames = recompute(&class, "repeat");
+        assert_eq!(class::attributes::StackMapTableAttribute { frames: frames.clone() }.offsets(), expected.offsets());
+        let array_list = VerificationType::Object(ClassInfo(Utf8Info::from("java/util/ArrayList")));
+        assert!(matches!(&frames[0], StackMapFrame::Append { locals, .. } if locals[0] == array_list));
+        assert_eq!(frames[1], expected.frames[1]);
+
+        let class = load_class("StackFrames.class");
+        let method = class.methods.iter().find(|method| method.name.as_str() == "full").unwrap();
+        let mut code = method.code().unwrap().clone();
+        code.code.truncate(4);
+        assert!(matches!(code.recompute_stack_map(&class, method),
+            Err(DeserializationError::Parsing(message)) if message == "Branch to invalid pc 25"));
+    }
+
+    #[test]
+    fn polymorphic_method_lookup() {
+        let class = load_class("HelloWorld.class");
+        let descriptor = "([Ljava/lang/String;)V";
+        assert_eq!(class.resolve_method("main", descriptor), class.find_method("main", descriptor));
+        assert!(class.resolve_method("main", "(I)V").is_none());
+
+        let mut handle = class.clone_with_renamed_class("java/lang/invoke/MethodHandle");
+        let mut invoke_exact = handle.find_method("main", descriptor).unwrap().clone();
+        invoke_exact.name = Utf8Info::from("invokeExact");
+        invoke_exact.descriptor = Utf8Info::from("([Ljava/lang/Object;)Ljava/lang/Object;");
+        invoke_exact.access = MethodAccessSpecifier::Public | MethodAccessSpecifier::Final | MethodAccessSpecifier::Native
+            | MethodAccessSpecifier::Varargs;
+        invoke_exact.attributes.clear();
+        handle.methods.push(invoke_exact);
+        let resolved = handle.resolve_method("invokeExact", "(ILjava/lang/String;)J").unwrap();
+        assert_eq!(resolved.descriptor.as_str(), "([Ljava/lang/Object;)Ljava/lang/Object;");
+        assert!(handle.find_method("invokeExact", "(ILjava/lang/String;)J").is_none());
+        // Ordinary methods of MethodHandle still need an exact descriptor.
+        assert!(handle.resolve_method("main", "(I)V").is_none());
+    }
+
+    #[test]
+    fn access_flag_rules() {
+        for name in ["Annotated.class", "Bytecode.class", "Color.class", "Constants.class", "Generics.class", "Info.class",
+            "MethodFlags.class", "ParameterAnnotated$Marker.class", "Shape.class", "Shape$Circle.class", "StackFrames.class",
+            "Visible.class", "module-info.class"] {
+            assert_eq!(validate(&load_class(name)), Ok(()), "{name}");
+        }
+
+        let mut class = load_class("HelloWorld.class");
+        class.access |= ClassAccess::Final | ClassAccess::Abstract;
+        class.methods[0].access |= MethodAccessSpecifier::Static;
+        class.methods[1].access |= MethodAccessSpecifier::Private;
+        class.fields.push(ComponentInfo {
+            access: AccessSpecifier::Final | AccessSpecifier::Volatile,
+            name: Utf8Info::from("counter"),
+            descriptor: Utf8Info::from("I"),
+            attributes: vec![]
+        });
+        assert_eq!(validate(&class), Err(vec![
+            ValidationError::IllegalAccessFlags { owner: "class `HelloWorld`".to_string(),
+                reason: "a class cannot be both final and abstract" },
+            ValidationError::IllegalAccessFlags { owner: "field `counter`".to_string(),
+                reason: "a field cannot be both final and volatile" },
+            ValidationError::IllegalAccessFlags { owner: "method `<init>()V`".to_string(),
+                reason: "an instance initializer may only carry visibility, varargs, strict and synthetic" },
+            ValidationError::IllegalAccessFlags { owner: "method `main([Ljava/lang/String;)V`".to_string(),
+                reason: "at most one of public, private and protected is allowed" }
+        ]));
+
+        let mut class = load_class("Shape.class");
+        assert!(class.access.contains(ClassAccess::Interface));
+        class.access.remove(ClassAccess::Abstract);
+        let error = validate(&class).unwrap_err();
+        assert_eq!(error[0].to_string(), "Access flags of class `Shape` are illegal: an interface must be abstract.");
+
+        let mut class = load_class("HelloWorld.class");
+        class.access |= ClassAccess::Abstract;
+        class.methods[1].access = MethodAccessSpecifier::Public | MethodAccessSpecifier::Abstract | MethodAccessSpecifier::Strict;
+        class.methods[1].attributes.clear();
+        // ACC_STRICT is not yet defined in a Java 1.1 class and no longer in a Java 17 one.
+        for (major, legal) in [(45, true), (46, false), (60, false), (61, true)] {
+            class.version.major = major;
+            assert_eq!(validate(&class).is_ok(), legal, "{major}");
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn class_is_thread_safe() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        let class = load_class("Lambda.class");
+        assert_send_sync(&class);
+        let shared = std::sync::Arc::new(class);
+        let handle = {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.methods.len())
+        };
+        assert_eq!(handle.join().unwrap(), shared.methods.len());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parse_in_parallel() {
+        use class_parser::deserialization::parse_all;
+        let data = read_resource("HelloWorld.class");
+        let mut inputs = vec![data.clone(); 100];
+        inputs[50].truncate(10);
+        let classes = parse_all(inputs);
+        assert_eq!(classes.len(), 100);
+        assert!(matches!(&classes[50], Err(DeserializationError::AtOffset { .. })));
+        let expected = load_class("HelloWorld.class");
+        assert!(classes.iter().enumerate().filter(|(index, _)| *index != 50).all(|(_, class)| class.as_ref().unwrap() == &expected));
+    }
+
+    #[test]
+    fn serde_round_trip_of_every_attribute() {
+        for name in ["Annotated.class", "GenericLocals.class", "Lambda.class", "module-info.class", "ParameterAnnotated.class",
+            "Point.class", "Shape.class", "StackFrames.class", "TryCatch.class", "TypeAnnotated.class"] {
+            let class = load_class(name);
+            let restored: Class = serde_json::from_str(&serde_json::to_string(&class).unwrap()).unwrap();
+            assert_eq!(restored, class, "{name}");
+        }
+    }
+
+    #[test]
+    fn build_class_with_fields() {
+        let constant = FieldBuilder::new("LIMIT", "J")
+            .access(AccessSpecifier::Public | AccessSpecifier::Static | AccessSpecifier::Final)
+            .constant_value(ConstValueType::Long(1 << 40))
+            .build();
+        assert_eq!(constant.attributes, vec![Attribute::ConstantValue(ConstantValueAttribute { value: ConstValueType::Long(1 << 40) })]);
+        let class = ClassBuilder::new("Settings")
+            .field(constant)
+            .field(FieldBuilder::new("name", "Ljava/lang/String;").access(AccessSpecifier::Private.into()).build())
+            .field(FieldBuilder::new("GREETING", "Ljava/lang/String;")
+                .access(AccessSpecifier::Static | AccessSpecifier::Final)
+                .constant_value(ConstValueType::String(Utf8Info::from("hi")))
+                .build())
+            .build()
+            .unwrap();
+        assert_eq!(validate(&class), Ok(()));
+        let parsed = reserialize(&class);
+        assert_eq!(parsed, class);
+        assert_eq!(parsed.find_field("name").unwrap().access, AccessSpecifier::Private);
+        assert!(parsed.find_field("name").unwrap().attributes.is_empty());
+        assert_eq!(parsed.find_field("GREETING").unwrap().attributes,
+            vec![Attribute::ConstantValue(ConstantValueAttribute { value: ConstValueType::String(Utf8Info::from("hi")) })]);
+    }
+
+    #[derive(Default)]
+    struct CodeCollector {
+        attributes: Vec<Attribute>,
+        ended: bool
+    }
+
+    impl MethodVisitor for CodeCollector {
+        fn visit_attribute(&mut self, attribute: Attribute) {
+            self.attributes.push(attribute);
+        }
+
+        fn visit_end(&mut self) {
+            self.ended = true;
+        }
+    }
+
+    #[derive(Default)]
+    struct EventRecorder {
+        events: Vec<String>,
+        parse: CodeCollector
+    }
+
+    impl ClassVisitor for EventRecorder {
+        fn visit_version(&mut self, version: &ClassVersion) {
+            self.events.push(format!("version {}", version.major));
+        }
+
+        fn visit_header(&mut self, _: BitFlags<ClassAccess>, this_class: &ClassInfo, super_class: Option<&ClassInfo>,
+                        interfaces: &[ClassInfo]) {
+            self.events.push(format!("class {} extends {} ({} interfaces)", this_class.0, super_class.unwrap().0, interfaces.len()));
+        }
+
+        fn visit_field(&mut self, field: FieldInfo) {
+            self.events.push(format!("field {}", field.name));
+        }
+
+        fn visit_method(&mut self, _: BitFlags<MethodAccessSpecifier>, name: &Utf8Info,
+                        descriptor: &Utf8Info) -> Option<&mut dyn MethodVisitor> {
+            self.events.push(format!("method {name}{descriptor}"));
+            (name.as_str() == "parse").then_some(&mut self.parse as &mut dyn MethodVisitor)
+        }
+
+        fn visit_attribute(&mut self, attribute: Attribute) {
+            self.events.push(format!("attribute {}", attribute.name()));
+        }
+
+        fn visit_end(&mut self) {
+            self.events.push("end".to_string());
+        }
+    }
+
+    #[test]
+    fn class_visitor() {
+        let class = load_class("TryCatch.class");
+        let mut recorder = EventRecorder::default();
+        visit_class(&mut &read_resource("TryCatch.class")[..], &mut recorder, &ParseOptions::default()).unwrap();
+        let mut expected = vec!["version 55".to_string(), "class TryCatch extends java/lang/Object (0 interfaces)".to_string()];
+        expected.extend(class.fields.iter().map(|field| format!("field {}", field.name)));
+        expected.extend(class.methods.iter().map(|method| format!("method {}{}", method.name, method.descriptor)));
+        expected.extend(class.attributes.iter().map(|attribute| format!("attribute {}", attribute.name())));
+        expected.push("end".to_string());
+        assert_eq!(recorder.events, expected);
+        assert!(recorder.parse.ended);
+        assert_eq!(recorder.parse.attributes, class.find_method("parse", "(Ljava/lang/String;)I").unwrap().attributes);
+
+        let mut data = read_resource("TryCatch.class");
+        data.truncate(data.len() - 4);
+        let error = visit_class(&mut &data[..], &mut EventRecorder::default(), &ParseOptions::default()).unwrap_err();
+        assert!(matches!(error.into_inner(), DeserializationError::CannotRead(_)));
+    }
+
+    #[test]
+    fn class_diff() {
+        let old = load_class("HelloWorld.class");
+        assert!(diff(&old, &old).is_empty());
+        assert!(diff(&old, &reserialize(&old)).is_empty());
+
+        let mut new = old.clone();
+        new.version.major = 61;
+        new.interfaces.push(ClassInfo(Utf8Info::from("java/io/Serializable")));
+        new.methods.remove(0);
+        new.methods[0].access |= MethodAccessSpecifier::Final;
+        new.methods[0].attributes.push(Attribute::Deprecated(DeprecatedAttribute {}));
+        new.fields.push(FieldBuilder::new("count", "I").build());
+        new.attributes.clear();
+        assert_eq!(diff(&old, &new), vec![
+            ClassChange::Version { old: old.version.clone(), new: new.version.clone() },
+            ClassChange::InterfaceAdded("java/io/Serializable".to_string()),
+            ClassChange::FieldAdded("count:I".to_string()),
+            ClassChange::MethodRemoved("<init>()V".to_string()),
+            ClassChange::MethodChanged {
+                method: "main([Ljava/lang/String;)V".to_string(),
+                changes: vec![
+                    MemberChange::Access { old: old.methods[1].access, new: new.methods[0].access },
+                    MemberChange::Attribute(AttributeChange::Added("Deprecated".to_string()))
+                ]
+            },
+            ClassChange::Attribute(AttributeChange::Removed("SourceFile".to_string()))
+        ]);
+
+        let reversed = diff(&new, &old);
+        assert!(reversed.contains(&ClassChange::InterfaceRemoved("java/io/Serializable".to_string())));
+        assert!(reversed.contains(&ClassChange::MethodAdded("<init>()V".to_string())));
+        assert!(reversed.contains(&ClassChange::FieldRemoved("count:I".to_string())));
+    }
+
+    #[test]
+    fn class_names() {
+        let string = ClassInfo::from_binary_name("java.lang.String");
+        assert_eq!(string.internal_name(), "java/lang/String");
+        assert_eq!(string.binary_name(), "java.lang.String");
+        assert_eq!(string.descriptor(), "Ljava/lang/String;");
+        assert_eq!(string.package_name(), "java/lang");
+        assert_eq!(string.simple_name(), "String");
+        assert!(!string.is_array());
+
+        let entry = ClassInfo(Utf8Info::from("java/util/Map$Entry"));
+        assert_eq!(entry.binary_name(), "java.util.Map$Entry");
+        assert_eq!(entry.simple_name(), "Entry");
+        assert_eq!(entry.package_name(), "java/util");
+
+        let matrix = ClassInfo(Utf8Info::from("[[Ljava/lang/String;"));
+        assert!(matrix.is_array());
+        assert_eq!(matrix.binary_name(), "[[Ljava.lang.String;");
+        assert_eq!(matrix.descriptor(), "[[Ljava/lang/String;");
+        assert_eq!(matrix.element_name(), "java/lang/String");
+        assert_eq!(matrix.package_name(), "java/lang");
+        assert_eq!(matrix.simple_name(), "String[][]");
+
+        let ints = ClassInfo(Utf8Info::from("[I"));
+        assert_eq!((ints.element_name(), ints.package_name(), ints.simple_name()), ("I", "", "int[]".to_string()));
+        let hello = load_class("HelloWorld.class").this_class;
+        assert_eq!((hello.package_name(), hello.simple_name()), ("", "HelloWorld".to_string()));
+    }
+
+    #[test]
+    fn class_stats() {
+        let class = load_class("HelloWorld.class");
+        let stats = class.stats().unwrap();
+        assert_eq!(stats.size, read_resource("HelloWorld.class").len());
+        assert_eq!(stats.const_pool.values().sum::<usize>(), class.const_pool.len());
+        assert_eq!(stats.const_pool["Utf8"], 17);
+        assert_eq!(stats.const_pool["Methodref"], 2);
+        assert_eq!(stats.method_code, vec![("<init>()V".to_string(), 5), ("main([Ljava/lang/String;)V".to_string(), 9)]);
+        assert_eq!(stats.attributes["SourceFile"], AttributeStats { count: 1, bytes: 8 });
+        assert_eq!(stats.attributes["Code"].count, 2);
+        assert_eq!(stats.attributes["LineNumberTable"].count, 2);
+        assert_eq!(stats.attributes.len(), 3);
+
+        let stats = load_class("Constants.class").stats().unwrap();
+        assert_eq!(stats.attributes["ConstantValue"], AttributeStats { count: 4, bytes: 32 });
+        assert_eq!(stats.const_pool["Integer"], 2);
+    }
+
+    #[test]
+    fn resolve_instruction_operands() {
+        let class = load_class("HelloWorld.class");
+        let resolved = decode(&method_code(&class, "main").code).unwrap().into_iter()
+            .map(|(_, instruction)| instruction.constant(&class).map(ToString::to_string))
+            .collect::<Vec<Option<String>>>();
+        assert_eq!(resolved, vec![
+            Some("Fieldref java/lang/System.out:Ljava/io/PrintStream;".to_string()),
+            Some("String \"You entered lol\"".to_string()),
+            Some("Methodref java/io/PrintStream.println:(Ljava/lang/String;)V".to_string()),
+            None
+        ]);
+        assert_eq!(Instruction::Invokeinterface { index: 7, count: 2 }.pool_index(), Some(7));
+        assert_eq!(Instruction::Iinc { index: 7, constant: 1 }.pool_index(), None);
+        assert!(Instruction::Getstatic(0).constant(&class).is_none());
+
+        // A wide iinc keeps its 16-bit local index and constant.
+        let wide = decode(&[0xC4, 0x84, 0x01, 0x00, 0xFF, 0xFE]).unwrap();
+        assert_eq!(wide, vec![(0, Instruction::Iinc { index: 256, constant: -2 })]);
+    }
+}
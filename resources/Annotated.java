@@ -0,0 +1,24 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Deprecated(since = "1")
+@Info(level = 3, ratio = 1.5, tags = {"a", "b"}, kind = ElementType.TYPE, type = String.class,
+      nested = @Retention(RetentionPolicy.CLASS), flag = true, letter = 'x')
+public class Annotated {
+    @Deprecated
+    void old() {
+    }
+}
+
+@Retention(RetentionPolicy.CLASS)
+@interface Info {
+    int level();
+    double ratio();
+    String[] tags();
+    ElementType kind();
+    Class<?> type();
+    Retention nested();
+    boolean flag();
+    char letter();
+}
@@ -0,0 +1,39 @@
+public class Bytecode {
+    static int arithmetic(int a, int b) {
+        return a * b + 3;
+    }
+
+    static int dense(int value) {
+        switch (value) {
+            case 1: return 10;
+            case 2: return 20;
+            case 3: return 30;
+            default: return 0;
+        }
+    }
+
+    static int sparse(int value) {
+        switch (value) {
+            case 1: return 10;
+            case 1000: return 20;
+            case 100000: return 30;
+            default: return 0;
+        }
+    }
+
+    static int loop(int count) {
+        int sum = 0;
+        for (int i = 0; i < count; i++) {
+            sum += 1000;
+        }
+        return sum;
+    }
+
+    static double mixed(long a, double b) {
+        return a + b;
+    }
+
+    static long arithmetic(long a, long b) {
+        return a * b + 3;
+    }
+}
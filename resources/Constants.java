@@ -0,0 +1,6 @@
+public class Constants {
+    public static final int ANSWER = 42;
+    public static final float RATIO = 0.5f;
+    public static final char LETTER = 'J';
+    public static final String GREETING = "Hello";
+}
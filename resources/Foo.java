@@ -0,0 +1,4 @@
+public @interface Foo {
+    int bar() default 7;
+    String name();
+}
@@ -0,0 +1,12 @@
+import java.util.ArrayList;
+import java.util.List;
+
+public class GenericLocals {
+    public static <T> List<T> repeat(T value, int count) {
+        List<T> result = new ArrayList<>();
+        for (int i = 0; i < count; i++) {
+            result.add(value);
+        }
+        return result;
+    }
+}
@@ -0,0 +1,16 @@
+import java.util.ArrayList;
+import java.util.List;
+
+public class Generics<E extends Comparable<E>> {
+    private List<E> items = new ArrayList<>();
+
+    public <T> List<T> foo(T value) {
+        List<T> result = new ArrayList<>();
+        result.add(value);
+        return result;
+    }
+
+    public int size() {
+        return items.size();
+    }
+}
@@ -0,0 +1,10 @@
+import java.util.function.Supplier;
+
+public class Lambda {
+    public static void main(String[] args) {
+        Runnable greet = () -> System.out.println("Hello " + args.length);
+        Supplier<String> name = String::new;
+        greet.run();
+        System.out.println(name.get());
+    }
+}
@@ -0,0 +1,16 @@
+public abstract class MethodFlags {
+    abstract void run();
+
+    synchronized void lock() {
+    }
+
+    native void call();
+
+    static int sum(int... values) {
+        return values.length;
+    }
+
+    strictfp double divide(double a, double b) {
+        return a / b;
+    }
+}
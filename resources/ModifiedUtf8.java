@@ -0,0 +1,6 @@
+public class ModifiedUtf8 {
+    static int zero_field = 1;
+    static final String EMOJI = "smile \uD83D\uDE00";
+    static final String NUL = "a\u0000b";
+    static final String LONE = "\uD800";
+}
@@ -0,0 +1,11 @@
+public class Node<T> {
+    Node<T> next;
+
+    Node<T> last() {
+        Node<T> node = this;
+        while (node.next != null) {
+            node = node.next;
+        }
+        return node;
+    }
+}
@@ -0,0 +1,10 @@
+public class ParameterAnnotated {
+    @interface Marker {
+    }
+
+    public void handle(@Deprecated String first, int second, @Marker @Deprecated long third) {
+    }
+
+    public void plain(String value) {
+    }
+}
@@ -0,0 +1,2 @@
+public record Point(int x, int y) {
+}
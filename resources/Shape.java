@@ -0,0 +1,7 @@
+public sealed interface Shape permits Shape.Circle, Shape.Square {
+    final class Circle implements Shape {
+    }
+
+    final class Square implements Shape {
+    }
+}
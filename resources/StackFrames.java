@@ -0,0 +1,24 @@
+public class StackFrames {
+    static StringBuilder uninitialized(boolean flag) {
+        return new StringBuilder(flag ? "yes" : "no");
+    }
+
+    static int full(int value) {
+        if (value > 0) {
+            long a = value;
+            double b = a;
+            String c = "c";
+            value += c.length() + (int) b;
+        }
+        String s = String.valueOf(value);
+        return s.length();
+    }
+
+    static Object caught(Object value) {
+        try {
+            return value.toString();
+        } catch (RuntimeException e) {
+            return e;
+        }
+    }
+}
@@ -0,0 +1,13 @@
+public class TryCatch {
+    static int parse(String value) {
+        int result = 0;
+        try {
+            result = Integer.parseInt(value);
+        } catch (NumberFormatException e) {
+            result = -1;
+        } finally {
+            result++;
+        }
+        return result;
+    }
+}
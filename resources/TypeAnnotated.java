@@ -0,0 +1,27 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+import java.util.List;
+
+@Retention(RetentionPolicy.RUNTIME)
+@Target(ElementType.TYPE_USE)
+@interface Visible {
+}
+
+@Target(ElementType.TYPE_USE)
+@interface Hidden {
+}
+
+public class TypeAnnotated<@Visible T> implements @Hidden Comparable<TypeAnnotated<T>> {
+    private List<@Visible String> names;
+
+    public int compareTo(TypeAnnotated<T> other) {
+        @Hidden Object local = other;
+        try {
+            return ((@Visible String) local).length();
+        } catch (@Visible RuntimeException exception) {
+            return 0;
+        }
+    }
+}
@@ -0,0 +1,9 @@
+public class WideConstants {
+    public static final long BIG = 1234567890123L;
+    public static final double RATE = 2.5;
+    public static final int AFTER = 7;
+
+    public static long twice(long value) {
+        return value * 2 + BIG;
+    }
+}
@@ -0,0 +1,3 @@
+module org.example.hello {
+    exports org.example.hello;
+}